@@ -8,27 +8,41 @@ use std::{
     fs::{self, File},
     io::{self, BufReader, Cursor, Read, Write},
     num::NonZeroU32,
+    path::{Path, PathBuf},
 };
 
 use anyhow::Context;
-use clap::Parser;
-#[cfg(feature = "decode-from-xbm")]
-use image::DynamicImage;
-use image::{ImageFormat, imageops};
+use clap::{Parser, ValueEnum};
+use image::{DynamicImage, ImageFormat, imageops};
 #[cfg(feature = "optimize-output-png")]
 use oxipng::{Deflaters, Options};
-use qrcode2::{QrCode, bits::Bits};
+use qrcode2::{EcLevel, QrCode, Version, bits::Bits, types::QrError};
 use rqrr::PreparedImage;
 #[cfg(feature = "decode-from-xbm")]
 use xbm::Decoder;
 
-#[cfg(any(feature = "decode-from-svg", feature = "decode-from-xbm"))]
+#[cfg(any(
+    feature = "decode-from-pdf",
+    feature = "decode-from-svg",
+    feature = "decode-from-xbm"
+))]
 use crate::cli::InputFormat;
 use crate::{
-    cli::{Command, Opt, OutputFormat, Variant},
-    decode, encode,
+    capacity,
+    cli::{
+        CapacityFormat, Command, Decode, DecodeFormat, Ecc, Eci, Encode, Opt, OutputEncoding,
+        OutputFormat, ParseFormat, Payload, Variant,
+    },
+    decode::{self, DecodedBytes, Geometry, Record, Subimage},
+    eci,
+    encode::{self, Symbol},
+    gs1,
     input::Input,
-    metadata::Extractor,
+    mask,
+    metadata::{Extractor, Fnc1},
+    parse,
+    payload::{self, epc, qr_bill},
+    segment::{self, Segment},
 };
 
 const MAX_DATA_SIZE: u64 = 7089;
@@ -39,220 +53,728 @@ pub fn run() -> anyhow::Result<()> {
     let opt = Opt::parse().validate()?;
 
     match opt.command {
-        Command::Encode(arg) => {
-            let input = if let Some(string) = arg.input {
-                Input::String(Cursor::new(string))
-            } else if let Some(ref path) = arg.read_from {
-                let f = File::open(path)
-                    .with_context(|| format!("could not open {}", path.display()))?;
-                Input::File(f)
-            } else {
-                Input::Stdin(io::stdin())
-            };
-            let reader = BufReader::new(input);
+        Command::Encode(mut arg) => {
+            let segments = arg
+                .segments
+                .as_ref()
+                .map(|path| {
+                    let spec = if path.as_os_str() == "-" {
+                        io::read_to_string(io::stdin())
+                            .context("could not read data from standard input")?
+                    } else {
+                        fs::read_to_string(path).with_context(|| {
+                            format!("could not read data from {}", path.display())
+                        })?
+                    };
+                    segment::parse(&spec).context("could not parse the segment specification")
+                })
+                .transpose()?;
             let mut buf = Vec::new();
-            reader
-                .take(MAX_DATA_SIZE + 1)
-                .read_to_end(&mut buf)
-                .context("could not read data")?;
-
-            let variant = arg.variant;
-            let level = arg.error_correction_level.into();
-            let code = if let Some(version) = arg.symbol_version {
-                let v =
-                    encode::set_version(&version, &variant).context("could not set the version")?;
-                let mut bits = Bits::new(v);
-                if let Some(mode) = arg.mode {
-                    encode::push_data_for_selected_mode(&mut bits, buf, &mode)
-                } else {
-                    bits.push_optimal_data(&buf)
-                }
-                .and_then(|()| bits.push_terminator(level))
-                .and_then(|()| QrCode::with_bits(bits, level))
-            } else {
-                match variant {
-                    Variant::Normal => QrCode::with_error_correction_level(&buf, level),
-                    Variant::Micro => QrCode::micro_with_error_correction_level(&buf, level),
-                    Variant::Rmqr => QrCode::rect_micro_with_error_correction_level(&buf, level),
+            if arg.symbol_version.is_none() && arg.max_version.is_none() {
+                match arg.payload {
+                    Some(Payload::Epc(_)) => arg.max_version = Some(vec![epc::MAX_VERSION]),
+                    Some(Payload::QrBill(_)) => {
+                        arg.max_version = Some(vec![qr_bill::MAX_VERSION]);
+                    }
+                    _ => {}
                 }
             }
-            .context("could not construct a QR code")?;
-
-            if arg.verbose {
-                let metadata = code.metadata();
-                eprintln!("Version: {}", metadata.symbol_version());
-                eprintln!("Level: {:?}", metadata.error_correction_level());
+            let version = arg
+                .symbol_version
+                .as_ref()
+                .map(|version| encode::set_version(version, &arg.variant))
+                .transpose()
+                .context("could not set the version")?;
+            let range = (arg.min_version.is_some() || arg.max_version.is_some())
+                .then(|| {
+                    encode::versions_within(
+                        &arg.variant,
+                        arg.min_version.as_deref(),
+                        arg.max_version.as_deref(),
+                    )
+                })
+                .transpose()
+                .context("could not set the version range")?;
+            if let Some(ref payload) = arg.payload {
+                let level = arg.error_correction_level.into();
+                let versions = version.map_or_else(
+                    || {
+                        range
+                            .clone()
+                            .unwrap_or_else(|| encode::versions(&arg.variant))
+                    },
+                    |version| vec![version],
+                );
+                let target = payload::Target {
+                    versions: &versions,
+                    level,
+                    mode: arg.mode.as_ref(),
+                    eci: arg.eci,
+                };
+                buf = payload::build(payload, target)?.into_bytes();
+            } else if segments.is_none() {
+                let input = if let Some(string) = arg.input.take() {
+                    Input::String(Cursor::new(string))
+                } else if let Some(ref path) = arg.read_from {
+                    let f = File::open(path)
+                        .with_context(|| format!("could not open {}", path.display()))?;
+                    Input::File(f)
+                } else {
+                    Input::Stdin(io::stdin())
+                };
+                let reader = BufReader::new(input);
+                let max_data_size = if arg.structured_append {
+                    MAX_DATA_SIZE * u64::try_from(encode::MAX_STRUCTURED_APPEND_SYMBOLS)?
+                } else {
+                    MAX_DATA_SIZE
+                };
+                reader
+                    .take(max_data_size + 1)
+                    .read_to_end(&mut buf)
+                    .context("could not read data")?;
             }
 
-            let margin = arg.margin;
-            let module_size = arg.size.map(NonZeroU32::get);
-            let is_invert = matches!(
-                arg.output_format,
-                OutputFormat::AsciiInvert | OutputFormat::UnicodeInvert
-            );
-            let output = match arg.output_format {
-                OutputFormat::Png => {
-                    let image = encode::to_image(
-                        &code,
-                        margin,
-                        &(arg.foreground, arg.background),
-                        module_size,
-                    );
-                    let mut buf = Vec::new();
-                    image
-                        .write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)
-                        .context("could not write the image to the buffer")?;
-
-                    #[cfg(feature = "optimize-output-png")]
-                    if let Some(level) = arg.optimize_png {
-                        let mut optimize_opt = Options::from_preset(level.into());
-                        if let Some(iterations) = arg.zopfli {
-                            optimize_opt.deflate = Deflaters::Zopfli { iterations };
+            let variant = &arg.variant;
+            let level = arg.error_correction_level.into();
+            let mode = arg.mode.as_ref();
+            let eci_for = |variant: &Variant| match arg.eci {
+                Some(Eci::Designator(designator)) => Some(designator),
+                Some(Eci::None) => None,
+                None if segments.is_some() || arg.gs1 => None,
+                None => encode::auto_eci(&buf, variant, mode),
+            };
+            let gs1 = arg
+                .gs1
+                .then(|| {
+                    let element_string = std::str::from_utf8(&buf)
+                        .context("GS1 element string is not valid UTF-8")?;
+                    gs1::parse(element_string).context("could not parse the GS1 element string")
+                })
+                .transpose()?;
+            #[allow(clippy::option_if_let_else)]
+            let encode_as = |variant: &Variant, version: Option<Version>, level: EcLevel| {
+                if let Some(ref segments) = segments {
+                    encode::with_segments(segments, version, variant, level)
+                } else if let Some(ref data) = gs1 {
+                    encode::with_gs1(data, version, variant, level)
+                } else if let Some(designator) = eci_for(variant) {
+                    encode::with_eci(&buf, designator, version, variant, level, mode)
+                } else if let Some(version) = version {
+                    let mut bits = Bits::new(version);
+                    if let Some(mode) = mode {
+                        encode::push_data_for_selected_mode(&mut bits, &buf, mode)
+                    } else {
+                        bits.push_optimal_data(&buf)
+                    }
+                    .and_then(|()| bits.push_terminator(level))
+                    .and_then(|()| QrCode::with_bits(bits, level))
+                } else {
+                    match variant {
+                        Variant::Normal => QrCode::with_error_correction_level(&buf, level),
+                        Variant::Micro => QrCode::micro_with_error_correction_level(&buf, level),
+                        Variant::Rmqr => {
+                            QrCode::rect_micro_with_error_correction_level(&buf, level)
                         }
-                        buf = oxipng::optimize_from_memory(&buf, &optimize_opt)
-                            .context("could not optimize the image")?;
+                        Variant::Auto => unreachable!("the type of QR code should be determined"),
                     }
-                    buf
                 }
-                OutputFormat::Svg => encode::to_svg(
-                    &code,
-                    margin,
-                    &(arg.foreground, arg.background),
-                    module_size,
-                )
-                .into(),
-                OutputFormat::Eps => encode::to_eps(
-                    &code,
-                    margin,
-                    &(arg.foreground, arg.background),
-                    module_size,
-                )
-                .into(),
-                OutputFormat::Pic => encode::to_pic(&code, margin, module_size).into(),
-                #[cfg(feature = "output-as-ansi")]
-                OutputFormat::Ansi => encode::to_ansi(
-                    &code,
-                    margin,
-                    &(arg.foreground, arg.background),
-                    module_size,
-                )
-                .into(),
-                #[cfg(feature = "output-as-ansi")]
-                OutputFormat::Ansi256 => encode::to_ansi_256(
-                    &code,
-                    margin,
-                    &(arg.foreground, arg.background),
-                    module_size,
+            };
+            let code = if *variant == Variant::Auto {
+                // Micro QR code cannot contain the ECI designator which would be
+                // inserted automatically into the other types, so the character set of
+                // the data would be lost.
+                let needs_eci =
+                    eci_for(&Variant::Normal).is_some() && eci_for(&Variant::Micro).is_none();
+                encode::smallest(
+                    |version| {
+                        if needs_eci && version.is_micro() {
+                            return Err(QrError::UnsupportedCharacterSet);
+                        }
+                        encode_as(&encode::variant_of(version), Some(version), level)
+                    },
+                    arg.prefer,
+                    arg.margin,
                 )
-                .into(),
-                #[cfg(feature = "output-as-ansi")]
-                OutputFormat::AnsiTrueColor => encode::to_ansi_true_color(
-                    &code,
-                    margin,
-                    &(arg.foreground, arg.background),
-                    module_size,
+            } else if let Some(ref range) = range {
+                encode::first_fit(
+                    |version| encode_as(variant, Some(version), level),
+                    range.iter().copied(),
                 )
-                .into(),
-                OutputFormat::Ascii | OutputFormat::AsciiInvert => {
-                    encode::to_ascii(&code, margin, module_size, is_invert).into()
+            } else {
+                encode_as(variant, version, level)
+            };
+            let code = if arg.boost_ecc {
+                code.map(|code| {
+                    encode::boost_ecc(code, |version, level| {
+                        encode_as(&encode::variant_of(version), Some(version), level)
+                    })
+                })
+            } else {
+                code
+            };
+            let variant = code
+                .as_ref()
+                .map_or_else(|_| variant.clone(), |c| encode::variant_of(c.version()));
+            let eci = eci_for(&variant);
+            let measure = |version: Version, data: &[u8]| {
+                let mut bits = Bits::new(version);
+                if let Some(ref segments) = segments {
+                    encode::push_segments(&mut bits, segments)
+                } else if let Some(ref data) = gs1 {
+                    encode::push_gs1(&mut bits, data)
+                } else if let Some(designator) = eci_for(&encode::variant_of(version)) {
+                    encode::push_eci(&mut bits, data, designator, mode)
+                } else {
+                    encode::push_data(&mut bits, data, mode)
                 }
-                OutputFormat::Unicode | OutputFormat::UnicodeInvert => {
-                    encode::to_unicode(&code, margin, module_size, is_invert).into()
+                .ok()
+                .map(|()| bits.len())
+            };
+            let diagnose = || {
+                let target = version
+                    .or_else(|| range.as_ref().and_then(|range| range.last().copied()))
+                    .or_else(|| capacity::largest(&variant, level))?;
+                let mut err = capacity::diagnose(|version| measure(version, &buf), target, level);
+                // The input is read up to one byte more than the maximum size, so
+                // the actual data may be longer than the measured one.
+                err.truncated = u64::try_from(buf.len()).is_ok_and(|len| len > MAX_DATA_SIZE);
+                let is_plain = segments.is_none() && gs1.is_none() && !err.truncated;
+                let uppercase = buf.to_ascii_uppercase();
+                if is_plain && mode.is_none() && uppercase != buf {
+                    let fits = Bits::new(target).max_len(level).is_ok_and(|bits| {
+                        measure(target, &uppercase).is_some_and(|used| used <= bits)
+                    });
+                    if fits {
+                        err.suggestions.push(capacity::Suggestion::Uppercase);
+                    }
                 }
+                if is_plain && range.is_none() && matches!(variant, Variant::Normal | Variant::Auto)
+                {
+                    if let Ok(codes) = encode::to_structured_append(&buf, eci, version, level, mode)
+                    {
+                        err.suggestions
+                            .push(capacity::Suggestion::StructuredAppend(codes.len()));
+                    }
+                }
+                Some(err)
             };
+            let codes = match code {
+                Err(QrError::DataTooLong) if arg.structured_append => {
+                    encode::to_structured_append(&buf, eci, version, level, mode)
+                        .map_err(anyhow::Error::from)
+                }
+                Err(QrError::DataTooLong) => {
+                    Err(diagnose().map_or_else(|| QrError::DataTooLong.into(), anyhow::Error::from))
+                }
+                code => code.map(|c| vec![c]).map_err(anyhow::Error::from),
+            }
+            .with_context(|| {
+                if range.is_some() {
+                    "could not construct a QR code within the version range"
+                } else {
+                    "could not construct a QR code"
+                }
+            })?;
+            let designators = segments.as_ref().map_or_else(
+                || eci.into_iter().collect(),
+                |segments| {
+                    segments
+                        .iter()
+                        .filter_map(|segment| match segment {
+                            Segment::Eci(designator) => Some(*designator),
+                            Segment::Data(..) => None,
+                        })
+                        .collect::<Vec<_>>()
+                },
+            );
 
-            if let Some(file) = arg.output {
-                fs::write(&file, output)
-                    .with_context(|| format!("could not write the image to {}", file.display()))?;
-            } else {
-                io::stdout()
-                    .write_all(&output)
-                    .context("could not write the image to standard output")?;
+            for (index, code) in codes.iter().enumerate() {
+                if arg.verbose {
+                    if arg.variant == Variant::Auto {
+                        eprintln!("Variant: {}", variant_name(&variant));
+                    }
+                    let metadata = code.metadata().with_fnc1(arg.gs1.then_some(Fnc1::First));
+                    eprintln!("Version: {}", metadata.symbol_version());
+                    eprintln!("Level: {:?}", metadata.error_correction_level());
+                    for designator in &designators {
+                        eprintln!("ECI: {}", eci::describe(*designator));
+                    }
+                    if let Some(fnc1) = metadata.fnc1() {
+                        eprintln!("FNC1: {fnc1}");
+                    }
+                    let reference = arg.mask.or_else(|| mask::detect(code));
+                    if let Some(reference) = reference.filter(|_| !code.version().is_rect_micro()) {
+                        eprintln!("Mask: {reference}");
+                        // The encoder does not necessarily choose the mask pattern with the
+                        // best evaluation, so the applied one is marked.
+                        for (candidate, evaluation) in mask::evaluate_all(code) {
+                            let marker = if candidate == reference {
+                                " (applied)"
+                            } else {
+                                ""
+                            };
+                            eprintln!("Mask {candidate}: {evaluation}{marker}");
+                        }
+                    }
+                }
+
+                let symbol = if let Some(reference) = arg.mask {
+                    Symbol::with_mask(code, reference)
+                        .context("the mask pattern is not available for the symbol")?
+                } else {
+                    Symbol::new(code)
+                };
+                let output = render(&symbol, &arg)?;
+
+                if let Some(ref file) = arg.output {
+                    let file = if codes.len() > 1 {
+                        numbered_path(file, index)
+                    } else {
+                        file.clone()
+                    };
+                    fs::write(&file, output).with_context(|| {
+                        format!("could not write the image to {}", file.display())
+                    })?;
+                } else {
+                    io::stdout()
+                        .write_all(&output)
+                        .context("could not write the image to standard output")?;
+                }
             }
         }
         Command::Decode(arg) => {
-            let input = match arg.input {
-                Some(ref path) if path.as_os_str() != "-" => fs::read(path)
-                    .with_context(|| format!("could not read data from {}", path.display()))?,
-                _ => {
-                    let mut buf = Vec::new();
-                    io::stdin()
-                        .read_to_end(&mut buf)
-                        .context("could not read data from standard input")?;
-                    buf
-                }
+            let inputs = if arg.input.is_empty() {
+                vec![None]
+            } else {
+                arg.input
+                    .iter()
+                    .map(|path| Some(path.as_path()).filter(|p| p.as_os_str() != "-"))
+                    .collect()
             };
-            let input_format = arg.input_format;
-            #[cfg(feature = "decode-from-svg")]
-            let input_format =
-                input_format.or_else(|| is_svg::is_svg(&input).then_some(InputFormat::Svg));
-            #[cfg(feature = "decode-from-xbm")]
-            let input_format =
-                input_format.or_else(|| input.starts_with(b"#define").then_some(InputFormat::Xbm));
-            #[allow(clippy::option_if_let_else)]
-            let image = match input_format {
-                #[cfg(feature = "decode-from-svg")]
-                Some(InputFormat::Svg) => decode::from_svg(&input),
-                #[cfg(feature = "decode-from-xbm")]
-                Some(InputFormat::Xbm) => {
-                    let decoder = Decoder::new(Cursor::new(input))
-                        .context("could not create new XBM decoder")?;
-                    DynamicImage::from_decoder(decoder).map_err(anyhow::Error::from)
-                }
-                format => {
-                    let format = if let Some(f) = format {
-                        f.try_into()
-                    } else {
-                        image::guess_format(&input).or_else(|err| {
-                            arg.input.map_or_else(|| Err(err), ImageFormat::from_path)
-                        })
+            let mut contents = Vec::new();
+            // The images are numbered consecutively across all input files.
+            let mut index = 0;
+            for path in &inputs {
+                let (images, symbols) = decode_image(*path, &arg)?;
+                if let Some(file) = &arg.annotate {
+                    let is_multiple = inputs.len() > 1 || images.len() > 1;
+                    for (image, geometries) in images {
+                        let file = if is_multiple {
+                            numbered_path(file, index)
+                        } else {
+                            file.clone()
+                        };
+                        index += 1;
+                        let mut image = image.into_rgba8();
+                        for geometry in &geometries {
+                            decode::annotate(&mut image, geometry);
+                        }
+                        image
+                            .save_with_format(&file, ImageFormat::Png)
+                            .with_context(|| {
+                                format!("could not write the image to {}", file.display())
+                            })?;
                     }
-                    .context("could not determine the image format")?;
-                    image::load_from_memory_with_format(&input, format).map_err(anyhow::Error::from)
                 }
+                contents.extend(symbols);
             }
-            .context("could not read the image")?;
-            let mut image = image.into_luma8();
+            let groups = decode::reassemble(contents)?;
 
-            let get_contents = |image| {
-                let mut image = PreparedImage::prepare(image);
-                let grids = image.detect_grids();
-                decode::grids_as_bytes(grids).context("could not decode the grid")
-            };
-
-            // NOTE: rqrr doesn't appear to work if the background is darker than the
-            // foreground. So we try with an inverted image if decoding fails or no content
-            // is returned.
-            let contents = match get_contents(image.clone()) {
-                Err(e) => {
-                    imageops::invert(&mut image);
-                    get_contents(image).map_err(|_| e)?
+            if let Some(format) = arg.format {
+                let mut records = Vec::new();
+                let transcode = |symbols: &[DecodedBytes]| {
+                    decode::to_element_string(symbols).map_or_else(
+                        || match arg.output_encoding {
+                            OutputEncoding::Utf8 => decode::to_utf8(symbols),
+                            OutputEncoding::Raw => {
+                                symbols.iter().flat_map(|c| c.data.clone()).collect()
+                            }
+                        },
+                        String::into_bytes,
+                    )
+                };
+                for group in &groups {
+                    // The data of a symbol in a Structured Append sequence may not be
+                    // decoded alone, because a character or the ECI designator in effect
+                    // can span the symbols.
+                    let data = transcode(group);
+                    for content in group {
+                        let record = Record::new(content, transcode(std::slice::from_ref(content)))
+                            .with_sequence_payload(data.clone());
+                        match format {
+                            DecodeFormat::Json => records.push(record),
+                            DecodeFormat::Jsonl => println!(
+                                "{}",
+                                serde_json::to_string(&record)
+                                    .context("could not serialize the decoded symbol")?
+                            ),
+                        }
+                    }
                 }
-                Ok(contents) if contents.is_empty() => {
-                    imageops::invert(&mut image);
-                    get_contents(image).unwrap_or(contents)
+                if format == DecodeFormat::Json {
+                    println!(
+                        "{}",
+                        serde_json::to_string(&records)
+                            .context("could not serialize the decoded symbols")?
+                    );
                 }
-                Ok(contents) => contents,
-            };
+                return Ok(());
+            }
 
-            for content in contents {
+            for (index, group) in groups.into_iter().enumerate() {
                 if arg.verbose || arg.metadata {
-                    let metadata = content.0.metadata();
-                    eprintln!("Version: {}", metadata.symbol_version());
-                    eprintln!("Level: {:?}", metadata.error_correction_level());
+                    for content in &group {
+                        let metadata = content.metadata();
+                        eprintln!("Version: {}", metadata.symbol_version());
+                        eprintln!("Level: {:?}", metadata.error_correction_level());
+                        if let Some(sa) = content.structured_append {
+                            eprintln!("Sequence: {}/{}", sa.index + 1, sa.count);
+                        }
+                        for eci in &content.eci {
+                            eprintln!("ECI: {}", eci::describe(eci.designator));
+                        }
+                        if let Some(fnc1) = metadata.fnc1() {
+                            eprintln!("FNC1: {fnc1}");
+                        }
+                        if let Some(subimage) = content.subimage {
+                            eprintln!("{subimage}");
+                        }
+                        let geometry = content.geometry;
+                        let corners = geometry
+                            .corners
+                            .map(|(x, y)| format!("({x}, {y})"))
+                            .join(", ");
+                        eprintln!("Corners: {corners}");
+                        eprintln!("Module size: {} px", geometry.module_size);
+                        eprintln!("Rotation: {} degrees", geometry.rotation);
+                        if geometry.mirrored {
+                            eprintln!("Mirrored: yes");
+                        }
+                    }
                     if arg.metadata {
                         continue;
                     }
                 }
 
+                let data = if let Some(element_string) = decode::to_element_string(&group) {
+                    element_string.into_bytes()
+                } else {
+                    match arg.output_encoding {
+                        OutputEncoding::Utf8 => decode::to_utf8(&group),
+                        OutputEncoding::Raw => group.into_iter().flat_map(|c| c.data).collect(),
+                    }
+                };
+                if let Some(format) = arg.parse {
+                    let parsed = parse::parse(&String::from_utf8_lossy(&data))
+                        .context("could not parse the decoded data")?;
+                    match format {
+                        ParseFormat::Text => {
+                            if index > 0 {
+                                println!();
+                            }
+                            print!("{parsed}");
+                        }
+                        ParseFormat::Json => println!(
+                            "{}",
+                            serde_json::to_string(&parsed)
+                                .context("could not serialize the parsed data")?
+                        ),
+                    }
+                    continue;
+                }
                 io::stdout()
-                    .write_all(&content.1)
+                    .write_all(&data)
                     .context("could not write data to standard output")?;
             }
         }
+        Command::Capacity(arg) => {
+            let data = if let Some(ref string) = arg.input {
+                Some(string.clone().into_bytes())
+            } else if let Some(ref path) = arg.read_from {
+                let data = fs::read(path)
+                    .with_context(|| format!("could not read data from {}", path.display()))?;
+                Some(data)
+            } else {
+                None
+            };
+            let payload = match (&data, arg.length, &arg.mode) {
+                (Some(data), ..) => Some(capacity::Payload::Data(data, arg.mode.as_ref(), None)),
+                (None, Some(length), Some(mode)) => Some(capacity::Payload::Length(length, mode)),
+                _ => None,
+            };
+            let levels = arg.error_correction_level.map_or_else(
+                || Ecc::value_variants().iter().map(|&l| l.into()).collect(),
+                |level| vec![level.into()],
+            );
+            let entries = capacity::list(&arg.variant, &levels, payload);
+            let smallest = payload.map(|_| capacity::smallest(&entries));
+
+            match arg.output_format {
+                CapacityFormat::Table => {
+                    print!("{}", capacity::to_table(&entries));
+                    if let Some(smallest) = smallest {
+                        let smallest = smallest.map_or_else(
+                            || String::from("none"),
+                            |entry| {
+                                format!(
+                                    "{}-{:?}",
+                                    capacity::designation(entry.version),
+                                    entry.level
+                                )
+                            },
+                        );
+                        println!("Smallest: {smallest}");
+                    }
+                }
+                CapacityFormat::Json => {
+                    let report = capacity::Report {
+                        symbols: &entries,
+                        smallest,
+                    };
+                    let report = serde_json::to_string(&report)
+                        .context("could not serialize the capacity")?;
+                    println!("{report}");
+                }
+            }
+        }
         Command::Completion(arg) => {
             Opt::print_completion(arg.shell);
         }
     }
     Ok(())
 }
+
+/// Decodes the symbols in the image.
+fn decode_symbols(image: &DynamicImage) -> anyhow::Result<Vec<DecodedBytes>> {
+    let get_contents = |image| {
+        let mut image = PreparedImage::prepare(image);
+        let grids = image.detect_grids();
+        decode::grids_as_bytes(grids).context("could not decode the grid")
+    };
+
+    // NOTE: rqrr doesn't appear to work if the background is darker than the
+    // foreground. So we try with an inverted image if decoding fails or no content
+    // is returned.
+    let inverted = |mut contents: Vec<DecodedBytes>| {
+        for content in &mut contents {
+            content.inverted = true;
+        }
+        contents
+    };
+    let mut luma = image.to_luma8();
+    match get_contents(luma.clone()) {
+        Err(e) => {
+            imageops::invert(&mut luma);
+            get_contents(luma).map(inverted).map_err(|_| e)
+        }
+        Ok(contents) if contents.is_empty() => {
+            imageops::invert(&mut luma);
+            Ok(get_contents(luma).map_or(contents, inverted))
+        }
+        Ok(contents) => Ok(contents),
+    }
+}
+
+/// An image paired with the geometries of the symbols detected in it.
+type DetectedImage = (DynamicImage, Vec<Geometry>);
+
+/// Reads the image and decodes all symbols in it.
+///
+/// Each frame, page or entry of the image is returned together with the
+/// geometries of the symbols detected in it. If `path` is [`None`], the image
+/// is read from standard input.
+fn decode_image(
+    path: Option<&Path>,
+    arg: &Decode,
+) -> anyhow::Result<(Vec<DetectedImage>, Vec<DecodedBytes>)> {
+    let input = if let Some(path) = path {
+        fs::read(path).with_context(|| format!("could not read data from {}", path.display()))?
+    } else {
+        let mut buf = Vec::new();
+        io::stdin()
+            .read_to_end(&mut buf)
+            .context("could not read data from standard input")?;
+        buf
+    };
+    let input_format = arg.input_format;
+    #[cfg(feature = "decode-from-pdf")]
+    let input_format =
+        input_format.or_else(|| input.starts_with(b"%PDF-").then_some(InputFormat::Pdf));
+    #[cfg(feature = "decode-from-svg")]
+    let input_format = input_format.or_else(|| is_svg::is_svg(&input).then_some(InputFormat::Svg));
+    #[cfg(feature = "decode-from-xbm")]
+    let input_format =
+        input_format.or_else(|| input.starts_with(b"#define").then_some(InputFormat::Xbm));
+    // Each image is paired with the index of the frame, page or entry in which it
+    // appears.
+    #[allow(clippy::option_if_let_else)]
+    let (kind, images) = match input_format {
+        #[cfg(feature = "decode-from-pdf")]
+        Some(InputFormat::Pdf) => decode::from_pdf(input, arg.dpi, arg.extract_images)
+            .map(|images| (Subimage::Page as _, images)),
+        #[cfg(feature = "decode-from-svg")]
+        Some(InputFormat::Svg) => {
+            decode::from_svg(&input).map(|image| (Subimage::Frame as _, vec![(0, image)]))
+        }
+        #[cfg(feature = "decode-from-xbm")]
+        Some(InputFormat::Xbm) => {
+            let decoder =
+                Decoder::new(Cursor::new(input)).context("could not create new XBM decoder")?;
+            DynamicImage::from_decoder(decoder)
+                .map(|image| (Subimage::Frame as _, vec![(0, image)]))
+                .map_err(anyhow::Error::from)
+        }
+        format => {
+            let format = if let Some(f) = format {
+                f.try_into()
+            } else {
+                image::guess_format(&input)
+                    .or_else(|err| path.map_or_else(|| Err(err), ImageFormat::from_path))
+            }
+            .context("could not determine the image format")?;
+            decode::images(&input, format)
+                .map(|images| {
+                    (
+                        Subimage::kind(format),
+                        images.into_iter().enumerate().collect::<Vec<_>>(),
+                    )
+                })
+                .map_err(anyhow::Error::from)
+        }
+    }
+    .context("could not read the image")?;
+    anyhow::ensure!(!images.is_empty(), "the image has no frames");
+    let is_multiple = images.last().is_some_and(|(index, _)| *index > 0);
+    let subimage = |index| is_multiple.then(|| kind(index));
+    // NOTE: The same symbol often appears in consecutive frames of an animation or
+    // in entries of different sizes of an icon, but the pages of a document are
+    // independent of each other.
+    let is_deduplicated = !matches!(kind(0), Subimage::Page(_));
+
+    // Unless the images are pages, symbols which were already found in the
+    // previous images are skipped. An image which fails to decode is ignored
+    // unless no symbol is found in any image.
+    let mut contents: Vec<DecodedBytes> = Vec::new();
+    let mut geometries: Vec<Vec<Geometry>> = Vec::with_capacity(images.len());
+    let mut error = None;
+    for (position, (index, image)) in images.iter().enumerate() {
+        if is_deduplicated && position > 0 && image.as_bytes() == images[position - 1].1.as_bytes()
+        {
+            geometries.push(geometries[position - 1].clone());
+            continue;
+        }
+        let symbols = match decode_symbols(image) {
+            Ok(symbols) => symbols,
+            Err(err) => {
+                error.get_or_insert(err);
+                geometries.push(Vec::new());
+                continue;
+            }
+        };
+        geometries.push(symbols.iter().map(|symbol| symbol.geometry).collect());
+        let found = if is_deduplicated { contents.len() } else { 0 };
+        for mut symbol in symbols {
+            if contents[..found].iter().any(|content| {
+                content.data == symbol.data && content.structured_append == symbol.structured_append
+            }) {
+                continue;
+            }
+            symbol.source = path.map(Path::to_path_buf);
+            symbol.subimage = subimage(*index);
+            contents.push(symbol);
+        }
+    }
+    if let Some(err) = error.filter(|_| contents.is_empty()) {
+        return Err(err);
+    }
+    let images = images
+        .into_iter()
+        .map(|(_, image)| image)
+        .zip(geometries)
+        .collect();
+    Ok((images, contents))
+}
+
+/// Returns the name of the type of QR code.
+fn variant_name(variant: &Variant) -> String {
+    variant
+        .to_possible_value()
+        .map(|value| value.get_name().to_owned())
+        .unwrap_or_default()
+}
+
+/// Renders the QR code into the output format.
+fn render(code: &Symbol<'_>, arg: &Encode) -> anyhow::Result<Vec<u8>> {
+    let margin = arg.margin;
+    let module_size = arg.size.map(NonZeroU32::get);
+    let colors = (arg.foreground.clone(), arg.background.clone());
+    let is_invert = matches!(
+        arg.output_format,
+        OutputFormat::AsciiInvert | OutputFormat::UnicodeInvert
+    );
+    let is_qr_bill = matches!(arg.payload, Some(Payload::QrBill(_)));
+    let output = match arg.output_format {
+        OutputFormat::Png => {
+            let image = if is_qr_bill {
+                encode::to_qr_bill_image(code, margin, &colors, module_size)
+            } else {
+                encode::to_image(code, margin, &colors, module_size)
+            };
+            let mut buf = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)
+                .context("could not write the image to the buffer")?;
+
+            #[cfg(feature = "optimize-output-png")]
+            if let Some(level) = arg.optimize_png {
+                let mut optimize_opt = Options::from_preset(level.into());
+                if let Some(iterations) = arg.zopfli {
+                    optimize_opt.deflate = Deflaters::Zopfli { iterations };
+                }
+                buf = oxipng::optimize_from_memory(&buf, &optimize_opt)
+                    .context("could not optimize the image")?;
+            }
+            if is_qr_bill {
+                encode::set_png_resolution(&mut buf, encode::qr_bill_resolution(code, module_size));
+            }
+            buf
+        }
+        OutputFormat::Svg if is_qr_bill => {
+            encode::to_qr_bill_svg(code, margin, &colors, module_size).into()
+        }
+        OutputFormat::Svg => encode::to_svg(code, margin, &colors, module_size).into(),
+        OutputFormat::Eps if is_qr_bill => {
+            encode::to_qr_bill_eps(code, margin, &colors, module_size).into()
+        }
+        OutputFormat::Eps => encode::to_eps(code, margin, &colors, module_size).into(),
+        OutputFormat::Pic => encode::to_pic(code, margin, module_size).into(),
+        #[cfg(feature = "output-as-ansi")]
+        OutputFormat::Ansi => encode::to_ansi(code, margin, &colors, module_size).into(),
+        #[cfg(feature = "output-as-ansi")]
+        OutputFormat::Ansi256 => encode::to_ansi_256(code, margin, &colors, module_size).into(),
+        #[cfg(feature = "output-as-ansi")]
+        OutputFormat::AnsiTrueColor => {
+            encode::to_ansi_true_color(code, margin, &colors, module_size).into()
+        }
+        OutputFormat::Ascii | OutputFormat::AsciiInvert => {
+            encode::to_ascii(code, margin, module_size, is_invert).into()
+        }
+        OutputFormat::Unicode | OutputFormat::UnicodeInvert => {
+            encode::to_unicode(code, margin, module_size, is_invert).into()
+        }
+    };
+    Ok(output)
+}
+
+/// Returns the path of the file numbered `index`.
+///
+/// The number starting from 1 is appended to the stem of `path`.
+fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(format!("-{:02}", index + 1));
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    path.with_file_name(file_name)
+}
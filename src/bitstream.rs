@@ -0,0 +1,598 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use qrcode2::{EcLevel, Version, bits::Bits};
+use rqrr::{BitGrid, DeQRError, Grid, MetaData};
+
+use crate::{cli::Ecc, metadata::Fnc1, reed_solomon};
+
+// This table is copied from ISO/IEC 18004:2015 Table 9.
+/// The number of error correction blocks for each version and error correction
+/// level.
+static ERROR_CORRECTION_BLOCKS: [[usize; 4]; 40] = [
+    [1, 1, 1, 1],
+    [1, 1, 1, 1],
+    [1, 1, 2, 2],
+    [1, 2, 2, 4],
+    [1, 2, 4, 4],
+    [2, 4, 4, 4],
+    [2, 4, 6, 5],
+    [2, 4, 6, 6],
+    [2, 5, 8, 8],
+    [4, 5, 8, 8],
+    [4, 5, 8, 11],
+    [4, 8, 10, 11],
+    [4, 9, 12, 16],
+    [4, 9, 16, 16],
+    [6, 10, 12, 18],
+    [6, 10, 17, 16],
+    [6, 11, 16, 19],
+    [6, 13, 18, 21],
+    [7, 14, 21, 25],
+    [8, 16, 20, 25],
+    [8, 17, 23, 25],
+    [9, 17, 23, 34],
+    [9, 18, 25, 30],
+    [10, 20, 27, 32],
+    [12, 21, 29, 35],
+    [12, 23, 34, 37],
+    [12, 25, 34, 40],
+    [13, 26, 35, 42],
+    [14, 28, 38, 45],
+    [15, 29, 40, 48],
+    [16, 31, 43, 51],
+    [17, 33, 45, 54],
+    [18, 35, 48, 57],
+    [19, 37, 51, 60],
+    [19, 38, 53, 63],
+    [20, 40, 56, 66],
+    [21, 43, 59, 70],
+    [22, 45, 62, 74],
+    [24, 47, 65, 77],
+    [25, 49, 68, 81],
+];
+
+/// The group separator (GS) used as FNC1 in the data.
+pub const GROUP_SEPARATOR: u8 = 0x1d;
+
+/// Structured Append header of a symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StructuredAppend {
+    /// The position of the symbol in the sequence, starting from 0.
+    pub index: usize,
+
+    /// The total number of symbols in the sequence.
+    pub count: usize,
+
+    /// The parity data of the whole sequence.
+    pub parity: u8,
+}
+
+/// ECI designator in a symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Eci {
+    /// The offset in the decoded data from which the ECI designator applies.
+    pub offset: usize,
+
+    /// The ECI designator.
+    pub designator: u32,
+}
+
+/// Payload of a symbol.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Payload {
+    /// The Structured Append header, if any.
+    pub structured_append: Option<StructuredAppend>,
+
+    /// The ECI designators in order of appearance.
+    pub eci: Vec<Eci>,
+
+    /// The FNC1 mode, if any.
+    pub fnc1: Option<Fnc1>,
+
+    /// The decoded data.
+    pub data: Vec<u8>,
+}
+
+/// A view of a grid with the data mask removed.
+///
+/// The format information is left as is so that it can still be read.
+struct UnmaskedGrid<'a> {
+    grid: &'a dyn BitGrid,
+    mask: Option<u16>,
+    mirrored: bool,
+}
+
+impl UnmaskedGrid<'_> {
+    fn is_format_information(&self, y: usize, x: usize) -> bool {
+        let size = self.size();
+        (y == 8 && (x <= 8 || x >= size - 8)) || (x == 8 && (y <= 8 || y >= size - 7))
+    }
+}
+
+impl BitGrid for UnmaskedGrid<'_> {
+    fn size(&self) -> usize {
+        self.grid.size()
+    }
+
+    fn bit(&self, y: usize, x: usize) -> bool {
+        let bit = if self.mirrored {
+            self.grid.bit(x, y)
+        } else {
+            self.grid.bit(y, x)
+        };
+        let Some(mask) = self.mask.filter(|_| !self.is_format_information(y, x)) else {
+            return bit;
+        };
+        let is_masked = match mask {
+            0 => (y + x) % 2 == 0,
+            1 => y % 2 == 0,
+            2 => x % 3 == 0,
+            3 => (y + x) % 3 == 0,
+            4 => ((y / 2) + (x / 3)) % 2 == 0,
+            5 => (y * x) % 2 + (y * x) % 3 == 0,
+            6 => ((y * x) % 2 + (y * x) % 3) % 2 == 0,
+            _ => ((y + x) % 2 + (y * x) % 3) % 2 == 0,
+        };
+        bit ^ is_masked
+    }
+}
+
+/// Returns the total number of codewords of the normal QR code version.
+const fn codeword_count(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignment_patterns = version / 7 + 2;
+        modules -= (25 * alignment_patterns - 10) * alignment_patterns - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+/// Deinterleaves the codewords and corrects errors in each block.
+///
+/// Returns the data codewords.
+fn correct_codewords(
+    codewords: &[u8],
+    version: usize,
+    level: EcLevel,
+) -> Result<Vec<u8>, DeQRError> {
+    let total = codeword_count(version);
+    let data_len = i16::try_from(version)
+        .ok()
+        .and_then(|v| Bits::new(Version::Normal(v)).max_len(level).ok())
+        .ok_or(DeQRError::InvalidVersion)?
+        / 8;
+    let block_count = ERROR_CORRECTION_BLOCKS[version - 1][level as usize];
+    let ecc_len = (total - data_len) / block_count;
+    let short_block_count = block_count - total % block_count;
+    let short_data_len = total / block_count - ecc_len;
+    if codewords.len() < total {
+        return Err(DeQRError::DataUnderflow);
+    }
+
+    let mut data = Vec::with_capacity(data_len);
+    let ecc_offset = data_len;
+    for i in 0..block_count {
+        let block_data_len = short_data_len + usize::from(i >= short_block_count);
+        let mut block = (0..block_data_len)
+            .map(|j| {
+                if j < short_data_len {
+                    codewords[j * block_count + i]
+                } else {
+                    codewords[short_data_len * block_count + i - short_block_count]
+                }
+            })
+            .chain((0..ecc_len).map(|j| codewords[ecc_offset + j * block_count + i]))
+            .collect::<Vec<_>>();
+        reed_solomon::correct(&mut block, ecc_len)?;
+        data.extend_from_slice(&block[..block_data_len]);
+    }
+    Ok(data)
+}
+
+fn read_codewords_from(
+    grid: &dyn BitGrid,
+    mirrored: bool,
+) -> Result<(MetaData, Vec<u8>), DeQRError> {
+    let (meta, _) = Grid::new(UnmaskedGrid {
+        grid,
+        mask: None,
+        mirrored,
+    })
+    .get_raw_data()?;
+    let (_, raw) = Grid::new(UnmaskedGrid {
+        grid,
+        mask: Some(meta.mask),
+        mirrored,
+    })
+    .get_raw_data()?;
+    let level = Ecc::from(meta.ecc_level).into();
+    let data = correct_codewords(&raw.data[..raw.len / 8], meta.version.0, level)?;
+    Ok((meta, data))
+}
+
+/// Reads the error corrected data codewords from the grid.
+///
+/// If the grid cannot be read, this also tries to read the mirrored grid.
+/// Returns whether the grid was read as mirrored together with the codewords.
+pub fn read_codewords(grid: &dyn BitGrid) -> Result<(MetaData, Vec<u8>, bool), DeQRError> {
+    read_codewords_from(grid, false)
+        .map(|(meta, data)| (meta, data, false))
+        .or_else(|err| {
+            read_codewords_from(grid, true)
+                .map(|(meta, data)| (meta, data, true))
+                .map_err(|_| err)
+        })
+}
+
+/// A reader of the bit stream.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, n: usize) -> Result<usize, DeQRError> {
+        if self.remaining() < n {
+            return Err(DeQRError::DataUnderflow);
+        }
+        let value = (self.position..self.position + n).fold(0, |value, i| {
+            (value << 1) | usize::from(self.data[i / 8] & (0x80 >> (i % 8)) != 0)
+        });
+        self.position += n;
+        Ok(value)
+    }
+}
+
+/// Returns the number of bits of the character count indicator.
+const fn length_bits_count(mode: usize, version: usize) -> usize {
+    let class = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    match mode {
+        0b0001 => [10, 12, 14][class],
+        0b0010 => [9, 11, 13][class],
+        0b0100 => [8, 16, 16][class],
+        _ => [8, 10, 12][class],
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn read_numeric(
+    reader: &mut BitReader<'_>,
+    count: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), DeQRError> {
+    for chunk in (0..count).collect::<Vec<_>>().chunks(3) {
+        let digits = chunk.len();
+        let mut value = reader.read(digits * 3 + 1)?;
+        let mut buf = [0; 3];
+        for digit in buf[..digits].iter_mut().rev() {
+            *digit = b'0' + (value % 10) as u8;
+            value /= 10;
+        }
+        out.extend_from_slice(&buf[..digits]);
+    }
+    Ok(())
+}
+
+/// Reads the data in alphanumeric mode.
+///
+/// In FNC1 mode, `%` represents the group separator (GS) and `%%` represents
+/// `%`.
+fn read_alphanumeric(
+    reader: &mut BitReader<'_>,
+    count: usize,
+    is_fnc1: bool,
+    out: &mut Vec<u8>,
+) -> Result<(), DeQRError> {
+    const CHARSET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+    let mut buf = Vec::with_capacity(count);
+    for _ in 0..count / 2 {
+        let value = reader.read(11)?;
+        let (first, second) = (value / 45, value % 45);
+        buf.push(*CHARSET.get(first).ok_or(DeQRError::EncodingError)?);
+        buf.push(CHARSET[second]);
+    }
+    if count % 2 == 1 {
+        let value = reader.read(6)?;
+        buf.push(*CHARSET.get(value).ok_or(DeQRError::EncodingError)?);
+    }
+    if !is_fnc1 {
+        out.extend(buf);
+        return Ok(());
+    }
+    let mut chars = buf.into_iter().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            b'%' if chars.next_if_eq(&b'%').is_some() => out.push(b'%'),
+            b'%' => out.push(GROUP_SEPARATOR),
+            c => out.push(c),
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn read_byte(reader: &mut BitReader<'_>, count: usize, out: &mut Vec<u8>) -> Result<(), DeQRError> {
+    for _ in 0..count {
+        out.push(reader.read(8)? as u8);
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn read_kanji(
+    reader: &mut BitReader<'_>,
+    count: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), DeQRError> {
+    for _ in 0..count {
+        let value = reader.read(13)?;
+        let intermediate = ((value / 0xc0) << 8) | (value % 0xc0);
+        let character = if intermediate + 0x8140 <= 0x9ffc {
+            intermediate + 0x8140
+        } else {
+            intermediate + 0xc140
+        };
+        out.extend_from_slice(&(character as u16).to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Parses the data codewords of the normal QR code version.
+#[allow(clippy::cast_possible_truncation)]
+pub fn parse(codewords: &[u8], version: usize) -> Result<Payload, DeQRError> {
+    let mut reader = BitReader::new(codewords);
+    let mut payload = Payload::default();
+    while reader.remaining() >= 4 {
+        let mode = reader.read(4)?;
+        match mode {
+            0b0000 => break,
+            0b0001 | 0b0010 | 0b0100 | 0b1000 => {
+                let count = reader.read(length_bits_count(mode, version))?;
+                let out = &mut payload.data;
+                match mode {
+                    0b0001 => read_numeric(&mut reader, count, out),
+                    0b0010 => read_alphanumeric(&mut reader, count, payload.fnc1.is_some(), out),
+                    0b0100 => read_byte(&mut reader, count, out),
+                    _ => read_kanji(&mut reader, count, out),
+                }?;
+            }
+            0b0011 => {
+                let (index, count) = (reader.read(4)?, reader.read(4)? + 1);
+                if index >= count {
+                    return Err(DeQRError::EncodingError);
+                }
+                payload.structured_append = Some(StructuredAppend {
+                    index,
+                    count,
+                    parity: reader.read(8)? as u8,
+                });
+            }
+            0b0111 => {
+                let first = reader.read(8)?;
+                let designator = match first {
+                    0x00..=0x7f => first,
+                    0x80..=0xbf => ((first & 0x3f) << 8) | reader.read(8)?,
+                    0xc0..=0xdf => ((first & 0x1f) << 16) | reader.read(16)?,
+                    _ => return Err(DeQRError::EncodingError),
+                };
+                payload.eci.push(Eci {
+                    offset: payload.data.len(),
+                    designator: u32::try_from(designator).map_err(|_| DeQRError::EncodingError)?,
+                });
+            }
+            0b0101 => payload.fnc1 = Some(Fnc1::First),
+            0b1001 => payload.fnc1 = Some(Fnc1::Second(reader.read(8)? as u8)),
+            _ => return Err(DeQRError::UnknownDataType),
+        }
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use qrcode2::QrCode;
+    use rqrr::SimpleGrid;
+
+    use super::*;
+    use crate::encode;
+
+    fn to_grid(code: &QrCode) -> SimpleGrid {
+        SimpleGrid::from_func(code.width(), |x, y| code[(x, y)] == qrcode2::Color::Dark)
+    }
+
+    fn encode_bits(data: &[u8], version: i16, level: EcLevel) -> Bits {
+        let mut bits = Bits::new(Version::Normal(version));
+        bits.push_optimal_data(data).unwrap();
+        bits.push_terminator(level).unwrap();
+        bits
+    }
+
+    #[test]
+    fn validate_codeword_count() {
+        assert_eq!(codeword_count(1), 26);
+        assert_eq!(codeword_count(7), 196);
+        assert_eq!(codeword_count(40), 3706);
+    }
+
+    #[test]
+    fn read_codewords_of_every_version() {
+        for version in [1, 2, 6, 7, 9, 10, 15, 26, 27, 36, 40] {
+            for level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                let bits = encode_bits(b"QR code", version, level);
+                let expected = bits.into_bytes();
+                let code =
+                    QrCode::with_bits(encode_bits(b"QR code", version, level), level).unwrap();
+                let (meta, actual, mirrored) = read_codewords(&to_grid(&code)).unwrap();
+                assert_eq!(meta.version.0, usize::try_from(version).unwrap());
+                assert_eq!(Ecc::from(meta.ecc_level), level.into());
+                assert_eq!(actual, expected);
+                assert!(!mirrored);
+            }
+        }
+    }
+
+    #[test]
+    fn read_codewords_from_mirrored_grid() {
+        let code = QrCode::with_version(b"QR code", Version::Normal(2), EcLevel::M).unwrap();
+        let mirrored =
+            SimpleGrid::from_func(code.width(), |x, y| code[(y, x)] == qrcode2::Color::Dark);
+        let (meta, codewords, is_mirrored) = read_codewords(&mirrored).unwrap();
+        assert!(is_mirrored);
+        assert_eq!(parse(&codewords, meta.version.0).unwrap().data, b"QR code");
+    }
+
+    #[test]
+    fn read_codewords_with_errors() {
+        let code = QrCode::with_version(b"QR code", Version::Normal(5), EcLevel::H).unwrap();
+        let grid = to_grid(&code);
+        let damaged = SimpleGrid::from_func(grid.size(), |x, y| {
+            let is_damaged = (15..25).contains(&x) && (20..30).contains(&y);
+            grid.bit(y, x) ^ is_damaged
+        });
+        let (meta, codewords, _) = read_codewords(&damaged).unwrap();
+        assert_eq!(parse(&codewords, meta.version.0).unwrap().data, b"QR code");
+    }
+
+    #[test]
+    fn parse_every_mode() {
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_numeric_data(b"01234567").unwrap();
+        bits.push_alphanumeric_data(b"AC-42").unwrap();
+        bits.push_byte_data(b"qr").unwrap();
+        bits.push_kanji_data(b"\x93\x5f\xe4\xaa").unwrap();
+        bits.push_terminator(EcLevel::L).unwrap();
+        assert_eq!(
+            parse(&bits.into_bytes(), 1).unwrap(),
+            Payload {
+                structured_append: None,
+                eci: Vec::new(),
+                fnc1: None,
+                data: b"01234567AC-42qr\x93\x5f\xe4\xaa".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_structured_append_header() {
+        let mut bits = Bits::new(Version::Normal(1));
+        encode::push_structured_append_header(&mut bits, 2, 4, 0x2e).unwrap();
+        bits.push_byte_data(b"QR code").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(
+            parse(&bits.into_bytes(), 1).unwrap(),
+            Payload {
+                structured_append: Some(StructuredAppend {
+                    index: 2,
+                    count: 4,
+                    parity: 0x2e
+                }),
+                eci: Vec::new(),
+                fnc1: None,
+                data: b"QR code".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_structured_append_header_with_invalid_index() {
+        // The headers of the symbol at position 2 and 4 in the sequence of 2
+        // symbols, followed by the terminator.
+        assert!(parse(&[0x31, 0x12, 0xe0, 0x00], 1).is_ok());
+        assert!(parse(&[0x33, 0x12, 0xe0, 0x00], 1).is_err());
+    }
+
+    #[test]
+    fn parse_eci_designator() {
+        let mut bits = Bits::new(Version::Normal(2));
+        bits.push_eci_designator(26).unwrap();
+        bits.push_byte_data(b"QR").unwrap();
+        bits.push_eci_designator(899).unwrap();
+        bits.push_byte_data(b" ").unwrap();
+        bits.push_eci_designator(999_999).unwrap();
+        bits.push_byte_data(b"code").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(
+            parse(&bits.into_bytes(), 2).unwrap(),
+            Payload {
+                structured_append: None,
+                eci: vec![
+                    Eci {
+                        offset: 0,
+                        designator: 26
+                    },
+                    Eci {
+                        offset: 2,
+                        designator: 899
+                    },
+                    Eci {
+                        offset: 3,
+                        designator: 999_999
+                    }
+                ],
+                fnc1: None,
+                data: b"QR code".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_fnc1_in_first_position() {
+        let mut bits = Bits::new(Version::Normal(2));
+        bits.push_fnc1_first_position().unwrap();
+        bits.push_numeric_data(b"0109501101530003").unwrap();
+        bits.push_alphanumeric_data(b"10AB%%C%21X").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        let payload = parse(&bits.into_bytes(), 2).unwrap();
+        assert_eq!(payload.fnc1, Some(Fnc1::First));
+        assert_eq!(payload.data, b"010950110153000310AB%C\x1d21X");
+    }
+
+    #[test]
+    fn parse_fnc1_in_second_position() {
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_fnc1_second_position(37).unwrap();
+        bits.push_alphanumeric_data(b"AB%C").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        let payload = parse(&bits.into_bytes(), 1).unwrap();
+        assert_eq!(payload.fnc1, Some(Fnc1::Second(37)));
+        assert_eq!(payload.data, b"AB\x1dC");
+    }
+
+    #[test]
+    fn parse_unknown_mode() {
+        assert!(matches!(
+            parse(&[0b1111_0000], 1),
+            Err(DeQRError::UnknownDataType)
+        ));
+    }
+
+    #[test]
+    fn parse_truncated_data() {
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_byte_data(b"QR code").unwrap();
+        let mut codewords = bits.into_bytes();
+        codewords.truncate(4);
+        assert!(matches!(
+            parse(&codewords, 1),
+            Err(DeQRError::DataUnderflow)
+        ));
+    }
+}
@@ -0,0 +1,686 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{cmp::Reverse, error::Error, fmt};
+
+use qrcode2::{
+    EcLevel, Version,
+    bits::{Bits, ExtendedMode},
+    types,
+};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    cli::{Eci, Mode, Variant},
+    encode,
+};
+
+/// The payload to be stored in a symbol.
+#[derive(Clone, Copy, Debug)]
+pub enum Payload<'a> {
+    /// The data encoded in the mode, or using the optimal encoding if the mode
+    /// is [`None`].
+    ///
+    /// The ECI designator is specified in the same way as `--eci`. If it is
+    /// [`None`], the ECI designator is inserted automatically in the same way
+    /// as when encoding.
+    Data(&'a [u8], Option<&'a Mode>, Option<Eci>),
+
+    /// The number of characters encoded in the mode.
+    Length(usize, &'a Mode),
+}
+
+impl Payload<'_> {
+    /// Returns the number of bits of the payload in the version.
+    ///
+    /// Returns [`None`] if the payload cannot be encoded in the version.
+    fn bits(self, version: Version) -> Option<usize> {
+        match self {
+            Self::Data(data, mode, eci) => {
+                let eci = match eci {
+                    Some(Eci::Designator(designator)) => Some(designator),
+                    Some(Eci::None) => None,
+                    None => encode::auto_eci(data, &encode::variant_of(version), mode),
+                };
+                let mut bits = Bits::new(version);
+                if let Some(designator) = eci {
+                    encode::push_eci(&mut bits, data, designator, mode)
+                } else {
+                    encode::push_data(&mut bits, data, mode)
+                }
+                .ok()?;
+                Some(bits.len())
+            }
+            Self::Length(length, mode) => {
+                let length_bits = length_bits(version, mode)?;
+                (length < 1 << length_bits).then(|| {
+                    version.mode_bits_count()
+                        + length_bits
+                        + types::Mode::from(mode).data_bits_count(length)
+                })
+            }
+        }
+    }
+}
+
+/// Returns the number of bits of the character count indicator for the mode in
+/// the version.
+///
+/// Returns [`None`] if the mode is not available in the version.
+fn length_bits(version: Version, mode: &Mode) -> Option<usize> {
+    let mode = types::Mode::from(mode);
+    Bits::new(version)
+        .push_mode_indicator(ExtendedMode::Data(mode))
+        .ok()?;
+    Some(mode.length_bits_count(version))
+}
+
+/// Returns the maximum number of characters which can be stored in `bits` bits
+/// of the data in the mode in the version.
+///
+/// Returns [`None`] if the mode is not available in the version.
+fn max_count(version: Version, bits: usize, mode: &Mode) -> Option<usize> {
+    let length_bits = length_bits(version, mode)?;
+    let available = bits.saturating_sub(version.mode_bits_count() + length_bits);
+    let count = match mode {
+        Mode::Numeric => available * 3 / 10,
+        Mode::Alphanumeric => available * 2 / 11,
+        Mode::Byte => available / 8,
+        Mode::Kanji => available / 13,
+    };
+    Some(count.min((1 << length_bits) - 1))
+}
+
+/// Returns the designation of the version.
+///
+/// The version of Micro QR code is prefixed with "M" to distinguish it from
+/// that of normal QR code.
+pub fn designation(version: Version) -> String {
+    match version {
+        Version::Normal(version) => version.to_string(),
+        Version::Micro(version) => format!("M{version}"),
+        Version::RectMicro(height, width) => format!("R{height}x{width}"),
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_version<S: Serializer>(version: &Version, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&designation(*version))
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_level<S: Serializer>(level: &EcLevel, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{level:?}"))
+}
+
+/// The capacity of a symbol.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    /// The type of QR code.
+    pub variant: Variant,
+
+    /// The version of the symbol.
+    #[serde(serialize_with = "serialize_version")]
+    pub version: Version,
+
+    /// The error correction level.
+    #[serde(serialize_with = "serialize_level")]
+    pub level: EcLevel,
+
+    /// The number of modules in the horizontal direction.
+    pub width: i16,
+
+    /// The number of modules in the vertical direction.
+    pub height: i16,
+
+    /// The number of bits of the data.
+    pub bits: usize,
+
+    /// The maximum number of digits in numeric mode.
+    pub numeric: Option<usize>,
+
+    /// The maximum number of characters in alphanumeric mode.
+    pub alphanumeric: Option<usize>,
+
+    /// The maximum number of bytes in byte mode.
+    pub byte: Option<usize>,
+
+    /// The maximum number of characters in kanji mode.
+    pub kanji: Option<usize>,
+
+    /// The number of remaining bits after storing the payload.
+    ///
+    /// This is [`None`] if no payload is specified, and [`Some(None)`] if the
+    /// payload cannot be encoded in the version.
+    #[allow(clippy::option_option)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<Option<isize>>,
+}
+
+impl Entry {
+    /// Computes the capacity of the symbol of the version at the level.
+    ///
+    /// Returns [`None`] if the level is not available in the version.
+    pub fn new(version: Version, level: EcLevel, payload: Option<Payload<'_>>) -> Option<Self> {
+        let bits = Bits::new(version).max_len(level).ok()?;
+        let remaining = payload.map(|payload| {
+            payload.bits(version).map(|used| {
+                isize::try_from(bits).expect("invalid number of bits")
+                    - isize::try_from(used).expect("invalid number of bits")
+            })
+        });
+        Some(Self {
+            variant: encode::variant_of(version),
+            version,
+            level,
+            width: version.width(),
+            height: version.height(),
+            bits,
+            numeric: max_count(version, bits, &Mode::Numeric),
+            alphanumeric: max_count(version, bits, &Mode::Alphanumeric),
+            byte: max_count(version, bits, &Mode::Byte),
+            kanji: max_count(version, bits, &Mode::Kanji),
+            remaining,
+        })
+    }
+
+    /// Returns [`true`] if the payload fits in the symbol.
+    pub const fn fits(&self) -> bool {
+        matches!(self.remaining, Some(Some(remaining)) if remaining >= 0)
+    }
+}
+
+/// Computes the capacity of every symbol of the type of QR code at the levels.
+///
+/// If `variant` is [`Variant::Auto`], the symbols of all types are computed.
+pub fn list(variant: &Variant, levels: &[EcLevel], payload: Option<Payload<'_>>) -> Vec<Entry> {
+    let variants = if variant == &Variant::Auto {
+        vec![Variant::Normal, Variant::Micro, Variant::Rmqr]
+    } else {
+        vec![variant.clone()]
+    };
+    variants
+        .iter()
+        .flat_map(encode::versions)
+        .flat_map(|version| {
+            levels
+                .iter()
+                .filter_map(move |&level| Entry::new(version, level, payload))
+        })
+        .collect()
+}
+
+/// Returns the smallest symbol which can store the payload.
+///
+/// The symbols are compared by the area, and then by the error correction
+/// level, preferring the higher one.
+pub fn smallest(entries: &[Entry]) -> Option<&Entry> {
+    entries
+        .iter()
+        .filter(|entry| entry.fits())
+        .min_by_key(|entry| (entry.width * entry.height, Reverse(entry.level)))
+}
+
+/// The capacity of the symbols and the smallest symbol which can store the
+/// payload.
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    /// The capacity of the symbols.
+    pub symbols: &'a [Entry],
+
+    /// The smallest symbol which can store the payload.
+    ///
+    /// This is [`None`] if no payload is specified.
+    #[allow(clippy::option_option)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smallest: Option<Option<&'a Entry>>,
+}
+
+/// Returns the largest version of the type of QR code which is available at
+/// the level.
+pub fn largest(variant: &Variant, level: EcLevel) -> Option<Version> {
+    encode::versions(variant)
+        .into_iter()
+        .rev()
+        .find(|&version| Bits::new(version).max_len(level).is_ok())
+}
+
+/// An alternative to store the data which does not fit in the symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Suggestion {
+    /// Lower the error correction level.
+    Level(EcLevel),
+
+    /// Use the version instead.
+    Version(Version),
+
+    /// Convert the data to uppercase so that it can be encoded in
+    /// alphanumeric mode.
+    Uppercase,
+
+    /// Split the data into the number of symbols using Structured Append.
+    StructuredAppend(usize),
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Level(level) => write!(
+                f,
+                "lower the error correction level to {level:?} with `-l {}`",
+                format!("{level:?}").to_lowercase()
+            ),
+            Self::Version(version @ Version::Normal(v)) => write!(
+                f,
+                "use QR code version {} with `--variant normal -v {v}`",
+                designation(*version)
+            ),
+            Self::Version(version @ Version::Micro(v)) => write!(
+                f,
+                "use Micro QR code version {} with `--variant micro -v {v}`",
+                designation(*version)
+            ),
+            Self::Version(version @ Version::RectMicro(height, width)) => write!(
+                f,
+                "use rMQR code {} with `--variant rmqr -v {height} {width}`",
+                designation(*version)
+            ),
+            Self::Uppercase => f.write_str(
+                "convert the data to uppercase so that it can be encoded in alphanumeric mode",
+            ),
+            Self::StructuredAppend(count) => write!(
+                f,
+                "split the data into {count} symbols with `--structured-append`"
+            ),
+        }
+    }
+}
+
+/// An error which can be returned when the data does not fit in the symbol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataTooLongError {
+    /// The largest version which was tried.
+    pub version: Version,
+
+    /// The error correction level.
+    pub level: EcLevel,
+
+    /// The number of bits by which the data exceeds the capacity.
+    ///
+    /// This is [`None`] if the data cannot be encoded in the version.
+    pub excess: Option<usize>,
+
+    /// [`true`] if the data was truncated before being measured, so `excess`
+    /// is a lower bound.
+    pub truncated: bool,
+
+    /// The alternatives to store the data.
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl fmt::Display for DataTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (version, level) = (designation(self.version), self.level);
+        if let Some(excess) = self.excess {
+            let at_least = if self.truncated { "at least " } else { "" };
+            let plural = |n: usize, unit: &str| {
+                if n == 1 {
+                    format!("{n} {unit}")
+                } else {
+                    format!("{n} {unit}s")
+                }
+            };
+            write!(
+                f,
+                "the data is {at_least}{} ({}) over the capacity of version {version} at level {level:?}",
+                plural(excess, "bit"),
+                plural(excess.div_ceil(8), "byte")
+            )?;
+        } else {
+            write!(
+                f,
+                "the data cannot be stored in version {version} at level {level:?}"
+            )?;
+        }
+        for suggestion in &self.suggestions {
+            write!(f, "\nhint: {suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for DataTooLongError {}
+
+/// Diagnoses why the data does not fit in the version at the level.
+///
+/// `measure` returns the number of bits of the data in the version, or
+/// [`None`] if the data cannot be encoded in the version. A lower error
+/// correction level and the smallest symbol of any type which can store the
+/// data are suggested if available.
+pub fn diagnose(
+    measure: impl Fn(Version) -> Option<usize>,
+    version: Version,
+    level: EcLevel,
+) -> DataTooLongError {
+    let fits = |version: Version, level: EcLevel| {
+        Bits::new(version)
+            .max_len(level)
+            .is_ok_and(|bits| measure(version).is_some_and(|used| used <= bits))
+    };
+    let excess = Bits::new(version)
+        .max_len(level)
+        .ok()
+        .and_then(|bits| measure(version).map(|used| used.saturating_sub(bits)));
+    let mut suggestions = Vec::new();
+    if let Some(lower) = [EcLevel::Q, EcLevel::M, EcLevel::L]
+        .into_iter()
+        .filter(|&lower| lower < level)
+        .find(|&lower| fits(version, lower))
+    {
+        suggestions.push(Suggestion::Level(lower));
+    }
+    if let Some(smallest) = encode::versions(&Variant::Auto)
+        .into_iter()
+        .find(|&v| v != version && fits(v, level))
+    {
+        suggestions.push(Suggestion::Version(smallest));
+    }
+    DataTooLongError {
+        version,
+        level,
+        excess,
+        truncated: false,
+        suggestions,
+    }
+}
+
+/// Formats the entries as a table.
+pub fn to_table(entries: &[Entry]) -> String {
+    let has_payload = entries.iter().any(|entry| entry.remaining.is_some());
+    let mut header = vec![
+        "Version",
+        "Level",
+        "Width",
+        "Height",
+        "Bits",
+        "Numeric",
+        "Alphanumeric",
+        "Byte",
+        "Kanji",
+    ];
+    if has_payload {
+        header.push("Remaining");
+    }
+    let count = |count: Option<usize>| count.map_or_else(|| String::from("-"), |c| c.to_string());
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            let mut row = vec![
+                designation(entry.version),
+                format!("{:?}", entry.level),
+                entry.width.to_string(),
+                entry.height.to_string(),
+                entry.bits.to_string(),
+                count(entry.numeric),
+                count(entry.alphanumeric),
+                count(entry.byte),
+                count(entry.kanji),
+            ];
+            if let Some(remaining) = entry.remaining {
+                row.push(remaining.map_or_else(|| String::from("-"), |r| r.to_string()));
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([title.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    // The version and the level are left-aligned, and the numbers are
+    // right-aligned.
+    let format_row = |row: &[&str]| {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| {
+                if i < 2 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .collect::<Vec<_>>();
+        cells.join("  ")
+    };
+    let mut table = format_row(&header);
+    table.push('\n');
+    for row in &rows {
+        table.push_str(&format_row(
+            &row.iter().map(String::as_str).collect::<Vec<_>>(),
+        ));
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_payload_bits() {
+        let payload = Payload::Data(b"01234567", None, None);
+        assert_eq!(payload.bits(Version::Normal(1)), Some(4 + 10 + 27));
+        assert_eq!(payload.bits(Version::Micro(1)), None);
+        let payload = Payload::Data(b"01234", None, None);
+        assert_eq!(payload.bits(Version::Micro(1)), Some(3 + 17));
+        let payload = Payload::Data(b"QR code", Some(&Mode::Byte), None);
+        assert_eq!(payload.bits(Version::Normal(1)), Some(4 + 8 + 56));
+        assert_eq!(payload.bits(Version::Micro(2)), None);
+        let payload = Payload::Data("QRコード".as_bytes(), None, None);
+        assert_eq!(payload.bits(Version::Normal(1)), Some(4 + 8 + 4 + 8 + 88));
+        let without_eci = Payload::Data("QRコード".as_bytes(), None, Some(Eci::None));
+        assert_eq!(without_eci.bits(Version::Normal(1)), Some(4 + 8 + 88));
+        assert_eq!(
+            payload.bits(Version::Micro(4)),
+            without_eci.bits(Version::Micro(4))
+        );
+        let payload = Payload::Length(8, &Mode::Numeric);
+        assert_eq!(payload.bits(Version::Normal(1)), Some(4 + 10 + 27));
+        assert_eq!(payload.bits(Version::Micro(1)), None);
+        let payload = Payload::Length(5, &Mode::Numeric);
+        assert_eq!(payload.bits(Version::Micro(1)), Some(3 + 17));
+    }
+
+    #[test]
+    fn compute_max_count() {
+        let bits = Bits::new(Version::Normal(1)).max_len(EcLevel::L).unwrap();
+        assert_eq!(
+            max_count(Version::Normal(1), bits, &Mode::Numeric),
+            Some(41)
+        );
+        assert_eq!(
+            max_count(Version::Normal(1), bits, &Mode::Alphanumeric),
+            Some(25)
+        );
+        assert_eq!(max_count(Version::Normal(1), bits, &Mode::Byte), Some(17));
+        assert_eq!(max_count(Version::Normal(1), bits, &Mode::Kanji), Some(10));
+        let bits = Bits::new(Version::Normal(40)).max_len(EcLevel::L).unwrap();
+        assert_eq!(
+            max_count(Version::Normal(40), bits, &Mode::Numeric),
+            Some(7089)
+        );
+        assert_eq!(
+            max_count(Version::Normal(40), bits, &Mode::Alphanumeric),
+            Some(4296)
+        );
+        assert_eq!(
+            max_count(Version::Normal(40), bits, &Mode::Byte),
+            Some(2953)
+        );
+        assert_eq!(
+            max_count(Version::Normal(40), bits, &Mode::Kanji),
+            Some(1817)
+        );
+        let bits = Bits::new(Version::Micro(1)).max_len(EcLevel::L).unwrap();
+        assert_eq!(max_count(Version::Micro(1), bits, &Mode::Numeric), Some(5));
+        assert_eq!(
+            max_count(Version::Micro(1), bits, &Mode::Alphanumeric),
+            None
+        );
+        let bits = Bits::new(Version::Micro(4)).max_len(EcLevel::L).unwrap();
+        assert_eq!(max_count(Version::Micro(4), bits, &Mode::Numeric), Some(35));
+        assert_eq!(max_count(Version::Micro(4), bits, &Mode::Byte), Some(15));
+        let version = Version::RectMicro(17, 139);
+        let bits = Bits::new(version).max_len(EcLevel::M).unwrap();
+        assert_eq!(max_count(version, bits, &Mode::Numeric), Some(361));
+        assert_eq!(max_count(version, bits, &Mode::Byte), Some(150));
+    }
+
+    #[test]
+    fn format_designation() {
+        assert_eq!(designation(Version::Normal(1)), "1");
+        assert_eq!(designation(Version::Micro(2)), "M2");
+        assert_eq!(designation(Version::RectMicro(13, 27)), "R13x27");
+    }
+
+    #[test]
+    fn compute_entries() {
+        let entries = list(&Variant::Auto, &[EcLevel::L, EcLevel::H], None);
+        assert_eq!(entries.len(), 40 * 2 + 4 + 32);
+        assert!(entries.iter().all(|entry| entry.remaining.is_none()));
+        let entries = list(&Variant::Micro, &[EcLevel::M], None);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.version)
+                .collect::<Vec<_>>(),
+            [Version::Micro(2), Version::Micro(3), Version::Micro(4)]
+        );
+        let entries = list(&Variant::Rmqr, &[EcLevel::Q], None);
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn find_smallest() {
+        let entries = list(
+            &Variant::Auto,
+            &[EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H],
+            Some(Payload::Data(b"01234567", None, None)),
+        );
+        let entry = smallest(&entries).unwrap();
+        assert_eq!(entry.version, Version::Micro(2));
+        assert_eq!(entry.level, EcLevel::M);
+        let entries = list(
+            &Variant::Normal,
+            &[EcLevel::H],
+            Some(Payload::Length(3000, &Mode::Byte)),
+        );
+        assert!(smallest(&entries).is_none());
+    }
+
+    #[test]
+    fn format_table() {
+        let entries = list(
+            &Variant::Micro,
+            &[EcLevel::L],
+            Some(Payload::Data(b"QR code", None, None)),
+        );
+        assert_eq!(
+            to_table(&entries),
+            concat!(
+                "Version  Level  Width  Height  Bits  Numeric  Alphanumeric  Byte  Kanji  Remaining\n",
+                "M1       L         11      11    20        5             -     -      -          -\n",
+                "M2       L         13      13    40       10             6     -      -          -\n",
+                "M3       L         15      15    84       23            14     9      6         23\n",
+                "M4       L         17      17   128       35            21    15      9         64\n"
+            )
+        );
+    }
+
+    #[test]
+    fn diagnose_data_too_long() {
+        let data = [b'a'; 30];
+        let measure = |version| Payload::Data(&data, None, None).bits(version);
+        let err = diagnose(measure, Version::Normal(1), EcLevel::M);
+        assert_eq!(err.excess, Some(4 + 8 + 240 - 128));
+        assert_eq!(
+            err.suggestions,
+            [Suggestion::Version(Version::RectMicro(15, 43))]
+        );
+        assert_eq!(
+            err.to_string(),
+            concat!(
+                "the data is 124 bits (16 bytes) over the capacity of version 1 at level M\n",
+                "hint: use rMQR code R15x43 with `--variant rmqr -v 15 43`"
+            )
+        );
+
+        let err = diagnose(measure, Version::Normal(2), EcLevel::H);
+        assert_eq!(
+            err.suggestions,
+            [
+                Suggestion::Level(EcLevel::L),
+                Suggestion::Version(Version::Normal(4))
+            ]
+        );
+
+        let measure = |version| Payload::Data(b"QR code", None, None).bits(version);
+        let err = diagnose(measure, Version::Micro(2), EcLevel::M);
+        assert_eq!(err.excess, None);
+        assert_eq!(
+            err.to_string(),
+            concat!(
+                "the data cannot be stored in version M2 at level M\n",
+                "hint: use Micro QR code version M3 with `--variant micro -v 3`"
+            )
+        );
+    }
+
+    #[test]
+    fn find_largest() {
+        assert_eq!(
+            largest(&Variant::Normal, EcLevel::H),
+            Some(Version::Normal(40))
+        );
+        assert_eq!(
+            largest(&Variant::Micro, EcLevel::M),
+            Some(Version::Micro(4))
+        );
+        assert_eq!(largest(&Variant::Micro, EcLevel::H), None);
+        assert_eq!(
+            largest(&Variant::Rmqr, EcLevel::M),
+            Some(Version::RectMicro(17, 139))
+        );
+        assert_eq!(
+            largest(&Variant::Auto, EcLevel::L),
+            Some(Version::Normal(40))
+        );
+    }
+
+    #[test]
+    fn format_suggestion() {
+        assert_eq!(
+            Suggestion::Level(EcLevel::L).to_string(),
+            "lower the error correction level to L with `-l l`"
+        );
+        assert_eq!(
+            Suggestion::Version(Version::RectMicro(13, 77)).to_string(),
+            "use rMQR code R13x77 with `--variant rmqr -v 13 77`"
+        );
+        assert_eq!(
+            Suggestion::StructuredAppend(2).to_string(),
+            "split the data into 2 symbols with `--structured-append`"
+        );
+    }
+}
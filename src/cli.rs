@@ -9,16 +9,27 @@ use std::{
     io::{self, Write},
     num::NonZeroU32,
     path::PathBuf,
+    str::FromStr,
 };
 
 use anyhow::anyhow;
-use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint, value_parser};
 use clap_complete::Generator;
 use csscolorparser::Color;
-#[cfg(any(feature = "decode-from-svg", feature = "decode-from-xbm"))]
+#[cfg(any(
+    feature = "decode-from-pdf",
+    feature = "decode-from-svg",
+    feature = "decode-from-xbm"
+))]
 use image::error::ImageFormatHint;
 use image::{ImageError, ImageFormat};
-use qrcode2::EcLevel;
+use qrcode2::{EcLevel, types};
+use serde::Serialize;
+
+use crate::{
+    eci,
+    payload::{epc, qr_bill},
+};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -35,6 +46,7 @@ pub struct Opt {
     pub command: Command,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Encode input data in a QR code.
@@ -47,16 +59,24 @@ pub enum Command {
     /// By default, the result will be output to standard output.
     Decode(Decode),
 
+    /// Show the capacity of each symbol.
+    ///
+    /// The result will be output to standard output.
+    Capacity(Capacity),
+
     /// Generate shell completion.
     ///
     /// The completion is output to standard output.
+    #[command(alias("c"))]
     Completion(Completion),
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands(true))]
 pub struct Encode {
     /// Output the result to a file.
-    #[arg(short, long, value_name("FILE"))]
+    #[arg(short, long, value_name("FILE"), global(true))]
     pub output: Option<PathBuf>,
 
     /// Read input data from a file.
@@ -73,7 +93,7 @@ pub struct Encode {
     ///
     /// If this option is not specified, the module size is 8 when the output
     /// format is PNG, SVG, EPS, or PIC, and 1 otherwise.
-    #[arg(short, long, value_name("NUMBER"))]
+    #[arg(short, long, value_name("NUMBER"), global(true))]
     pub size: Option<NonZeroU32>,
 
     /// Error correction level.
@@ -84,10 +104,18 @@ pub struct Encode {
         default_value_t,
         visible_alias("level"),
         value_name("LEVEL"),
-        ignore_case(true)
+        ignore_case(true),
+        global(true)
     )]
     pub error_correction_level: Ecc,
 
+    /// Raise the error correction level as high as possible without increasing
+    /// the version of the symbol.
+    ///
+    /// The level specified by '--error-correction-level' is the minimum level.
+    #[arg(long, conflicts_with("structured_append"), global(true))]
+    pub boost_ecc: bool,
+
     /// The version of the symbol.
     ///
     /// If this option is not specified, the minimum version required to store
@@ -102,15 +130,45 @@ pub struct Encode {
         long,
         visible_alias("symversion"),
         num_args(1..=2),
-        value_name("NUMBER")
+        value_name("NUMBER"),
+        global(true)
     )]
     pub symbol_version: Option<Vec<i16>>,
 
+    /// The minimum version of the symbol.
+    ///
+    /// The minimum version required to store the data is chosen from the
+    /// versions not smaller than this. <NUMBER> is specified in the same way as
+    /// '--symbol-version'. For rMQR code, the first <NUMBER> is the minimum
+    /// height and the second <NUMBER> is the minimum width. If the second
+    /// <NUMBER> is omitted, the width is not bounded.
+    #[arg(
+        long,
+        num_args(1..=2),
+        value_name("NUMBER"),
+        conflicts_with_all(["symbol_version", "structured_append"]),
+        global(true)
+    )]
+    pub min_version: Option<Vec<i16>>,
+
+    /// The maximum version of the symbol.
+    ///
+    /// If the data does not fit in this version, the encoding fails. <NUMBER>
+    /// is specified in the same way as '--min-version'.
+    #[arg(
+        long,
+        num_args(1..=2),
+        value_name("NUMBER"),
+        conflicts_with_all(["symbol_version", "structured_append"]),
+        global(true)
+    )]
+    pub max_version: Option<Vec<i16>>,
+
     /// The width of margin.
     ///
     /// If this option is not specified, the margin will be 4 for normal QR code
     /// and 2 for others.
-    #[arg(short, long, value_name("NUMBER"))]
+    #[arg(short, long, value_name("NUMBER"), global(true))]
     pub margin: Option<u32>,
 
     /// The format of the output.
@@ -120,7 +178,8 @@ pub struct Encode {
         value_enum,
         default_value_t,
         value_name("FORMAT"),
-        ignore_case(true)
+        ignore_case(true),
+        global(true)
     )]
     pub output_format: OutputFormat,
 
@@ -136,7 +195,8 @@ pub struct Encode {
         num_args(0..=1),
         value_name("LEVEL"),
         ignore_case(true),
-        default_missing_value("2")
+        default_missing_value("2"),
+        global(true)
     )]
     pub optimize_png: Option<PngOptimizationLevel>,
 
@@ -151,26 +211,115 @@ pub struct Encode {
         requires("optimize_png"),
         num_args(0..=1),
         value_name("ITERATION"),
-        default_missing_value("15")
+        default_missing_value("15"),
+        global(true)
     )]
     pub zopfli: Option<NonZeroU8>,
 
     /// The mode of the output.
     ///
     /// If this option is not specified, use the optimal encoding.
-    #[arg(long, value_enum, requires("symbol_version"), ignore_case(true))]
+    #[arg(
+        long,
+        value_enum,
+        requires("symbol_version"),
+        ignore_case(true),
+        global(true)
+    )]
     pub mode: Option<Mode>,
 
     /// The type of QR code.
+    ///
+    /// If "auto" is specified, the data is encoded in all types at the
+    /// specified error correction level, and the smallest symbol which can
+    /// store the data is chosen. '--symbol-version' cannot be used with "auto".
     #[arg(
         long,
         value_enum,
         default_value_t,
         value_name("TYPE"),
-        ignore_case(true)
+        ignore_case(true),
+        global(true)
     )]
     pub variant: Variant,
 
+    /// The dimension of the symbol to be minimized when "auto" is specified for
+    /// '--variant'.
+    ///
+    /// The size of the symbol includes the margin. If symbols have the same
+    /// size in <DIMENSION>, the one with the smaller area is chosen.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("DIMENSION"),
+        ignore_case(true),
+        global(true)
+    )]
+    pub prefer: Prefer,
+
+    /// The mask pattern reference of the symbol.
+    ///
+    /// <NUMBER> is between 0 and 7 for normal QR code, and between 0 and 3 for
+    /// Micro QR code. If this option is not specified, the mask pattern is
+    /// chosen by the encoder. This is not available for rMQR code and the
+    /// automatic type.
+    #[arg(long, value_name("NUMBER"), value_parser(value_parser!(u8).range(..=7)), global(true))]
+    pub mask: Option<u8>,
+
+    /// Split the input data into a Structured Append sequence if it does not
+    /// fit in a single symbol.
+    ///
+    /// The input data is split into up to 16 symbols, and each symbol is
+    /// written to a separate file named by appending the sequence number to the
+    /// stem of <FILE> specified by '--output' (e.g. "output-01.png"). This is
+    /// only available for normal QR code.
+    #[arg(long, requires("output"), global(true))]
+    pub structured_append: bool,
+
+    /// Encode the segments described in a file instead of input data.
+    ///
+    /// Each line of <FILE> consists of a mode ("numeric", "alphanumeric",
+    /// "byte", "kanji", or "eci") and the data separated by a space. Empty
+    /// lines and lines starting with "#" are ignored. If <FILE> starts with
+    /// "[", it is parsed as a JSON array of objects with "mode" and "data". The
+    /// data of "eci" is a character set as in '--eci'. The data of "kanji" is
+    /// converted to Shift JIS. The escape sequences "\\", "\n", "\r", "\t",
+    /// and "\xHH" can be used in the data. If "-" is specified, the
+    /// specification will be read from standard input.
+    #[arg(
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with_all(["input", "read_from", "mode", "eci", "structured_append"])
+    )]
+    pub segments: Option<PathBuf>,
+
+    /// The character set of the input data specified by the ECI designator.
+    ///
+    /// <CHARSET> takes a character set name (e.g. "utf-8", "iso-8859-1",
+    /// "shift_jis", "utf-16be", or "binary") or the ECI designator number. If
+    /// "none" is specified, no ECI designator is inserted. If this option is
+    /// not specified, the ECI designator for UTF-8 is inserted only if the
+    /// input data is a valid UTF-8 string that contains non-ASCII characters
+    /// and '--mode' is not specified. This is not available for Micro QR code.
+    #[allow(clippy::doc_markdown)]
+    #[arg(long, value_name("CHARSET"), global(true))]
+    pub eci: Option<Eci>,
+
+    /// Interpret the input data as a GS1 element string.
+    ///
+    /// The input data consists of application identifiers in parentheses
+    /// followed by their data (e.g. "(01)09501101530003(17)250101(10)ABC").
+    /// The application identifiers, the length and the characters of the data,
+    /// the check digits, and the dates are validated. The data is encoded in
+    /// FNC1 in first position mode, and the data of the application identifiers
+    /// which do not have a predefined length is terminated by a group separator
+    /// unless it is at the end. The data cannot contain "(". This is not
+    /// available for Micro QR code.
+    #[arg(long, conflicts_with_all(["mode", "eci", "segments", "structured_append"]))]
+    pub gs1: bool,
+
     /// Foreground color.
     ///
     /// <COLOR> takes a CSS color string. Colored output is only available when
@@ -178,7 +327,7 @@ pub struct Encode {
     /// lossy conversion may be performed depending on the color space supported
     /// by the method to specify a color, the color depth supported by the
     /// output format, etc.
-    #[arg(long, default_value("black"), value_name("COLOR"))]
+    #[arg(long, default_value("black"), value_name("COLOR"), global(true))]
     pub foreground: Color,
 
     /// Background color.
@@ -188,13 +337,13 @@ pub struct Encode {
     /// lossy conversion may be performed depending on the color space supported
     /// by the method to specify a color, the color depth supported by the
     /// output format, etc.
-    #[arg(long, default_value("white"), value_name("COLOR"))]
+    #[arg(long, default_value("white"), value_name("COLOR"), global(true))]
     pub background: Color,
 
     /// Also print the metadata.
     ///
     /// It is output to stderr.
-    #[arg(long)]
+    #[arg(long, global(true))]
     pub verbose: bool,
 
     /// Input data.
@@ -203,6 +352,497 @@ pub struct Encode {
     /// [STRING] must be a valid UTF-8 string.
     #[arg(value_name("STRING"))]
     pub input: Option<String>,
+
+    #[command(subcommand)]
+    pub payload: Option<Payload>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
+pub enum Payload {
+    /// Encode the credentials of a Wi-Fi network.
+    ///
+    /// The QR code can be scanned by a device to join the network.
+    Wifi(Wifi),
+
+    /// Encode contact information.
+    ///
+    /// The contact is encoded as a vCard or a MeCard.
+    #[allow(clippy::doc_markdown)]
+    Contact(Contact),
+
+    /// Encode a calendar event.
+    ///
+    /// The event is encoded as a VEVENT component of iCalendar, which can be
+    /// scanned by a device to add it to a calendar.
+    #[allow(clippy::doc_markdown)]
+    Event(Event),
+
+    /// Encode a SEPA credit transfer as an EPC QR code.
+    ///
+    /// The EPC QR code (EPC069-12) is also known as GiroCode. The error
+    /// correction level must be M, and the version must be 13 or lower.
+    #[allow(clippy::doc_markdown)]
+    #[command(visible_alias("girocode"))]
+    Epc(Epc),
+
+    /// Encode a merchant-presented payment as an EMV QR code.
+    ///
+    /// The payload consists of the fields in the TLV format of EMV QRCPS-MPM,
+    /// which is used by many payment schemes (e.g. Pix, PayNow, PromptPay).
+    /// The CRC is computed automatically.
+    #[allow(clippy::doc_markdown)]
+    Emv(Emv),
+
+    /// Encode a payment part of a Swiss QR-bill.
+    ///
+    /// The Swiss cross is drawn at the center of the symbol, and the symbol is
+    /// scaled to 46 x 46 mm. The error correction level must be M, the version
+    /// must be 25 or lower, and the output format must be PNG, SVG, or EPS.
+    QrBill(QrBill),
+
+    /// Encode a key for generating one-time passwords.
+    ///
+    /// The key is encoded as an otpauth URI, which can be scanned by an
+    /// authenticator app to provision two-factor authentication.
+    Otp(Otp),
+}
+
+#[derive(Args, Debug)]
+pub struct Wifi {
+    /// The SSID of the network.
+    ///
+    /// <SSID> must be between 1 and 32 bytes.
+    #[arg(long)]
+    pub ssid: String,
+
+    /// The password of the network.
+    ///
+    /// For WPA, <PASSWORD> must be between 8 and 63 ASCII characters, or 64
+    /// hexadecimal digits. For WPA3, <PASSWORD> must be at least 8 characters.
+    /// For WEP, <PASSWORD> must be 5 or 13 ASCII characters, or 10 or 26
+    /// hexadecimal digits. This cannot be specified for "nopass".
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// The security type of the network.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("TYPE"),
+        ignore_case(true)
+    )]
+    pub security: Security,
+
+    /// The network does not broadcast its SSID.
+    #[arg(long)]
+    pub hidden: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct Contact {
+    /// The name of the person.
+    #[arg(long, required_unless_present("input"))]
+    pub name: Option<String>,
+
+    /// The organization which the person belongs to.
+    #[arg(long, visible_aliases(["org", "organisation"]), value_name("NAME"))]
+    pub organization: Option<String>,
+
+    /// The telephone number.
+    ///
+    /// This option can be specified multiple times.
+    #[arg(long("phone"), value_name("NUMBER"))]
+    pub phones: Vec<String>,
+
+    /// The email address.
+    ///
+    /// This option can be specified multiple times.
+    #[arg(long("email"), value_name("ADDRESS"))]
+    pub emails: Vec<String>,
+
+    /// The postal address.
+    ///
+    /// This option can be specified multiple times.
+    #[arg(long("address"), value_name("ADDRESS"))]
+    pub addresses: Vec<String>,
+
+    /// The URL of the website.
+    ///
+    /// This option can be specified multiple times.
+    #[arg(long("url"), value_name("URL"))]
+    pub urls: Vec<String>,
+
+    /// The format of the payload.
+    ///
+    /// If "auto" is specified, the format which can be stored in the smaller
+    /// symbol at the specified error correction level is chosen from vCard 3.0
+    /// and MeCard.
+    #[allow(clippy::doc_markdown)]
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("FORMAT"),
+        ignore_case(true)
+    )]
+    pub format: ContactFormat,
+
+    /// Read the contact from a vCard file.
+    ///
+    /// Only the first vCard in [FILE] is read, and only the properties which
+    /// can be specified by the options are used. If "-" is specified, the
+    /// vCard will be read from standard input.
+    #[arg(
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with_all(["name", "organization", "phones", "emails", "addresses", "urls"])
+    )]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Event {
+    /// The summary of the event.
+    #[arg(long, value_name("TEXT"), required_unless_present("input"))]
+    pub summary: Option<String>,
+
+    /// The start of the event.
+    ///
+    /// <DATETIME> is a date (e.g. "2026-10-17") or a date-time (e.g.
+    /// "2026-10-17T09:00"). If a date-time ends with "Z", it is in UTC.
+    #[arg(long, value_name("DATETIME"), required_unless_present("input"))]
+    pub start: Option<String>,
+
+    /// The end of the event.
+    ///
+    /// <DATETIME> must be of the same type as the start, and must not be before
+    /// it.
+    #[arg(long, value_name("DATETIME"))]
+    pub end: Option<String>,
+
+    /// The time zone of the start and the end.
+    ///
+    /// <TZID> is a time zone identifier (e.g. "Europe/Zurich"). This can be
+    /// specified only if the start is a local date-time.
+    #[arg(long, value_name("TZID"))]
+    pub timezone: Option<String>,
+
+    /// The location of the event.
+    #[arg(long, value_name("TEXT"))]
+    pub location: Option<String>,
+
+    /// The description of the event.
+    #[arg(long, value_name("TEXT"))]
+    pub description: Option<String>,
+
+    /// Read the event from an iCalendar file.
+    ///
+    /// Only the first event in [FILE] is read, and only the properties which
+    /// can be specified by the options are used. If the payload does not fit
+    /// in the symbol, the description and the location are truncated or
+    /// removed. If "-" is specified, the event will be read from standard
+    /// input.
+    #[arg(
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with_all(["summary", "start", "end", "timezone", "location", "description"])
+    )]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Epc {
+    /// The name of the beneficiary.
+    ///
+    /// <NAME> must be at most 70 characters.
+    #[arg(long)]
+    pub name: String,
+
+    /// The IBAN of the account of the beneficiary.
+    ///
+    /// The spaces in <IBAN> are removed, and the check digits are validated.
+    #[arg(long)]
+    pub iban: String,
+
+    /// The BIC of the bank of the beneficiary.
+    #[arg(long)]
+    pub bic: Option<String>,
+
+    /// The amount in euros.
+    ///
+    /// <AMOUNT> must be between 0.01 and 999999999.99, with up to 2 decimal
+    /// places.
+    #[arg(long)]
+    pub amount: Option<String>,
+
+    /// The purpose code of the transfer.
+    ///
+    /// <CODE> is 4 uppercase letters (e.g. "GDDS").
+    #[arg(long, value_name("CODE"))]
+    pub purpose: Option<String>,
+
+    /// The structured creditor reference.
+    ///
+    /// <REFERENCE> must be a valid ISO 11649 creditor reference (e.g.
+    /// "RF18539007547034").
+    #[arg(long, conflicts_with("remittance"))]
+    pub reference: Option<String>,
+
+    /// The unstructured remittance information.
+    ///
+    /// <TEXT> must be at most 140 characters.
+    #[arg(long, value_name("TEXT"))]
+    pub remittance: Option<String>,
+
+    /// The information from the beneficiary to the originator.
+    ///
+    /// <TEXT> must be at most 70 characters.
+    #[arg(long, value_name("TEXT"))]
+    pub information: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct Emv {
+    /// The field to add.
+    ///
+    /// <ID> is the two-digit ID of the field, or the IDs separated by "." for
+    /// the field in a template (e.g. "26.00"). This is used for the merchant
+    /// account information (02 to 51), which is specific to each payment
+    /// scheme. This option can be specified multiple times.
+    #[arg(long("field"), value_name("ID=VALUE"))]
+    pub fields: Vec<String>,
+
+    /// The payment is dynamic.
+    ///
+    /// If this is not specified, the point of initiation method is static.
+    #[arg(long)]
+    pub dynamic: bool,
+
+    /// The merchant category code (ISO 18245).
+    #[arg(long, default_value("0000"), value_name("CODE"))]
+    pub merchant_category_code: String,
+
+    /// The transaction currency.
+    ///
+    /// <CODE> is the ISO 4217 numeric code (e.g. "986" for BRL).
+    #[arg(long, value_name("CODE"), required_unless_present("input"))]
+    pub currency: Option<String>,
+
+    /// The transaction amount.
+    #[arg(long)]
+    pub amount: Option<String>,
+
+    /// The country code of the merchant.
+    ///
+    /// <CODE> is the ISO 3166-1 alpha-2 code (e.g. "BR").
+    #[arg(long, value_name("CODE"), required_unless_present("input"))]
+    pub country: Option<String>,
+
+    /// The name of the merchant.
+    ///
+    /// <NAME> must be at most 25 characters.
+    #[arg(long, value_name("NAME"), required_unless_present("input"))]
+    pub merchant_name: Option<String>,
+
+    /// The city of the merchant.
+    ///
+    /// <CITY> must be at most 15 characters.
+    #[arg(long, value_name("CITY"), required_unless_present("input"))]
+    pub merchant_city: Option<String>,
+
+    /// The postal code of the merchant.
+    #[arg(long, value_name("CODE"))]
+    pub postal_code: Option<String>,
+
+    /// The reference label of the transaction.
+    #[arg(long, value_name("LABEL"))]
+    pub reference: Option<String>,
+
+    /// Read the fields from a JSON file.
+    ///
+    /// [FILE] contains a JSON object whose keys are the IDs of the fields and
+    /// whose values are strings, or nested objects for templates. If "-" is
+    /// specified, the fields will be read from standard input.
+    #[arg(
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with_all([
+            "fields",
+            "dynamic",
+            "merchant_category_code",
+            "currency",
+            "amount",
+            "country",
+            "merchant_name",
+            "merchant_city",
+            "postal_code",
+            "reference"
+        ])
+    )]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct QrBill {
+    /// The IBAN or the QR-IBAN of the account of the creditor.
+    ///
+    /// <IBAN> must be a Swiss or Liechtenstein IBAN. The spaces in <IBAN> are
+    /// removed, and the check digits are validated.
+    #[arg(long, value_name("IBAN"))]
+    pub account: String,
+
+    /// The name of the creditor.
+    ///
+    /// <NAME> must be at most 70 characters.
+    #[arg(long, value_name("NAME"))]
+    pub creditor_name: String,
+
+    /// The street of the creditor.
+    #[arg(long, value_name("STREET"))]
+    pub creditor_street: Option<String>,
+
+    /// The building number of the creditor.
+    #[arg(long, value_name("NUMBER"))]
+    pub creditor_building_number: Option<String>,
+
+    /// The postal code of the creditor.
+    #[arg(long, value_name("CODE"))]
+    pub creditor_postal_code: String,
+
+    /// The town of the creditor.
+    #[arg(long, value_name("TOWN"))]
+    pub creditor_town: String,
+
+    /// The country of the creditor.
+    ///
+    /// <CODE> is an ISO 3166-1 alpha-2 country code.
+    #[arg(long, default_value("CH"), value_name("CODE"))]
+    pub creditor_country: String,
+
+    /// The amount.
+    ///
+    /// <AMOUNT> must be between 0.01 and 999999999.99, with up to 2 decimal
+    /// places. If this is not specified, the debtor enters the amount.
+    #[arg(long)]
+    pub amount: Option<String>,
+
+    /// The currency of the amount.
+    #[arg(long, value_enum, default_value_t, ignore_case(true))]
+    pub currency: Currency,
+
+    /// The name of the ultimate debtor.
+    ///
+    /// <NAME> must be at most 70 characters.
+    #[arg(
+        long,
+        value_name("NAME"),
+        requires_all(["debtor_postal_code", "debtor_town"])
+    )]
+    pub debtor_name: Option<String>,
+
+    /// The street of the ultimate debtor.
+    #[arg(long, value_name("STREET"), requires("debtor_name"))]
+    pub debtor_street: Option<String>,
+
+    /// The building number of the ultimate debtor.
+    #[arg(long, value_name("NUMBER"), requires("debtor_name"))]
+    pub debtor_building_number: Option<String>,
+
+    /// The postal code of the ultimate debtor.
+    #[arg(long, value_name("CODE"), requires("debtor_name"))]
+    pub debtor_postal_code: Option<String>,
+
+    /// The town of the ultimate debtor.
+    #[arg(long, value_name("TOWN"), requires("debtor_name"))]
+    pub debtor_town: Option<String>,
+
+    /// The country of the ultimate debtor.
+    ///
+    /// <CODE> is an ISO 3166-1 alpha-2 country code.
+    #[arg(long, default_value("CH"), value_name("CODE"))]
+    pub debtor_country: String,
+
+    /// The reference.
+    ///
+    /// If the account is a QR-IBAN, <REFERENCE> must be a QR reference, which
+    /// is 27 digits with a check digit. Otherwise, <REFERENCE> must be a valid
+    /// ISO 11649 creditor reference (e.g. "RF18539007547034"). The spaces in
+    /// <REFERENCE> are removed.
+    #[arg(long)]
+    pub reference: Option<String>,
+
+    /// The unstructured message.
+    ///
+    /// <TEXT> must be at most 140 characters.
+    #[arg(long, value_name("TEXT"))]
+    pub message: Option<String>,
+
+    /// The structured billing information.
+    ///
+    /// <TEXT> must start with "//", and it must be at most 140 characters
+    /// together with the message.
+    #[arg(long, value_name("TEXT"))]
+    pub billing_information: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct Otp {
+    /// The provider or service which the account belongs to.
+    ///
+    /// <ISSUER> must not contain a colon.
+    #[arg(long)]
+    pub issuer: Option<String>,
+
+    /// The name of the account (e.g. the email address).
+    ///
+    /// <NAME> must not contain a colon.
+    #[arg(long, value_name("NAME"))]
+    pub account: String,
+
+    /// The shared secret encoded in Base32.
+    ///
+    /// <SECRET> is case-insensitive, and the spaces and the padding are
+    /// ignored.
+    #[arg(long, required_unless_present("generate_secret"))]
+    pub secret: Option<String>,
+
+    /// Generate a random 160-bit secret.
+    ///
+    /// The secret is generated by the random number generator of the operating
+    /// system, and it is output to stderr in Base32.
+    #[arg(long, conflicts_with("secret"))]
+    pub generate_secret: bool,
+
+    /// The hash algorithm.
+    #[arg(long, value_enum, default_value_t, ignore_case(true))]
+    pub algorithm: OtpAlgorithm,
+
+    /// The number of digits of the password.
+    #[arg(
+        long,
+        default_value("6"),
+        value_name("NUMBER"),
+        value_parser(value_parser!(u8).range(6..=8))
+    )]
+    pub digits: u8,
+
+    /// The period of TOTP in seconds.
+    #[arg(
+        long,
+        default_value("30"),
+        value_name("SECONDS"),
+        value_parser(value_parser!(u64).range(1..)),
+        conflicts_with("counter")
+    )]
+    pub period: u64,
+
+    /// The initial counter of HOTP.
+    ///
+    /// If this is specified, the key is for HOTP instead of TOTP.
+    #[arg(long, value_name("NUMBER"))]
+    pub counter: Option<u64>,
 }
 
 #[derive(Args, Debug)]
@@ -232,16 +872,168 @@ pub struct Decode {
     #[arg(long)]
     pub metadata: bool,
 
-    /// Input image file.
+    /// The encoding of the output.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("ENCODING"),
+        ignore_case(true)
+    )]
+    pub output_encoding: OutputEncoding,
+
+    /// Parse the decoded data and print the extracted fields.
+    ///
+    /// The data is classified as a URL, Wi-Fi network, vCard, MeCard, geo URI,
+    /// tel URI, email, SMS, otpauth URI, EPC QR code, EMV QR code, GS1 element
+    /// string, or plain text, and the fields are validated. If <FORMAT> is
+    /// omitted, the fields are printed as text.
+    #[allow(clippy::doc_markdown)]
+    #[arg(
+        long,
+        value_enum,
+        num_args(0..=1),
+        require_equals(true),
+        default_missing_value("text"),
+        value_name("FORMAT"),
+        ignore_case(true),
+        conflicts_with_all(["metadata", "output_encoding"])
+    )]
+    pub parse: Option<ParseFormat>,
+
+    /// Print the decoded symbols in the machine-readable format.
+    ///
+    /// An object is output for each symbol, with the decoded data as a UTF-8
+    /// string or Base64, the metadata, the mask pattern, the location and
+    /// geometry of the symbol, the input file, and whether the image was
+    /// inverted.
+    #[arg(
+        long,
+        value_enum,
+        value_name("FORMAT"),
+        ignore_case(true),
+        conflicts_with_all(["verbose", "metadata", "parse"])
+    )]
+    pub format: Option<DecodeFormat>,
+
+    /// Draw the outlines of the detected symbols onto a copy of the input
+    /// image and write it to <FILE> as PNG.
+    ///
+    /// The top-left corner of each symbol is marked with a filled square. If
+    /// multiple input images are specified, or if the input contains multiple
+    /// frames, pages or entries, each of them is written separately and its
+    /// number is appended to the stem of <FILE> (e.g. "output-01.png"). The
+    /// images are numbered consecutively across all input images.
+    #[arg(long, value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub annotate: Option<PathBuf>,
+
+    /// The resolution in DPI at which the pages of PDF are rasterized.
+    #[cfg(feature = "decode-from-pdf")]
+    #[arg(
+        long,
+        default_value("150"),
+        value_name("DPI"),
+        value_parser(value_parser!(u16).range(1..=1200))
+    )]
+    pub dpi: u16,
+
+    /// Decode the raster images embedded in the pages of PDF instead of
+    /// rasterizing the pages.
+    ///
+    /// Each image is decoded at its own resolution, so this is faster and more
+    /// reliable than rasterizing if the symbols are embedded as images. Symbols
+    /// drawn as vector graphics are not found.
+    #[cfg(feature = "decode-from-pdf")]
+    #[arg(long, conflicts_with("dpi"))]
+    pub extract_images: bool,
+
+    /// Input image files.
     ///
     /// If [IMAGE] is not specified, or if "-" is specified, the image will be
     /// read from standard input. Supported raster image formats are based on
     /// the formats supported by the image crate. The format of [IMAGE] is
     /// determined based on the extension or the magic number if possible. If
     /// the format cannot be determined, use '--type'. Note that the SVG image
-    /// is rasterized before scanning.
+    /// and the pages of PDF are rasterized before scanning. If multiple images
+    /// are specified, the symbols of Structured Append found in them are
+    /// reassembled in order.
     #[arg(value_name("IMAGE"), value_hint(ValueHint::FilePath))]
-    pub input: Option<PathBuf>,
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Capacity {
+    /// Error correction level.
+    ///
+    /// If this option is not specified, all levels are shown.
+    #[arg(
+        short('l'),
+        long,
+        value_enum,
+        visible_alias("level"),
+        value_name("LEVEL"),
+        ignore_case(true)
+    )]
+    pub error_correction_level: Option<Ecc>,
+
+    /// The type of QR code.
+    ///
+    /// If "auto" is specified, all types are shown.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Variant::Auto,
+        value_name("TYPE"),
+        ignore_case(true)
+    )]
+    pub variant: Variant,
+
+    /// The mode used to encode the payload.
+    ///
+    /// If this option is not specified, use the optimal encoding.
+    #[arg(long, value_enum, ignore_case(true))]
+    pub mode: Option<Mode>,
+
+    /// The length of the payload.
+    ///
+    /// <NUMBER> is the number of characters in the mode specified by '--mode'.
+    /// For byte mode, this is the number of bytes.
+    #[arg(
+        long,
+        value_name("NUMBER"),
+        requires("mode"),
+        conflicts_with_all(["input", "read_from"])
+    )]
+    pub length: Option<usize>,
+
+    /// Read the payload from a file.
+    #[arg(
+        short,
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with("input")
+    )]
+    pub read_from: Option<PathBuf>,
+
+    /// The format of the output.
+    #[arg(
+        short('t'),
+        long("type"),
+        value_enum,
+        default_value_t,
+        value_name("FORMAT"),
+        ignore_case(true)
+    )]
+    pub output_format: CapacityFormat,
+
+    /// Payload.
+    ///
+    /// If a payload is specified, the number of remaining bits in each symbol
+    /// and the smallest symbol which can store the payload are also shown.
+    /// [STRING] must be a valid UTF-8 string.
+    #[arg(value_name("STRING"))]
+    pub input: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -251,6 +1043,27 @@ pub struct Completion {
     pub shell: Shell,
 }
 
+/// Validates the arguments for the payment payload, which must be stored in a
+/// single normal QR code at the error correction level M.
+fn validate_payment(arg: &Encode, name: &str, max_version: i16) -> anyhow::Result<()> {
+    if arg.error_correction_level != Ecc::M || arg.boost_ecc {
+        return Err(anyhow!("{name} requires the error correction level M"));
+    }
+    if arg.variant != Variant::Normal || arg.structured_append {
+        return Err(anyhow!("{name} must be a single normal QR code"));
+    }
+    let exceeds_max_version = [&arg.symbol_version, &arg.min_version, &arg.max_version]
+        .into_iter()
+        .flatten()
+        .any(|version| version[0] > max_version);
+    if exceeds_max_version {
+        return Err(anyhow!(
+            "{name} requires the version {max_version} or lower"
+        ));
+    }
+    Ok(())
+}
+
 impl Opt {
     /// Validates arguments.
     pub fn validate(self) -> anyhow::Result<Self> {
@@ -275,6 +1088,61 @@ impl Opt {
                     "foreground and/or background colors cannot be changed"
                 ));
             }
+            if arg.structured_append && (arg.variant != Variant::Normal) {
+                return Err(anyhow!(
+                    "Structured Append is only available for normal QR code"
+                ));
+            }
+            if matches!(arg.eci, Some(Eci::Designator(_))) && (arg.variant == Variant::Micro) {
+                return Err(anyhow!("ECI is not available for Micro QR code"));
+            }
+            if arg.symbol_version.is_some() && (arg.variant == Variant::Auto) {
+                return Err(anyhow!(
+                    "symbol version cannot be specified when the type of QR code is auto"
+                ));
+            }
+            if (arg.min_version.is_some() || arg.max_version.is_some())
+                && (arg.variant == Variant::Auto)
+            {
+                return Err(anyhow!(
+                    "version range cannot be specified when the type of QR code is auto"
+                ));
+            }
+            if let Some(mask) = arg.mask {
+                match arg.variant {
+                    Variant::Micro if mask > 3 => {
+                        return Err(anyhow!(
+                            "mask pattern must be between 0 and 3 for Micro QR code"
+                        ));
+                    }
+                    Variant::Rmqr | Variant::Auto => {
+                        return Err(anyhow!(
+                            "mask pattern can only be specified for normal QR code and Micro QR code"
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            if arg.gs1 && (arg.variant == Variant::Micro) {
+                return Err(anyhow!("GS1 is not available for Micro QR code"));
+            }
+            match arg.payload {
+                Some(Payload::Epc(_)) => {
+                    validate_payment(arg, "EPC QR code", epc::MAX_VERSION)?;
+                }
+                Some(Payload::QrBill(_)) => {
+                    validate_payment(arg, "Swiss QR-bill", qr_bill::MAX_VERSION)?;
+                    if !matches!(
+                        arg.output_format,
+                        OutputFormat::Png | OutputFormat::Svg | OutputFormat::Eps
+                    ) {
+                        return Err(anyhow!(
+                            "Swiss QR-bill can only be output as PNG, SVG, or EPS"
+                        ));
+                    }
+                }
+                _ => {}
+            }
         }
         Ok(self)
     }
@@ -338,7 +1206,7 @@ impl Generator for Shell {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, ValueEnum)]
 pub enum Ecc {
     /// Level L.
     ///
@@ -446,7 +1314,7 @@ pub enum OutputFormat {
 }
 
 #[cfg(feature = "optimize-output-png")]
-#[derive(Clone, Debug, ValueEnum)]
+#[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum PngOptimizationLevel {
     /// Level 0.
     ///
@@ -488,6 +1356,16 @@ impl From<PngOptimizationLevel> for u8 {
     }
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum CapacityFormat {
+    /// A table.
+    #[default]
+    Table,
+
+    /// JSON.
+    Json,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, ValueEnum)]
 pub enum Mode {
     /// All digits.
@@ -503,7 +1381,19 @@ pub enum Mode {
     Kanji,
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+impl From<&Mode> for types::Mode {
+    fn from(mode: &Mode) -> Self {
+        match mode {
+            Mode::Numeric => Self::Numeric,
+            Mode::Alphanumeric => Self::Alphanumeric,
+            Mode::Byte => Self::Byte,
+            Mode::Kanji => Self::Kanji,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum Variant {
     /// Normal QR code.
     #[default]
@@ -514,6 +1404,143 @@ pub enum Variant {
 
     /// rMQR code.
     Rmqr,
+
+    /// The smallest symbol among normal QR code, Micro QR code, and rMQR code.
+    Auto,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Security {
+    /// WPA or WPA2 Personal.
+    #[default]
+    Wpa,
+
+    /// WPA3 Personal only.
+    Wpa3,
+
+    /// WEP.
+    Wep,
+
+    /// No password.
+    Nopass,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[allow(clippy::doc_markdown)]
+pub enum ContactFormat {
+    /// vCard 3.0 or MeCard, whichever can be stored in the smaller symbol.
+    #[default]
+    Auto,
+
+    /// vCard 3.0.
+    #[value(name("vcard3"))]
+    Vcard3,
+
+    /// vCard 4.0.
+    #[value(name("vcard4"))]
+    Vcard4,
+
+    /// MeCard.
+    Mecard,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Currency {
+    /// Swiss franc.
+    #[default]
+    Chf,
+
+    /// Euro.
+    Eur,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OtpAlgorithm {
+    /// SHA-1.
+    #[default]
+    Sha1,
+
+    /// SHA-256.
+    Sha256,
+
+    /// SHA-512.
+    Sha512,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ParseFormat {
+    /// Human-readable text.
+    Text,
+
+    /// JSON.
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum DecodeFormat {
+    /// A JSON array of the symbols.
+    Json,
+
+    /// JSON Lines. Each symbol is output on a separate line.
+    Jsonl,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Prefer {
+    /// The smallest area.
+    #[default]
+    Area,
+
+    /// The smallest height.
+    Height,
+
+    /// The smallest width.
+    Width,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OutputEncoding {
+    /// Transcode the decoded data to UTF-8.
+    ///
+    /// The character set is determined by the ECI designator. If there is no
+    /// ECI designator, it is guessed from UTF-8, Shift_JIS, and ISO-8859-1.
+    /// Binary data and unsupported character sets are output as is.
+    #[allow(clippy::doc_markdown)]
+    #[value(name = "utf-8", alias("utf8"))]
+    Utf8,
+
+    /// Output the decoded data as is.
+    #[default]
+    Raw,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Eci {
+    /// Do not insert the ECI designator.
+    None,
+
+    /// Insert the ECI designator.
+    Designator(u32),
+}
+
+impl FromStr for Eci {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("none") {
+            return Ok(Self::None);
+        }
+        if let Some(designator) = eci::designator(s) {
+            return Ok(Self::Designator(designator));
+        }
+        match s.parse() {
+            Ok(designator) if designator <= eci::MAX_DESIGNATOR => Ok(Self::Designator(designator)),
+            _ => Err(anyhow!(
+                "expected a character set name or a number between 0 and {}",
+                eci::MAX_DESIGNATOR
+            )),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -554,6 +1581,12 @@ pub enum InputFormat {
     #[cfg(feature = "decode-from-exr")]
     OpenExr,
 
+    /// Portable Document Format.
+    ///
+    /// Each page is rasterized before scanning.
+    #[cfg(feature = "decode-from-pdf")]
+    Pdf,
+
     /// Portable Network Graphics.
     Png,
 
@@ -609,6 +1642,8 @@ impl TryFrom<InputFormat> for ImageFormat {
             InputFormat::Jpeg => Ok(Self::Jpeg),
             #[cfg(feature = "decode-from-exr")]
             InputFormat::OpenExr => Ok(Self::OpenExr),
+            #[cfg(feature = "decode-from-pdf")]
+            InputFormat::Pdf => Err(Self::Error::Unsupported(ImageFormatHint::Unknown.into())),
             InputFormat::Png => Ok(Self::Png),
             #[cfg(feature = "decode-from-pnm")]
             InputFormat::Pnm => Ok(Self::Pnm),
@@ -698,6 +1733,33 @@ mod tests {
         assert_eq!(Variant::default(), Variant::Normal);
     }
 
+    #[test]
+    fn default_prefer() {
+        assert_eq!(Prefer::default(), Prefer::Area);
+    }
+
+    #[test]
+    fn default_output_encoding() {
+        assert_eq!(OutputEncoding::default(), OutputEncoding::Raw);
+    }
+
+    #[test]
+    fn from_str_to_eci() {
+        assert_eq!(Eci::from_str("none").unwrap(), Eci::None);
+        assert_eq!(Eci::from_str("NONE").unwrap(), Eci::None);
+        assert_eq!(Eci::from_str("utf-8").unwrap(), Eci::Designator(26));
+        assert_eq!(Eci::from_str("ISO-8859-1").unwrap(), Eci::Designator(3));
+        assert_eq!(Eci::from_str("iso-8859-15").unwrap(), Eci::Designator(17));
+        assert_eq!(Eci::from_str("shift_jis").unwrap(), Eci::Designator(20));
+        assert_eq!(Eci::from_str("utf-16be").unwrap(), Eci::Designator(25));
+        assert_eq!(Eci::from_str("binary").unwrap(), Eci::Designator(899));
+        assert_eq!(Eci::from_str("0").unwrap(), Eci::Designator(0));
+        assert_eq!(Eci::from_str("999999").unwrap(), Eci::Designator(999_999));
+        assert!(Eci::from_str("1000000").is_err());
+        assert!(Eci::from_str("-1").is_err());
+        assert!(Eci::from_str("utf-32").is_err());
+    }
+
     #[test]
     fn try_from_input_format_to_image_format() {
         #[cfg(feature = "decode-from-bmp")]
@@ -754,6 +1816,8 @@ mod tests {
             ImageFormat::try_from(InputFormat::Qoi).unwrap(),
             ImageFormat::Qoi
         );
+        #[cfg(feature = "decode-from-pdf")]
+        assert!(ImageFormat::try_from(InputFormat::Pdf).is_err());
         #[cfg(feature = "decode-from-svg")]
         assert!(ImageFormat::try_from(InputFormat::Svg).is_err());
         #[cfg(feature = "decode-from-tga")]
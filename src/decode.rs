@@ -2,20 +2,357 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-#[cfg(feature = "decode-from-svg")]
+use std::{
+    error::Error,
+    fmt,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(feature = "decode-from-pdf", feature = "decode-from-svg"))]
 use anyhow::Context;
-#[cfg(feature = "decode-from-svg")]
-use image::{DynamicImage, ImageFormat};
+use base64::{Engine, engine::general_purpose::STANDARD};
+#[cfg(feature = "decode-from-pdf")]
+use hayro::{
+    PixmapSettings, RenderCache, RenderSettings,
+    hayro_interpret::{
+        BlendMode, ClipPath, Device, DrawMode, DrawProps, ImageData, ImageDrawProps,
+        InterpreterCache, InterpreterSettings, SoftMask, TransformExt, font::GlyphRun,
+    },
+    hayro_syntax::Pdf,
+    kurbo::{BezPath, Rect},
+    vello_cpu::color::palette::css::WHITE,
+};
+#[cfg(feature = "decode-from-gif")]
+use image::codecs::gif::GifDecoder;
+#[cfg(feature = "decode-from-webp")]
+use image::codecs::webp::WebPDecoder;
+use image::{
+    AnimationDecoder, DynamicImage, Frames, ImageFormat, ImageResult, Rgba, RgbaImage,
+    codecs::png::PngDecoder,
+};
+#[cfg(any(feature = "decode-from-pdf", feature = "decode-from-tiff"))]
+use image::{GrayImage, RgbImage};
+#[cfg(feature = "decode-from-tiff")]
+use image::{
+    ImageBuffer, ImageError, Luma, LumaA, Rgb, Rgb32FImage, Rgba32FImage,
+    error::{DecodingError, UnsupportedError, UnsupportedErrorKind},
+};
 #[cfg(feature = "decode-from-svg")]
 use resvg::{
     tiny_skia::{Pixmap, Transform},
     usvg::{Options, Tree},
 };
 use rqrr::{BitGrid, DeQRError, Grid, MetaData};
+use serde::Serialize;
+#[cfg(feature = "decode-from-tiff")]
+use tiff::{
+    ColorType, TiffError,
+    decoder::{Decoder as TiffDecoder, DecodingResult},
+};
+
+use crate::{
+    bitstream::{self, Eci, StructuredAppend},
+    eci, gs1,
+    metadata::{self, Extractor, Fnc1, Metadata},
+};
+
+/// The decoded symbol.
+#[derive(Debug)]
+pub struct DecodedBytes {
+    /// The metadata of the symbol.
+    pub metadata: MetaData,
+
+    /// The Structured Append header of the symbol, if any.
+    pub structured_append: Option<StructuredAppend>,
+
+    /// The ECI designators in the symbol.
+    pub eci: Vec<Eci>,
+
+    /// The FNC1 mode of the symbol, if any.
+    pub fnc1: Option<Fnc1>,
+
+    /// The decoded data.
+    pub data: Vec<u8>,
+
+    /// The location and geometry of the symbol in the image.
+    pub geometry: Geometry,
+
+    /// The file from which the symbol was decoded, or [`None`] if it was read
+    /// from standard input.
+    pub source: Option<PathBuf>,
+
+    /// Whether the symbol was decoded from the inverted image.
+    pub inverted: bool,
+
+    /// The frame, page, or entry of the image in which the symbol was found,
+    /// if the image contains multiple images.
+    pub subimage: Option<Subimage>,
+}
+
+/// One of the multiple images contained in an image file.
+///
+/// The index starts from 0.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Subimage {
+    /// A frame of the animated GIF, APNG, or animated WebP.
+    Frame(usize),
+
+    /// A page of the multi-page TIFF.
+    Page(usize),
+
+    /// An entry of the ICO or CUR.
+    Entry(usize),
+}
+
+impl Subimage {
+    /// Returns the constructor of the subimage of the image format.
+    pub fn kind(format: ImageFormat) -> fn(usize) -> Self {
+        match format {
+            ImageFormat::Tiff => Self::Page,
+            ImageFormat::Ico => Self::Entry,
+            _ => Self::Frame,
+        }
+    }
+}
+
+impl fmt::Display for Subimage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Frame(index) => write!(f, "Frame: {index}"),
+            Self::Page(index) => write!(f, "Page: {index}"),
+            Self::Entry(index) => write!(f, "Entry: {index}"),
+        }
+    }
+}
+
+/// The location and geometry of a symbol in the image.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Geometry {
+    /// The corners of the symbol, in the order of top-left, top-right,
+    /// bottom-right, and bottom-left of the symbol.
+    pub corners: [(i32, i32); 4],
+
+    /// The estimated size of a module in pixels.
+    pub module_size: f64,
 
-use crate::metadata::{self, Extractor, Metadata};
+    /// The clockwise rotation angle of the symbol in degrees.
+    pub rotation: f64,
+
+    /// Whether the symbol is mirrored.
+    pub mirrored: bool,
+}
+
+impl Geometry {
+    /// Computes the geometry from the bounds of the grid of `size` modules.
+    ///
+    /// If the grid was read as mirrored, the top-right and the bottom-left
+    /// corners of the bounds are swapped, and the rotation is measured after
+    /// flipping the symbol horizontally.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn new(bounds: [(i32, i32); 4], size: usize, mirrored: bool) -> Self {
+        let round = |value: f64| (value * 100.0).round() / 100.0;
+
+        // NOTE: rqrr extends the bounds by one module beyond the right and the bottom
+        // of the grid, so the corners of the symbol are interpolated.
+        let t = size as f64 / (size + 1) as f64;
+        let point = |u: f64, v: f64| {
+            let [p0, p1, p2, p3] = bounds.map(|(x, y)| (f64::from(x), f64::from(y)));
+            let lerp = |a: f64, b: f64, c: f64, d: f64| {
+                (1.0 - v).mul_add((1.0 - u).mul_add(a, u * b), v * (1.0 - u).mul_add(d, u * c))
+            };
+            (
+                lerp(p0.0, p1.0, p2.0, p3.0).round() as i32,
+                lerp(p0.1, p1.1, p2.1, p3.1).round() as i32,
+            )
+        };
+        let (top_left, bottom_right) = (point(0.0, 0.0), point(t, t));
+        let (top_right, bottom_left) = if mirrored {
+            (point(0.0, t), point(t, 0.0))
+        } else {
+            (point(t, 0.0), point(0.0, t))
+        };
+        let corners = [top_left, top_right, bottom_right, bottom_left];
+
+        let sides = (0..4).map(|i| {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            f64::from(b.0 - a.0).hypot(f64::from(b.1 - a.1))
+        });
+        let module_size = sides.sum::<f64>() / 4.0 / size as f64;
+        let (dx, dy) = (top_right.0 - top_left.0, top_right.1 - top_left.1);
+        let dx = if mirrored { -dx } else { dx };
+        let rotation = round(f64::from(dy).atan2(f64::from(dx)).to_degrees()).rem_euclid(360.0);
+        Self {
+            corners,
+            module_size: round(module_size),
+            rotation,
+            mirrored,
+        }
+    }
+}
+
+/// The decoded data of a symbol.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Payload {
+    /// The data is valid UTF-8.
+    Text(String),
+
+    /// The data is not valid UTF-8, and is encoded in Base64.
+    Base64(String),
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(data: Vec<u8>) -> Self {
+        String::from_utf8(data).map_or_else(
+            |err| Self::Base64(STANDARD.encode(err.into_bytes())),
+            Self::Text,
+        )
+    }
+}
+
+/// The position of a symbol in a Structured Append sequence.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct Sequence {
+    /// The position of the symbol, starting from 1.
+    pub position: usize,
+
+    /// The total number of symbols in the sequence.
+    pub count: usize,
+
+    /// The decoded data of the whole sequence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Payload>,
+}
+
+/// A decoded symbol in machine-readable form.
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    /// The file from which the symbol was decoded.
+    pub source: Option<&'a Path>,
+
+    /// Whether the symbol was decoded from the inverted image.
+    pub inverted: bool,
+
+    /// The frame, page, or entry of the image.
+    #[serde(flatten)]
+    pub subimage: Option<Subimage>,
+
+    /// The decoded data.
+    pub payload: Payload,
+
+    /// The metadata of the symbol.
+    pub metadata: Metadata,
+
+    /// The mask pattern of the symbol.
+    pub mask: u16,
+
+    /// The ECI designators in the symbol.
+    pub eci: Vec<u32>,
+
+    /// The position of the symbol in a Structured Append sequence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<Sequence>,
+
+    /// The location and geometry of the symbol in the image.
+    #[serde(flatten)]
+    pub geometry: Geometry,
+}
+
+impl<'a> Record<'a> {
+    /// Constructs a new record from the decoded symbol and its data.
+    pub fn new(content: &'a DecodedBytes, data: Vec<u8>) -> Self {
+        Self {
+            source: content.source.as_deref(),
+            inverted: content.inverted,
+            subimage: content.subimage,
+            payload: data.into(),
+            metadata: content.metadata(),
+            mask: content.metadata.mask,
+            eci: content.eci.iter().map(|eci| eci.designator).collect(),
+            sequence: content.structured_append.map(|sa| Sequence {
+                position: sa.index + 1,
+                count: sa.count,
+                payload: None,
+            }),
+            geometry: content.geometry,
+        }
+    }
+
+    /// Sets the decoded data of the whole Structured Append sequence which the
+    /// symbol belongs to.
+    ///
+    /// This does nothing if the symbol is not a part of a Structured Append
+    /// sequence.
+    #[must_use]
+    pub fn with_sequence_payload(mut self, data: Vec<u8>) -> Self {
+        if let Some(sequence) = self.sequence.as_mut() {
+            sequence.payload = Some(data.into());
+        }
+        self
+    }
+}
 
-type DecodedBytes = (MetaData, Vec<u8>);
+/// An error which can be returned when reassembling Structured Append
+/// sequences.
+#[derive(Debug, Eq, PartialEq)]
+pub enum StructuredAppendError {
+    /// Some symbols of the sequence were not found.
+    MissingSymbols {
+        /// The total number of symbols in the sequence.
+        count: usize,
+
+        /// The positions of the missing symbols, starting from 1.
+        missing: Vec<usize>,
+    },
+
+    /// The same symbol of the sequence was found more than once.
+    DuplicatedSymbol {
+        /// The total number of symbols in the sequence.
+        count: usize,
+
+        /// The position of the duplicated symbol, starting from 1.
+        position: usize,
+    },
+
+    /// The parity data does not match the reassembled data.
+    ParityMismatch {
+        /// The parity data stored in the symbols.
+        expected: u8,
+
+        /// The parity data computed from the reassembled data.
+        actual: u8,
+    },
+}
+
+impl fmt::Display for StructuredAppendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSymbols { count, missing } => {
+                let missing = missing
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "Structured Append sequence of {count} symbols is missing symbol(s) {missing}"
+                )
+            }
+            Self::DuplicatedSymbol { count, position } => write!(
+                f,
+                "symbol {position} of Structured Append sequence of {count} symbols was found more than once"
+            ),
+            Self::ParityMismatch { expected, actual } => write!(
+                f,
+                "Structured Append parity mismatch (expected {expected:#04x}, got {actual:#04x})"
+            ),
+        }
+    }
+}
+
+impl Error for StructuredAppendError {}
 
 #[cfg(feature = "decode-from-svg")]
 fn svg_to_png(data: &[u8]) -> anyhow::Result<Vec<u8>> {
@@ -37,9 +374,353 @@ pub fn from_svg(data: impl AsRef<[u8]>) -> anyhow::Result<DynamicImage> {
     image::load_from_memory_with_format(&image, ImageFormat::Png).map_err(anyhow::Error::from)
 }
 
+/// Converts the error of the TIFF decoder.
+#[cfg(feature = "decode-from-tiff")]
+fn tiff_error(err: TiffError) -> ImageError {
+    ImageError::Decoding(DecodingError::new(ImageFormat::Tiff.into(), err))
+}
+
+/// Converts the decoded page of TIFF into an image.
+///
+/// Returns [`None`] if the color type is not supported.
+#[cfg(feature = "decode-from-tiff")]
+fn tiff_image(
+    (width, height): (u32, u32),
+    color_type: ColorType,
+    data: DecodingResult,
+) -> Option<DynamicImage> {
+    match (color_type, data) {
+        (ColorType::Gray(1), DecodingResult::U8(data)) => {
+            let columns = usize::try_from(width).ok()?;
+            let pixels = data
+                .chunks(columns.div_ceil(8))
+                .flat_map(|row| {
+                    (0..columns).map(move |x| {
+                        if row.get(x / 8).is_some_and(|b| b & (0x80 >> (x % 8)) != 0) {
+                            u8::MAX
+                        } else {
+                            u8::MIN
+                        }
+                    })
+                })
+                .collect();
+            GrayImage::from_raw(width, height, pixels).map(DynamicImage::from)
+        }
+        (ColorType::Gray(8), DecodingResult::U8(data)) => {
+            GrayImage::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::Gray(16), DecodingResult::U16(data)) => {
+            ImageBuffer::<Luma<u16>, _>::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+            ImageBuffer::<LumaA<u8>, _>::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::GrayA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::<LumaA<u16>, _>::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGB(8), DecodingResult::U8(data)) => {
+            RgbImage::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGB(16), DecodingResult::U16(data)) => {
+            ImageBuffer::<Rgb<u16>, _>::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGB(32), DecodingResult::F32(data)) => {
+            Rgb32FImage::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+            RgbaImage::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::<Rgba<u16>, _>::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::RGBA(32), DecodingResult::F32(data)) => {
+            Rgba32FImage::from_raw(width, height, data).map(DynamicImage::from)
+        }
+        (ColorType::CMYK(8), DecodingResult::U8(data)) => {
+            let pixels = data
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let k = u16::from(u8::MAX - cmyk[3]);
+                    cmyk[..3].iter().map(move |&c| {
+                        u8::try_from(u16::from(u8::MAX - c) * k / u16::from(u8::MAX))
+                            .expect("invalid color")
+                    })
+                })
+                .collect();
+            RgbImage::from_raw(width, height, pixels).map(DynamicImage::from)
+        }
+        _ => None,
+    }
+}
+
+/// Reads every page of the multi-page TIFF.
+///
+/// The pages are decoded one by one with a single decoder. If the TIFF has only
+/// one page, it is read in the same way as other images.
+#[cfg(feature = "decode-from-tiff")]
+fn tiff_pages(data: &[u8]) -> ImageResult<Vec<DynamicImage>> {
+    let mut decoder = TiffDecoder::new(Cursor::new(data)).map_err(tiff_error)?;
+    if !decoder.more_images() {
+        return image::load_from_memory_with_format(data, ImageFormat::Tiff)
+            .map(|image| vec![image]);
+    }
+    let mut pages = Vec::new();
+    loop {
+        let dimensions = decoder.dimensions().map_err(tiff_error)?;
+        let color_type = decoder.colortype().map_err(tiff_error)?;
+        let data = decoder.read_image().map_err(tiff_error)?;
+        let page = tiff_image(dimensions, color_type, data).ok_or_else(|| {
+            ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+                ImageFormat::Tiff.into(),
+                UnsupportedErrorKind::GenericFeature(format!("color type {color_type:?}")),
+            ))
+        })?;
+        pages.push(page);
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(tiff_error)?;
+    }
+    Ok(pages)
+}
+
+/// Splits the ICO or CUR into the files which contain each entry.
+#[cfg(feature = "decode-from-ico")]
+fn ico_entries(data: &[u8]) -> Vec<Vec<u8>> {
+    const HEADER_LEN: usize = 6;
+    const ENTRY_LEN: usize = 16;
+
+    let count = data
+        .get(4..HEADER_LEN)
+        .map_or(0, |c| u16::from_le_bytes([c[0], c[1]]));
+    (0..usize::from(count))
+        .filter_map(|i| {
+            let entry = data.get(HEADER_LEN + ENTRY_LEN * i..HEADER_LEN + ENTRY_LEN * (i + 1))?;
+            let len = u32::from_le_bytes(entry[8..12].try_into().ok()?);
+            let offset = u32::from_le_bytes(entry[12..].try_into().ok()?);
+            let (len, offset) = (usize::try_from(len).ok()?, usize::try_from(offset).ok()?);
+            let image = data.get(offset..offset.checked_add(len)?)?;
+
+            let offset = u32::try_from(HEADER_LEN + ENTRY_LEN).ok()?;
+            let mut file = Vec::with_capacity(HEADER_LEN + ENTRY_LEN + len);
+            file.extend_from_slice(&data[..4]);
+            file.extend_from_slice(&1_u16.to_le_bytes());
+            file.extend_from_slice(&entry[..12]);
+            file.extend_from_slice(&offset.to_le_bytes());
+            file.extend_from_slice(image);
+            Some(file)
+        })
+        .collect()
+}
+
+/// Reads all images in the data.
+///
+/// Animated GIF, APNG, and animated WebP are read frame by frame, every page of
+/// multi-page TIFF is read, and every entry of ICO and CUR is read. Other
+/// images are read as a single image.
+pub fn images(data: &[u8], format: ImageFormat) -> ImageResult<Vec<DynamicImage>> {
+    let frames: Frames<'_> = match format {
+        #[cfg(feature = "decode-from-gif")]
+        ImageFormat::Gif => GifDecoder::new(Cursor::new(data))?.into_frames(),
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(data))?;
+            if !decoder.is_apng()? {
+                return DynamicImage::from_decoder(decoder).map(|image| vec![image]);
+            }
+            decoder.apng()?.into_frames()
+        }
+        #[cfg(feature = "decode-from-webp")]
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(data))?;
+            if !decoder.has_animation() {
+                return DynamicImage::from_decoder(decoder).map(|image| vec![image]);
+            }
+            decoder.into_frames()
+        }
+        #[cfg(feature = "decode-from-tiff")]
+        ImageFormat::Tiff => return tiff_pages(data),
+        format => {
+            let files: Vec<Vec<u8>> = match format {
+                #[cfg(feature = "decode-from-ico")]
+                ImageFormat::Ico => ico_entries(data),
+                _ => Vec::new(),
+            };
+            if files.len() <= 1 {
+                return image::load_from_memory_with_format(data, format).map(|image| vec![image]);
+            }
+            return files
+                .iter()
+                .map(|file| image::load_from_memory_with_format(file, format))
+                .collect();
+        }
+    };
+    frames
+        .map(|frame| frame.map(|frame| DynamicImage::ImageRgba8(frame.into_buffer())))
+        .collect()
+}
+
+/// The device which collects the raster images drawn on a page of PDF.
+#[cfg(feature = "decode-from-pdf")]
+#[derive(Debug, Default)]
+struct ImageCollector(Vec<DynamicImage>);
+
+#[cfg(feature = "decode-from-pdf")]
+impl<'a> Device<'a> for ImageCollector {
+    fn draw_path(&mut self, _: &BezPath, _: DrawProps<'a>, _: &DrawMode) {}
+
+    fn push_clip_path(&mut self, _: &ClipPath) {}
+
+    fn push_transparency_group(&mut self, _: f32, _: Option<SoftMask<'a>>, _: BlendMode) {}
+
+    fn draw_glyph_run(&mut self, _: &GlyphRun<'_, 'a>, _: DrawProps<'a>, _: &DrawMode) {}
+
+    fn draw_image(&mut self, image: hayro::hayro_interpret::Image<'a, '_>, _: ImageDrawProps<'a>) {
+        // NOTE: Stencil masks are skipped because they have no colors of their
+        // own.
+        if let hayro::hayro_interpret::Image::Raster(image) = image {
+            image.with_rgba(
+                |data, _| {
+                    let image = match data {
+                        ImageData::Rgb(data) => {
+                            RgbImage::from_raw(data.width, data.height, data.data)
+                                .map(DynamicImage::from)
+                        }
+                        ImageData::Luma(data) => {
+                            GrayImage::from_raw(data.width, data.height, data.data)
+                                .map(DynamicImage::from)
+                        }
+                    };
+                    self.0.extend(image);
+                },
+                None,
+            );
+        }
+    }
+
+    fn pop_clip(&mut self) {}
+
+    fn pop_transparency_group(&mut self) {}
+}
+
+/// Reads all pages of PDF.
+///
+/// Each page is rasterized at `dpi` on a white background. If `extract_images`
+/// is `true`, the raster images drawn on each page are read instead. Returns
+/// the images with the indices of the pages on which they appear.
+#[cfg(feature = "decode-from-pdf")]
+pub fn from_pdf(
+    data: Vec<u8>,
+    dpi: u16,
+    extract_images: bool,
+) -> anyhow::Result<Vec<(usize, DynamicImage)>> {
+    let pdf = Pdf::new(data).map_err(|err| anyhow::anyhow!("invalid PDF: {err:?}"))?;
+    let settings = InterpreterSettings::default();
+    let mut images = Vec::new();
+    if extract_images {
+        let cache = InterpreterCache::new();
+        for (index, page) in pdf.pages().iter().enumerate() {
+            let (width, height) = page.render_dimensions();
+            let mut context = hayro::hayro_interpret::Context::new(
+                page.initial_transform(true).to_kurbo(),
+                Rect::new(0.0, 0.0, width.into(), height.into()),
+                &cache,
+                page.xref(),
+                settings.clone(),
+            );
+            let mut collector = ImageCollector::default();
+            hayro::hayro_interpret::interpret_page(page, &mut context, &mut collector);
+            images.extend(collector.0.into_iter().map(|image| (index, image)));
+        }
+        anyhow::ensure!(!images.is_empty(), "the PDF has no raster images");
+    } else {
+        let cache = RenderCache::new();
+        let scale = f32::from(dpi) / 72.0;
+        let pixmap_settings = PixmapSettings {
+            x_scale: scale,
+            y_scale: scale,
+            bg_color: WHITE,
+        };
+        for (index, page) in pdf.pages().iter().enumerate() {
+            let pixmap = hayro::render(
+                page,
+                &cache,
+                &settings,
+                &RenderSettings::default(),
+                &pixmap_settings,
+            );
+            // The pixmap is opaque because of the background, so the
+            // premultiplied colors are the same as the straight ones.
+            let image = RgbaImage::from_raw(
+                pixmap.width().into(),
+                pixmap.height().into(),
+                pixmap.data_as_u8_slice().to_vec(),
+            )
+            .context("could not convert the page to an image")?;
+            images.push((index, DynamicImage::from(image)));
+        }
+    }
+    Ok(images)
+}
+
+/// Draws the outline of the symbol onto the image.
+///
+/// The top-left corner of the symbol is marked with a filled square.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn annotate(image: &mut RgbaImage, geometry: &Geometry) {
+    const COLOR: Rgba<u8> = Rgba([0xff, 0x00, 0x00, 0xff]);
+
+    let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+    let mut fill = |x: i64, y: i64, radius: i64| {
+        for y in (y - radius).max(0)..=(y + radius).min(height - 1) {
+            for x in (x - radius).max(0)..=(x + radius).min(width - 1) {
+                image.put_pixel(x as u32, y as u32, COLOR);
+            }
+        }
+    };
+    let module_size = geometry.module_size.round() as i64;
+    let radius = (module_size / 8).max(1);
+    for i in 0..4 {
+        let (x0, y0) = geometry.corners[i];
+        let (x1, y1) = geometry.corners[(i + 1) % 4];
+        let (x0, y0, x1, y1) = (i64::from(x0), i64::from(y0), i64::from(x1), i64::from(y1));
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        loop {
+            fill(x, y, radius);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+    let (x, y) = geometry.corners[0];
+    fill(i64::from(x), i64::from(y), (module_size / 2).max(radius));
+}
+
 fn grid_as_bytes<G: BitGrid>(grid: &Grid<G>) -> Result<DecodedBytes, DeQRError> {
-    let mut writer = Vec::new();
-    grid.decode_to(&mut writer).map(|meta| (meta, writer))
+    let (metadata, codewords, mirrored) = bitstream::read_codewords(&grid.grid)?;
+    let payload = bitstream::parse(&codewords, metadata.version.0)?;
+    let bounds = grid.bounds.map(|point| (point.x, point.y));
+    Ok(DecodedBytes {
+        metadata,
+        structured_append: payload.structured_append,
+        eci: payload.eci,
+        fnc1: payload.fnc1,
+        data: payload.data,
+        geometry: Geometry::new(bounds, grid.grid.size(), mirrored),
+        source: None,
+        inverted: false,
+        subimage: None,
+    })
 }
 
 /// Decodes the grids as bytes.
@@ -53,6 +734,238 @@ pub fn grids_as_bytes<G: BitGrid>(
         .collect()
 }
 
+/// The maximum number of steps to search for the assignment of the symbols to
+/// the Structured Append sequences which share the header.
+const MAX_ASSIGNMENT_STEPS: usize = 1 << 16;
+
+/// Reassembles the Structured Append sequences.
+///
+/// Each group consists of either a symbol which is not a part of a sequence or
+/// all symbols of a sequence ordered by their positions. The groups are in
+/// order of first appearance.
+///
+/// The symbols with the same number of symbols and the same parity data belong
+/// to the same sequence. If the same position is found more than once with
+/// different data, the symbols are split into multiple sequences only if
+/// there is exactly one way to do so with the parity data matching in every
+/// sequence. Otherwise, the symbol is regarded as duplicated.
+pub fn reassemble(
+    contents: Vec<DecodedBytes>,
+) -> Result<Vec<Vec<DecodedBytes>>, StructuredAppendError> {
+    // Each symbol is paired with the order of appearance.
+    let mut groups: Vec<Vec<(usize, DecodedBytes)>> = Vec::new();
+    for (order, content) in contents.into_iter().enumerate() {
+        let group = content.structured_append.and_then(|sa| {
+            groups.iter_mut().find(|group| {
+                group[0]
+                    .1
+                    .structured_append
+                    .is_some_and(|first| (first.count, first.parity) == (sa.count, sa.parity))
+            })
+        });
+        match group {
+            Some(group) => group.push((order, content)),
+            None => groups.push(vec![(order, content)]),
+        }
+    }
+
+    let mut sequences = Vec::with_capacity(groups.len());
+    for group in groups {
+        let Some(StructuredAppend { count, parity, .. }) = group[0].1.structured_append else {
+            sequences.push(group);
+            continue;
+        };
+        sequences.extend(split_sequences(group, count, parity)?);
+    }
+    sequences.sort_by_key(|sequence| sequence.iter().map(|(order, _)| *order).min());
+    Ok(sequences
+        .into_iter()
+        .map(|sequence| sequence.into_iter().map(|(_, content)| content).collect())
+        .collect())
+}
+
+/// Splits the symbols with the same header into the Structured Append
+/// sequences ordered by their positions.
+fn split_sequences(
+    group: Vec<(usize, DecodedBytes)>,
+    count: usize,
+    parity: u8,
+) -> Result<Vec<Vec<(usize, DecodedBytes)>>, StructuredAppendError> {
+    let mut slots = (0..count).map(|_| Vec::new()).collect::<Vec<_>>();
+    for (order, content) in group {
+        let index = content.structured_append.map_or(0, |sa| sa.index);
+        slots[index].push((order, content));
+    }
+    let xor = |content: &DecodedBytes| content.data.iter().fold(0, |parity, byte| parity ^ byte);
+
+    let sequences = slots.iter().map(Vec::len).max().unwrap_or_default();
+    if sequences > 1 {
+        let duplicated = slots
+            .iter()
+            .position(|slot| slot.len() > 1)
+            .unwrap_or_default();
+        let is_duplicated = slots.iter().any(|slot| {
+            slot.len() != sequences
+                || slot
+                    .iter()
+                    .enumerate()
+                    .any(|(i, (_, a))| slot[..i].iter().any(|(_, b)| a.data == b.data))
+        });
+        let parities = slots
+            .iter()
+            .map(|slot| slot.iter().map(|(_, content)| xor(content)).collect())
+            .collect::<Vec<Vec<_>>>();
+        let Some(assignment) = (!is_duplicated)
+            .then(|| assign_sequences(&parities, parity))
+            .flatten()
+        else {
+            return Err(StructuredAppendError::DuplicatedSymbol {
+                count,
+                position: duplicated + 1,
+            });
+        };
+        let mut split = (0..sequences).map(|_| Vec::new()).collect::<Vec<_>>();
+        for (slot, targets) in slots.into_iter().zip(assignment) {
+            for (symbol, target) in slot.into_iter().zip(targets) {
+                split[target].push(symbol);
+            }
+        }
+        return Ok(split);
+    }
+
+    let missing = (0..count)
+        .filter(|&index| slots[index].is_empty())
+        .map(|index| index + 1)
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(StructuredAppendError::MissingSymbols { count, missing });
+    }
+    let sequence = slots.into_iter().flatten().collect::<Vec<_>>();
+    let actual = sequence
+        .iter()
+        .fold(0, |actual, (_, content)| actual ^ xor(content));
+    if actual != parity {
+        return Err(StructuredAppendError::ParityMismatch {
+            expected: parity,
+            actual,
+        });
+    }
+    Ok(vec![sequence])
+}
+
+/// Finds the only way to assign the symbols at each position to the sequences
+/// so that the parity data of every sequence matches `parity`.
+///
+/// `parities[index]` is the parity of each symbol at the position, and the
+/// symbols at the first position determine the sequences. Returns [`None`] if
+/// there is no such way, there is more than one, or the search takes too many
+/// steps.
+fn assign_sequences(parities: &[Vec<u8>], parity: u8) -> Option<Vec<Vec<usize>>> {
+    struct Search<'a> {
+        parities: &'a [Vec<u8>],
+        parity: u8,
+        steps: usize,
+        assignment: Vec<Vec<usize>>,
+        found: Vec<Vec<Vec<usize>>>,
+    }
+
+    impl Search<'_> {
+        /// Assigns the `symbol`-th symbol at the `index`-th position and the
+        /// following ones, where `acc` is the running parity of each sequence.
+        fn run(&mut self, index: usize, symbol: usize, acc: &mut [u8]) {
+            if self.found.len() > 1 || self.steps > MAX_ASSIGNMENT_STEPS {
+                return;
+            }
+            self.steps += 1;
+            let Some(slot) = self.parities.get(index) else {
+                if acc.iter().all(|&p| p == self.parity) {
+                    self.found.push(self.assignment.clone());
+                }
+                return;
+            };
+            if symbol == slot.len() {
+                self.run(index + 1, 0, acc);
+                return;
+            }
+            for target in 0..acc.len() {
+                if self.assignment[index][..symbol].contains(&target) {
+                    continue;
+                }
+                self.assignment[index].push(target);
+                acc[target] ^= slot[symbol];
+                self.run(index, symbol + 1, acc);
+                acc[target] ^= slot[symbol];
+                self.assignment[index].pop();
+            }
+        }
+    }
+
+    let (first, rest) = parities.split_first()?;
+    let mut acc = first.clone();
+    let mut search = Search {
+        parities: rest,
+        parity,
+        steps: 0,
+        assignment: vec![Vec::new(); rest.len()],
+        found: Vec::new(),
+    };
+    search.run(0, 0, &mut acc);
+    if search.found.len() != 1 || search.steps > MAX_ASSIGNMENT_STEPS {
+        return None;
+    }
+    let mut assignment = search.found.pop()?;
+    assignment.insert(0, (0..first.len()).collect());
+    Some(assignment)
+}
+
+/// Transcodes the data of the symbols to UTF-8.
+///
+/// The ECI designator remains in effect until the next one, even across the
+/// symbols of a Structured Append sequence. The character set of the data
+/// before the first ECI designator is guessed.
+pub fn to_utf8(group: &[DecodedBytes]) -> Vec<u8> {
+    let mut segments: Vec<(Option<u32>, Vec<u8>)> = Vec::new();
+    let mut designator = None;
+    for content in group {
+        let mut offset = 0;
+        let boundaries = content
+            .eci
+            .iter()
+            .map(|eci| (eci.offset, Some(eci.designator)))
+            .chain([(content.data.len(), None)]);
+        for (end, next) in boundaries {
+            let data = &content.data[offset..end];
+            match segments.last_mut() {
+                Some((d, buf)) if *d == designator => buf.extend_from_slice(data),
+                _ if data.is_empty() => {}
+                _ => segments.push((designator, data.to_vec())),
+            }
+            offset = end;
+            designator = next.or(designator);
+        }
+    }
+    segments
+        .iter()
+        .flat_map(|(designator, data)| eci::to_utf8(data, *designator).into_owned())
+        .collect()
+}
+
+/// Converts the data of the symbols in FNC1 in first position mode to the GS1
+/// element string in human readable form.
+///
+/// Returns [`None`] if the symbols are not in FNC1 in first position mode or
+/// the data is not a valid GS1 element string.
+pub fn to_element_string(group: &[DecodedBytes]) -> Option<String> {
+    if group.first()?.fnc1 != Some(Fnc1::First) {
+        return None;
+    }
+    let data = group
+        .iter()
+        .flat_map(|c| c.data.iter().copied())
+        .collect::<Vec<_>>();
+    gs1::to_element_string(&data)
+}
+
 impl Extractor for MetaData {
     fn metadata(&self) -> Metadata {
         let symbol_version = metadata::Version::new((self.version.0, None));
@@ -61,6 +974,12 @@ impl Extractor for MetaData {
     }
 }
 
+impl Extractor for DecodedBytes {
+    fn metadata(&self) -> Metadata {
+        self.metadata.metadata().with_fnc1(self.fnc1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rqrr::Version;
@@ -68,6 +987,42 @@ mod tests {
     use super::*;
     use crate::cli::Ecc;
 
+    #[test]
+    fn convert_to_element_string() {
+        let mut content = decoded_bytes(b"0109501101530003\x1d", None);
+        assert_eq!(to_element_string(&[]), None);
+        assert_eq!(to_element_string(std::slice::from_ref(&content)), None);
+        content.fnc1 = Some(Fnc1::Second(37));
+        assert_eq!(to_element_string(std::slice::from_ref(&content)), None);
+        content.fnc1 = Some(Fnc1::First);
+        assert_eq!(
+            to_element_string(&[content, decoded_bytes(b"10ABC", None)]).unwrap(),
+            "(01)09501101530003(10)ABC"
+        );
+    }
+
+    fn decoded_bytes(data: &[u8], header: Option<(usize, usize, u8)>) -> DecodedBytes {
+        DecodedBytes {
+            metadata: MetaData {
+                version: Version(1),
+                ecc_level: 0,
+                mask: 0,
+            },
+            structured_append: header.map(|(index, count, parity)| StructuredAppend {
+                index,
+                count,
+                parity,
+            }),
+            eci: Vec::new(),
+            fnc1: None,
+            data: data.to_vec(),
+            geometry: Geometry::new([(4, 4), (25, 4), (25, 25), (4, 25)], 21, false),
+            source: None,
+            inverted: false,
+            subimage: None,
+        }
+    }
+
     #[test]
     fn validate_metadata_extraction() {
         assert_eq!(
@@ -107,4 +1062,318 @@ mod tests {
             Metadata::new(metadata::Version::new((1, None)), Ecc::H)
         );
     }
+
+    #[test]
+    fn reassemble_structured_append() {
+        let groups = reassemble(vec![
+            decoded_bytes(b"code", Some((1, 2, 0x2e))),
+            decoded_bytes(b"foo", None),
+            decoded_bytes(b"QR ", Some((0, 2, 0x2e))),
+            decoded_bytes(b"bar", None),
+        ])
+        .unwrap();
+        let groups = groups
+            .iter()
+            .map(|group| group.iter().map(|c| c.data.as_slice()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            [vec![b"QR ".as_slice(), b"code"], vec![b"foo"], vec![b"bar"]]
+        );
+    }
+
+    #[test]
+    fn reassemble_multiple_structured_append_sequences() {
+        let groups = reassemble(vec![
+            decoded_bytes(b"b", Some((1, 2, b'a' ^ b'b'))),
+            decoded_bytes(b"d", Some((1, 2, b'c' ^ b'd'))),
+            decoded_bytes(b"c", Some((0, 2, b'c' ^ b'd'))),
+            decoded_bytes(b"a", Some((0, 2, b'a' ^ b'b'))),
+        ])
+        .unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0][0].data, b"a");
+        assert_eq!(groups[0][1].data, b"b");
+        assert_eq!(groups[1][0].data, b"c");
+        assert_eq!(groups[1][1].data, b"d");
+    }
+
+    #[test]
+    fn reassemble_interleaved_structured_append_sequences() {
+        // Both sequences have the same number of symbols and the same parity data,
+        // and only one pairing of the symbols matches the parity data.
+        let parity = b'a' ^ b'b';
+        let contents = || {
+            vec![
+                decoded_bytes(b"a", Some((0, 2, parity))),
+                decoded_bytes(b"c", Some((0, 2, parity))),
+                decoded_bytes(b"b", Some((1, 2, parity))),
+                decoded_bytes(b"`", Some((1, 2, parity))),
+            ]
+        };
+        let groups = reassemble(contents()).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0][0].data, b"a");
+        assert_eq!(groups[0][1].data, b"b");
+        assert_eq!(groups[1][0].data, b"c");
+        assert_eq!(groups[1][1].data, b"`");
+
+        let groups = reassemble(contents().into_iter().rev().collect()).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0][0].data, b"c");
+        assert_eq!(groups[0][1].data, b"`");
+        assert_eq!(groups[1][0].data, b"a");
+        assert_eq!(groups[1][1].data, b"b");
+    }
+
+    #[test]
+    fn reassemble_ambiguous_structured_append_sequences() {
+        // Any pairing of the symbols matches the parity data.
+        let contents = || {
+            vec![
+                decoded_bytes(b"QR", Some((0, 2, 0x2e))),
+                decoded_bytes(b"RQ", Some((0, 2, 0x2e))),
+                decoded_bytes(b" code", Some((1, 2, 0x2e))),
+                decoded_bytes(b"code ", Some((1, 2, 0x2e))),
+            ]
+        };
+        let err = StructuredAppendError::DuplicatedSymbol {
+            count: 2,
+            position: 1,
+        };
+        assert_eq!(reassemble(contents()).unwrap_err(), err);
+        assert_eq!(
+            reassemble(contents().into_iter().rev().collect()).unwrap_err(),
+            err
+        );
+    }
+
+    #[test]
+    fn reassemble_with_missing_symbols() {
+        assert_eq!(
+            reassemble(vec![decoded_bytes(b"code", Some((1, 4, 0x2e)))]).unwrap_err(),
+            StructuredAppendError::MissingSymbols {
+                count: 4,
+                missing: vec![1, 3, 4]
+            }
+        );
+    }
+
+    #[test]
+    fn reassemble_with_duplicated_symbol() {
+        assert_eq!(
+            reassemble(vec![
+                decoded_bytes(b"QR ", Some((0, 2, 0x2e))),
+                decoded_bytes(b"code", Some((1, 2, 0x2e))),
+                decoded_bytes(b"QR ", Some((0, 2, 0x2e))),
+            ])
+            .unwrap_err(),
+            StructuredAppendError::DuplicatedSymbol {
+                count: 2,
+                position: 1
+            }
+        );
+    }
+
+    #[test]
+    fn reassemble_with_conflicting_symbol() {
+        assert_eq!(
+            reassemble(vec![
+                decoded_bytes(b"QR ", Some((0, 2, 0x2e))),
+                decoded_bytes(b"code", Some((1, 2, 0x2e))),
+                decoded_bytes(b"QX ", Some((0, 2, 0x2e))),
+            ])
+            .unwrap_err(),
+            StructuredAppendError::DuplicatedSymbol {
+                count: 2,
+                position: 1
+            }
+        );
+    }
+
+    #[test]
+    fn reassemble_with_parity_mismatch() {
+        assert_eq!(
+            reassemble(vec![
+                decoded_bytes(b"QR ", Some((0, 2, 0x2e))),
+                decoded_bytes(b"cod", Some((1, 2, 0x2e))),
+            ])
+            .unwrap_err(),
+            StructuredAppendError::ParityMismatch {
+                expected: 0x2e,
+                actual: 0x2e ^ b'e'
+            }
+        );
+    }
+
+    #[test]
+    fn transcode_to_utf8() {
+        let mut first = decoded_bytes(b"QR\x83R", Some((0, 2, 0)));
+        first.eci.push(Eci {
+            offset: 2,
+            designator: 20,
+        });
+        let mut second = decoded_bytes(b"\x81[\x83h caf\xe9", Some((1, 2, 0)));
+        second.eci.push(Eci {
+            offset: 5,
+            designator: 3,
+        });
+        assert_eq!(to_utf8(&[first, second]), "QRコード café".as_bytes());
+
+        assert_eq!(
+            to_utf8(&[decoded_bytes(b"\x83R\x81[\x83h", None)]),
+            "コード".as_bytes()
+        );
+        assert_eq!(to_utf8(&[decoded_bytes(b"", None)]), b"");
+    }
+
+    #[test]
+    fn convert_to_payload() {
+        assert_eq!(
+            Payload::from(b"QR code".to_vec()),
+            Payload::Text(String::from("QR code"))
+        );
+        assert_eq!(
+            Payload::from(b"\x82\xa0\xff".to_vec()),
+            Payload::Base64(String::from("gqD/"))
+        );
+    }
+
+    #[cfg(feature = "decode-from-tiff")]
+    #[test]
+    fn convert_tiff_page() {
+        let image = tiff_image(
+            (10, 2),
+            ColorType::Gray(1),
+            DecodingResult::U8(vec![0b1010_0000, 0b0100_0000, 0, 0]),
+        )
+        .unwrap()
+        .into_luma8();
+        assert_eq!(image.dimensions(), (10, 2));
+        assert_eq!(image.as_raw()[..10], [255, 0, 255, 0, 0, 0, 0, 0, 0, 255]);
+        assert!(image.as_raw()[10..].iter().all(|&p| p == 0));
+
+        let image = tiff_image(
+            (1, 1),
+            ColorType::CMYK(8),
+            DecodingResult::U8(vec![255, 0, 0, 0]),
+        )
+        .unwrap()
+        .into_rgb8();
+        assert_eq!(image.as_raw(), &[0, 255, 255]);
+
+        assert!(tiff_image((1, 1), ColorType::Gray(8), DecodingResult::U16(vec![0])).is_none());
+        assert!(tiff_image((2, 1), ColorType::Gray(8), DecodingResult::U8(vec![0])).is_none());
+        assert!(tiff_image((1, 1), ColorType::YCbCr(8), DecodingResult::U8(vec![0; 3])).is_none());
+    }
+
+    #[cfg(feature = "decode-from-ico")]
+    #[test]
+    fn split_ico_into_entries() {
+        let mut data = vec![0, 0, 1, 0, 2, 0];
+        data.extend_from_slice(&[16, 16, 0, 0, 1, 0, 32, 0, 3, 0, 0, 0, 38, 0, 0, 0]);
+        data.extend_from_slice(&[32, 32, 0, 0, 1, 0, 32, 0, 2, 0, 0, 0, 41, 0, 0, 0]);
+        data.extend_from_slice(b"abcde");
+        let entries = ico_entries(&data);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0],
+            [
+                0, 0, 1, 0, 1, 0, 16, 16, 0, 0, 1, 0, 32, 0, 3, 0, 0, 0, 22, 0, 0, 0, b'a', b'b',
+                b'c'
+            ]
+        );
+        assert_eq!(entries[1][6..8], [32, 32]);
+        assert_eq!(entries[1][22..], *b"de");
+
+        data[4] = 3;
+        assert_eq!(ico_entries(&data).len(), 2);
+        assert!(ico_entries(&[0, 0, 1, 0]).is_empty());
+    }
+
+    #[test]
+    fn display_subimage() {
+        assert_eq!(Subimage::Frame(1).to_string(), "Frame: 1");
+        assert_eq!(Subimage::Page(0).to_string(), "Page: 0");
+        assert_eq!(Subimage::Entry(2).to_string(), "Entry: 2");
+        assert_eq!(Subimage::kind(ImageFormat::Tiff)(3), Subimage::Page(3));
+        assert_eq!(Subimage::kind(ImageFormat::Ico)(3), Subimage::Entry(3));
+        assert_eq!(Subimage::kind(ImageFormat::Gif)(3), Subimage::Frame(3));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn compute_geometry() {
+        let bounds = [(32, 32), (208, 32), (208, 208), (32, 208)];
+        assert_eq!(
+            Geometry::new(bounds, 21, false),
+            Geometry {
+                corners: [(32, 32), (200, 32), (200, 200), (32, 200)],
+                module_size: 8.0,
+                rotation: 0.0,
+                mirrored: false
+            }
+        );
+        let rotated = Geometry::new([(208, 32), (208, 208), (32, 208), (32, 32)], 21, false);
+        assert_eq!(
+            rotated.corners,
+            [(208, 32), (208, 200), (40, 200), (40, 32)]
+        );
+        assert_eq!(rotated.module_size, 8.0);
+        assert_eq!(rotated.rotation, 90.0);
+        let rotated = Geometry::new([(32, 208), (32, 32), (208, 32), (208, 208)], 21, false);
+        assert_eq!(rotated.rotation, 270.0);
+        let mirrored = Geometry::new([(208, 32), (208, 208), (32, 208), (32, 32)], 21, true);
+        assert_eq!(
+            mirrored.corners,
+            [(208, 32), (40, 32), (40, 200), (208, 200)]
+        );
+        assert_eq!(mirrored.rotation, 0.0);
+        assert!(mirrored.mirrored);
+        let skewed = Geometry::new([(0, 0), (100, 100), (0, 200), (-100, 100)], 33, false);
+        assert_eq!(skewed.module_size, 4.16);
+        assert_eq!(skewed.rotation, 45.0);
+    }
+
+    #[test]
+    fn annotate_image() {
+        let mut image = RgbaImage::new(40, 40);
+        annotate(
+            &mut image,
+            &Geometry::new([(8, 8), (30, 8), (30, 30), (8, 30)], 21, false),
+        );
+        assert_eq!(image[(20, 8)], Rgba([0xff, 0x00, 0x00, 0xff]));
+        assert_eq!(image[(30, 20)], Rgba([0xff, 0x00, 0x00, 0xff]));
+        assert_eq!(image[(20, 20)], Rgba([0x00, 0x00, 0x00, 0x00]));
+        assert_eq!(image[(0, 0)], Rgba([0x00, 0x00, 0x00, 0x00]));
+    }
+
+    #[test]
+    fn serialize_record() {
+        let mut content = decoded_bytes(b"QR code", Some((1, 3, 0)));
+        content.source = Some(PathBuf::from("input.png"));
+        assert_eq!(
+            serde_json::to_string(&Record::new(&content, content.data.clone())).unwrap(),
+            concat!(
+                r#"{"source":"input.png","inverted":false,"payload":{"text":"QR code"},"#,
+                r#""metadata":{"symbol_version":"1","error_correction_level":"M"},"mask":0,"#,
+                r#""eci":[],"sequence":{"position":2,"count":3},"#,
+                r#""corners":[[4,4],[24,4],[24,24],[4,24]],"module_size":0.95,"#,
+                r#""rotation":0.0,"mirrored":false}"#
+            )
+        );
+        assert!(
+            serde_json::to_string(
+                &Record::new(&content, Vec::new()).with_sequence_payload(b"QR code".to_vec())
+            )
+            .unwrap()
+            .contains(r#""sequence":{"position":2,"count":3,"payload":{"text":"QR code"}}"#)
+        );
+        content.fnc1 = Some(Fnc1::Second(37));
+        assert!(
+            serde_json::to_string(&Record::new(&content, Vec::new()))
+                .unwrap()
+                .contains(r#""fnc1":{"second":37}"#)
+        );
+    }
 }
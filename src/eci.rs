@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, SHIFT_JIS};
+
+/// The ECI designator for UTF-8.
+pub const UTF_8: u32 = 26;
+
+/// The maximum value of the ECI designator.
+pub const MAX_DESIGNATOR: u32 = 999_999;
+
+// This table is based on the AIM ECI specification.
+/// The character sets and their ECI designators.
+const CHARSETS: [(u32, &str); 28] = [
+    (2, "CP437"),
+    (3, "ISO-8859-1"),
+    (4, "ISO-8859-2"),
+    (5, "ISO-8859-3"),
+    (6, "ISO-8859-4"),
+    (7, "ISO-8859-5"),
+    (8, "ISO-8859-6"),
+    (9, "ISO-8859-7"),
+    (10, "ISO-8859-8"),
+    (11, "ISO-8859-9"),
+    (12, "ISO-8859-10"),
+    (13, "ISO-8859-11"),
+    (15, "ISO-8859-13"),
+    (16, "ISO-8859-14"),
+    (17, "ISO-8859-15"),
+    (18, "ISO-8859-16"),
+    (20, "Shift_JIS"),
+    (21, "windows-1250"),
+    (22, "windows-1251"),
+    (23, "windows-1252"),
+    (24, "windows-1256"),
+    (25, "UTF-16BE"),
+    (26, "UTF-8"),
+    (27, "US-ASCII"),
+    (28, "Big5"),
+    (29, "GB18030"),
+    (30, "EUC-KR"),
+    (899, "binary"),
+];
+
+/// Returns the ECI designator of the character set.
+///
+/// `name` is case-insensitive.
+pub fn designator(name: &str) -> Option<u32> {
+    CHARSETS
+        .iter()
+        .find(|(_, charset)| charset.eq_ignore_ascii_case(name))
+        .map(|&(designator, _)| designator)
+}
+
+/// Returns the name of the character set specified by the ECI designator.
+pub fn charset(designator: u32) -> Option<&'static str> {
+    // 0 and 1 are the obsolete designators for CP437 and ISO-8859-1.
+    let designator = match designator {
+        0 => 2,
+        1 => 3,
+        d => d,
+    };
+    CHARSETS
+        .iter()
+        .find(|&&(d, _)| d == designator)
+        .map(|&(_, charset)| charset)
+}
+
+/// Returns the description of the ECI designator.
+pub fn describe(designator: u32) -> String {
+    charset(designator).map_or_else(
+        || designator.to_string(),
+        |charset| format!("{designator} ({charset})"),
+    )
+}
+
+/// Decodes ISO/IEC 8859-1 data to UTF-8.
+fn latin1_to_utf8(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .map(|&b| char::from(b))
+        .collect::<String>()
+        .into_bytes()
+}
+
+/// Guesses the character set of the data which does not have the ECI
+/// designator.
+///
+/// This tries UTF-8, Shift JIS, and ISO/IEC 8859-1 in that order.
+pub fn guess_designator(data: &[u8]) -> u32 {
+    if std::str::from_utf8(data).is_ok() {
+        UTF_8
+    } else if SHIFT_JIS
+        .decode_without_bom_handling_and_without_replacement(data)
+        .is_some()
+    {
+        20
+    } else {
+        3
+    }
+}
+
+/// Transcodes the data in the character set specified by the ECI designator to
+/// UTF-8.
+///
+/// If `designator` is [`None`], the character set is guessed. The data is
+/// returned as is if it is UTF-8, binary data, or in an unsupported character
+/// set.
+pub fn to_utf8(data: &[u8], designator: Option<u32>) -> Cow<'_, [u8]> {
+    let designator = designator.unwrap_or_else(|| guess_designator(data));
+    match charset(designator) {
+        Some("ISO-8859-1") => Cow::Owned(latin1_to_utf8(data)),
+        Some(charset) if charset != "UTF-8" => {
+            Encoding::for_label(charset.as_bytes()).map_or(Cow::Borrowed(data), |encoding| {
+                let (text, _) = encoding.decode_without_bom_handling(data);
+                match text {
+                    Cow::Borrowed(text) => Cow::Borrowed(text.as_bytes()),
+                    Cow::Owned(text) => Cow::Owned(text.into_bytes()),
+                }
+            })
+        }
+        _ => Cow::Borrowed(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_designator() {
+        assert_eq!(designator("UTF-8"), Some(UTF_8));
+        assert_eq!(designator("utf-8"), Some(UTF_8));
+        assert_eq!(designator("iso-8859-1"), Some(3));
+        assert_eq!(designator("ISO-8859-16"), Some(18));
+        assert_eq!(designator("shift_jis"), Some(20));
+        assert_eq!(designator("utf-16be"), Some(25));
+        assert_eq!(designator("binary"), Some(899));
+        assert_eq!(designator("ISO-8859-12"), None);
+        assert_eq!(designator("utf-32"), None);
+    }
+
+    #[test]
+    fn get_charset() {
+        assert_eq!(charset(0), Some("CP437"));
+        assert_eq!(charset(1), Some("ISO-8859-1"));
+        assert_eq!(charset(3), Some("ISO-8859-1"));
+        assert_eq!(charset(20), Some("Shift_JIS"));
+        assert_eq!(charset(UTF_8), Some("UTF-8"));
+        assert_eq!(charset(899), Some("binary"));
+        assert_eq!(charset(14), None);
+        assert_eq!(charset(MAX_DESIGNATOR), None);
+    }
+
+    #[test]
+    fn guess_charset() {
+        assert_eq!(guess_designator(b"QR code"), UTF_8);
+        assert_eq!(guess_designator("QRコード".as_bytes()), UTF_8);
+        assert_eq!(guess_designator(b"QR\x83R\x81[\x83h"), 20);
+        assert_eq!(guess_designator(b"caf\xe9"), 3);
+    }
+
+    #[test]
+    fn transcode_to_utf8() {
+        assert_eq!(to_utf8(b"QR code", Some(UTF_8)), &b"QR code"[..]);
+        assert_eq!(to_utf8(b"caf\xe9", Some(3)), "café".as_bytes());
+        assert_eq!(to_utf8(b"caf\xe9", Some(1)), "café".as_bytes());
+        assert_eq!(to_utf8(b"\xe1\xe2\xe3", Some(9)), "αβγ".as_bytes());
+        assert_eq!(
+            to_utf8(b"QR\x83R\x81[\x83h", Some(20)),
+            "QRコード".as_bytes()
+        );
+        assert_eq!(to_utf8(b"\x00Q\x00R\x30\xb3", Some(25)), "QRコ".as_bytes());
+        assert_eq!(to_utf8(b"\xff\xfe", Some(899)), &b"\xff\xfe"[..]);
+        assert_eq!(to_utf8(b"\xff\xfe", Some(170)), &b"\xff\xfe"[..]);
+        assert_eq!(to_utf8(b"QR\x83R\x81[\x83h", None), "QRコード".as_bytes());
+        assert_eq!(to_utf8(b"caf\xe9", None), "café".as_bytes());
+    }
+
+    #[test]
+    fn describe_designator() {
+        assert_eq!(describe(UTF_8), "26 (UTF-8)");
+        assert_eq!(describe(1), "1 (ISO-8859-1)");
+        assert_eq!(describe(170), "170");
+    }
+}
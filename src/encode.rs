@@ -7,20 +7,24 @@
 use anstyle::RgbColor;
 #[cfg(feature = "output-as-ansi")]
 use anstyle_lossy::palette::Palette;
+use clap::ValueEnum;
 use csscolorparser::Color;
 use image::{Rgba, RgbaImage};
 use qrcode2::{
-    QrCode, QrResult, Version,
-    bits::Bits,
-    render::{eps, pic, svg, unicode::Dense1x2},
-    types::QrError,
+    EcLevel, QrCode, QrResult, Version,
+    bits::{Bits, ExtendedMode},
+    optimize::Parser,
+    render::{Pixel, Renderer, eps, pic, svg, unicode::Dense1x2},
+    types::{self, QrError},
 };
 #[cfg(feature = "output-as-ansi")]
 use yansi::Paint;
 
 use crate::{
-    cli::{Mode, Variant},
+    cli::{Ecc, Mode, Prefer, Variant},
+    eci, mask,
     metadata::{self, Extractor, Metadata},
+    segment::Segment,
 };
 
 /// Sets the version.
@@ -38,6 +42,7 @@ pub fn set_version(version: &[i16], variant: &Variant) -> QrResult<Version> {
         ))
         .filter(|v| v.is_rect_micro())
         .ok_or(QrError::InvalidVersion),
+        Variant::Auto => Err(QrError::InvalidVersion),
     }
 }
 
@@ -56,9 +61,486 @@ pub fn push_data_for_selected_mode(
     }
 }
 
+/// Encodes data for the selected mode, or using the optimal encoding if `mode`
+/// is [`None`], to the bits.
+pub fn push_data(bits: &mut Bits, data: &[u8], mode: Option<&Mode>) -> QrResult<()> {
+    if let Some(mode) = mode {
+        push_data_for_selected_mode(bits, data, mode)
+    } else {
+        bits.push_optimal_data(data)
+    }
+}
+
+/// The heights of rMQR code.
+const RMQR_HEIGHTS: [i16; 6] = [7, 9, 11, 13, 15, 17];
+
+/// The widths of rMQR code.
+const RMQR_WIDTHS: [i16; 6] = [27, 43, 59, 77, 99, 139];
+
+/// Returns all versions of the type of QR code in ascending order of size.
+pub fn versions(variant: &Variant) -> Vec<Version> {
+    match variant {
+        Variant::Normal => (1..=40).map(Version::Normal).collect(),
+        Variant::Micro => (1..=4).map(Version::Micro).collect(),
+        Variant::Rmqr => {
+            let mut versions = RMQR_HEIGHTS
+                .into_iter()
+                .flat_map(|height| {
+                    RMQR_WIDTHS
+                        .into_iter()
+                        .map(move |width| Version::RectMicro(height, width))
+                })
+                .filter(|v| v.is_rect_micro())
+                .collect::<Vec<_>>();
+            versions.sort_by_key(|v| v.width() * v.height());
+            versions
+        }
+        Variant::Auto => {
+            let mut versions = [Variant::Normal, Variant::Micro, Variant::Rmqr]
+                .iter()
+                .flat_map(versions)
+                .collect::<Vec<_>>();
+            versions.sort_by_key(|v| v.width() * v.height());
+            versions
+        }
+    }
+}
+
+/// Returns the versions of the type of QR code between `min` and `max` in
+/// ascending order of size.
+///
+/// The bounds are specified in the same way as [`set_version`]. For rMQR code,
+/// the first value of the bounds is the height and the second value is the
+/// width, and the width is not bounded if it is omitted.
+///
+/// # Errors
+///
+/// Returns [`QrError::InvalidVersion`] if the bounds are not valid for the
+/// type of QR code, or if no version is between them.
+pub fn versions_within(
+    variant: &Variant,
+    min: Option<&[i16]>,
+    max: Option<&[i16]>,
+) -> QrResult<Vec<Version>> {
+    let is_valid = |bound: &[i16]| match variant {
+        Variant::Normal => Version::Normal(bound[0]).is_normal(),
+        Variant::Micro => Version::Micro(bound[0]).is_micro(),
+        Variant::Rmqr => {
+            RMQR_HEIGHTS.contains(&bound[0])
+                && bound.get(1).is_none_or(|width| RMQR_WIDTHS.contains(width))
+        }
+        Variant::Auto => false,
+    };
+    if !min.into_iter().chain(max).all(is_valid) {
+        return Err(QrError::InvalidVersion);
+    }
+    let within = |value: i16, index: usize| {
+        min.and_then(|min| min.get(index))
+            .is_none_or(|&min| min <= value)
+            && max
+                .and_then(|max| max.get(index))
+                .is_none_or(|&max| value <= max)
+    };
+    let versions = versions(variant)
+        .into_iter()
+        .filter(|&version| match version {
+            Version::Normal(version) | Version::Micro(version) => within(version, 0),
+            Version::RectMicro(height, width) => within(height, 0) && within(width, 1),
+        })
+        .collect::<Vec<_>>();
+    if versions.is_empty() {
+        return Err(QrError::InvalidVersion);
+    }
+    Ok(versions)
+}
+
+/// Encodes the data by `encode` in the first version of `versions` which can
+/// store the data.
+///
+/// If the data cannot be encoded in any version, the error for the last
+/// version is returned.
+pub fn first_fit(
+    encode: impl Fn(Version) -> QrResult<QrCode>,
+    versions: impl IntoIterator<Item = Version>,
+) -> QrResult<QrCode> {
+    let mut result = Err(QrError::DataTooLong);
+    for version in versions {
+        result = encode(version);
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+/// Returns the type of QR code of the version.
+pub const fn variant_of(version: Version) -> Variant {
+    match version {
+        Version::Normal(_) => Variant::Normal,
+        Version::Micro(_) => Variant::Micro,
+        Version::RectMicro(..) => Variant::Rmqr,
+    }
+}
+
+/// Returns the width and the height of the symbol of the version including the
+/// margin.
+///
+/// If `margin` is [`None`], the default margin for the type of QR code is
+/// used.
+pub fn symbol_size(version: Version, margin: Option<u32>) -> (usize, usize) {
+    let margin = margin.map_or_else(
+        || if version.is_normal() { 4 } else { 2 },
+        |margin| usize::try_from(margin).expect("margin should be in the range of `usize`"),
+    );
+    let size = |modules| usize::try_from(modules).expect("invalid symbol version") + margin * 2;
+    (size(version.width()), size(version.height()))
+}
+
+/// Encodes the data by `encode` in the smallest version of all types of QR
+/// code which can store the data.
+///
+/// The versions are compared by the dimension specified by `prefer`, and then
+/// by the area. If the data cannot be encoded in any version, the error for
+/// the largest version is returned.
+pub fn smallest(
+    encode: impl Fn(Version) -> QrResult<QrCode>,
+    prefer: Prefer,
+    margin: Option<u32>,
+) -> QrResult<QrCode> {
+    let mut versions = versions(&Variant::Auto);
+    versions.sort_by_key(|&version| {
+        let (width, height) = symbol_size(version, margin);
+        let area = width * height;
+        match prefer {
+            Prefer::Area => (area, area),
+            Prefer::Height => (height, area),
+            Prefer::Width => (width, area),
+        }
+    });
+    first_fit(encode, versions)
+}
+
+/// Encodes the data by `encode` at the highest error correction level which
+/// can store the data in the version of `code`.
+///
+/// Only the levels higher than that of `code` are tried. If the data does not
+/// fit in the version at any of them, `code` is returned as is.
+pub fn boost_ecc(code: QrCode, encode: impl Fn(Version, EcLevel) -> QrResult<QrCode>) -> QrCode {
+    let version = code.version();
+    let current = code.error_correction_level();
+    Ecc::value_variants()
+        .iter()
+        .rev()
+        .map(|&level| EcLevel::from(level))
+        .take_while(|&level| level > current)
+        .find_map(|level| encode(version, level).ok())
+        .unwrap_or(code)
+}
+
+/// Returns the ECI designator to be inserted automatically.
+///
+/// The ECI designator for UTF-8 is inserted only if the data is a valid UTF-8
+/// string that contains non-ASCII characters and is encoded using the optimal
+/// encoding.
+pub fn auto_eci(data: impl AsRef<[u8]>, variant: &Variant, mode: Option<&Mode>) -> Option<u32> {
+    let data = data.as_ref();
+    let is_utf8 = !data.is_ascii() && std::str::from_utf8(data).is_ok();
+    (is_utf8 && variant != &Variant::Micro && mode.is_none()).then_some(eci::UTF_8)
+}
+
+/// Constructs a new QR code from the bits built by `push`.
+///
+/// If `version` is [`None`], the minimum version required to store the data
+/// will be chosen.
+fn with_bits_from(
+    version: Option<Version>,
+    variant: &Variant,
+    level: EcLevel,
+    push: impl Fn(&mut Bits) -> QrResult<()>,
+) -> QrResult<QrCode> {
+    let encode = |version| {
+        let mut bits = Bits::new(version);
+        push(&mut bits)?;
+        bits.push_terminator(level)?;
+        QrCode::with_bits(bits, level)
+    };
+
+    if let Some(version) = version {
+        return encode(version);
+    }
+    // Smaller versions of Micro QR code do not support some modes, so try the
+    // next version in that case as well.
+    let mut result = Err(QrError::DataTooLong);
+    for version in versions(variant) {
+        result = encode(version);
+        if !matches!(
+            result,
+            Err(QrError::DataTooLong | QrError::UnsupportedCharacterSet)
+        ) {
+            break;
+        }
+    }
+    result
+}
+
+/// Encodes the data with the ECI designator.
+///
+/// If `version` is [`None`], the minimum version required to store the data
+/// will be chosen.
+pub fn with_eci(
+    data: impl AsRef<[u8]>,
+    designator: u32,
+    version: Option<Version>,
+    variant: &Variant,
+    level: EcLevel,
+    mode: Option<&Mode>,
+) -> QrResult<QrCode> {
+    with_bits_from(version, variant, level, |bits| {
+        push_eci(bits, data.as_ref(), designator, mode)
+    })
+}
+
+/// Encodes the data with the ECI designator to the bits.
+pub fn push_eci(
+    bits: &mut Bits,
+    data: &[u8],
+    designator: u32,
+    mode: Option<&Mode>,
+) -> QrResult<()> {
+    bits.push_eci_designator(designator)?;
+    push_data(bits, data, mode)
+}
+
+/// Encodes the GS1 element string in FNC1 in first position mode.
+///
+/// `data` is the element string in which the group separator (GS) terminates
+/// the variable length data fields. If `version` is [`None`], the minimum
+/// version required to store the data will be chosen.
+pub fn with_gs1(
+    data: &[u8],
+    version: Option<Version>,
+    variant: &Variant,
+    level: EcLevel,
+) -> QrResult<QrCode> {
+    with_bits_from(version, variant, level, |bits| push_gs1(bits, data))
+}
+
+/// Encodes the GS1 element string in FNC1 in first position mode to the bits.
+pub fn push_gs1(bits: &mut Bits, data: &[u8]) -> QrResult<()> {
+    bits.push_fnc1_first_position()?;
+    let version = bits.version();
+    Parser::new(data).optimize(version).try_for_each(|segment| {
+        let data = &data[segment.begin..segment.end];
+        match segment.mode {
+            // In FNC1 mode, `%` in alphanumeric mode represents the group
+            // separator, so a literal `%` must be doubled.
+            types::Mode::Alphanumeric => {
+                let mut escaped = Vec::with_capacity(data.len());
+                for &b in data {
+                    if b == b'%' {
+                        escaped.push(b'%');
+                    }
+                    escaped.push(b);
+                }
+                bits.push_alphanumeric_data(&escaped)
+            }
+            types::Mode::Numeric => bits.push_numeric_data(data),
+            types::Mode::Kanji => bits.push_kanji_data(data),
+            types::Mode::Byte => bits.push_byte_data(data),
+        }
+    })
+}
+
+/// Encodes the segments.
+///
+/// If `version` is [`None`], the minimum version required to store the
+/// segments will be chosen.
+pub fn with_segments(
+    segments: &[Segment],
+    version: Option<Version>,
+    variant: &Variant,
+    level: EcLevel,
+) -> QrResult<QrCode> {
+    with_bits_from(version, variant, level, |bits| {
+        push_segments(bits, segments)
+    })
+}
+
+/// Encodes the segments to the bits.
+pub fn push_segments(bits: &mut Bits, segments: &[Segment]) -> QrResult<()> {
+    segments.iter().try_for_each(|segment| match segment {
+        Segment::Data(mode, data) => push_data_for_selected_mode(bits, data, mode),
+        Segment::Eci(designator) => bits.push_eci_designator(*designator),
+    })
+}
+
+/// The maximum number of symbols in a Structured Append sequence.
+pub const MAX_STRUCTURED_APPEND_SYMBOLS: usize = 16;
+
+/// Computes the parity data of a Structured Append sequence.
+///
+/// The parity data is the XOR of all bytes of the original input data.
+pub fn structured_append_parity(data: impl AsRef<[u8]>) -> u8 {
+    data.as_ref().iter().fold(0, |parity, byte| parity ^ byte)
+}
+
+/// Encodes the Structured Append header to the bits.
+pub fn push_structured_append_header(
+    bits: &mut Bits,
+    index: usize,
+    count: usize,
+    parity: u8,
+) -> QrResult<()> {
+    bits.push_mode_indicator(ExtendedMode::StructuredAppend)?;
+    bits.push_number_checked(4, index)?;
+    bits.push_number_checked(4, count.saturating_sub(1))?;
+    bits.push_number_checked(8, parity.into())
+}
+
+/// Splits the data into `count` chunks of almost equal size.
+///
+/// In Kanji mode, the data is only split at double-byte character boundaries.
+/// If the data is in UTF-8, it is only split at character boundaries.
+fn split_data<'a>(
+    data: &'a [u8],
+    count: usize,
+    eci: Option<u32>,
+    mode: Option<&Mode>,
+) -> Vec<&'a [u8]> {
+    let unit = if mode == Some(&Mode::Kanji) { 2 } else { 1 };
+    let units = data.len().div_ceil(unit);
+    let is_utf8 = mode != Some(&Mode::Kanji)
+        && (eci == Some(eci::UTF_8) || std::str::from_utf8(data).is_ok());
+    let boundary = |i: usize| {
+        let mut boundary = (units * i / count * unit).min(data.len());
+        // A continuation byte of UTF-8 is not at a character boundary.
+        while is_utf8 && data.get(boundary).is_some_and(|b| b & 0xc0 == 0x80) {
+            boundary -= 1;
+        }
+        boundary
+    };
+    (0..count)
+        .map(|i| &data[boundary(i)..boundary(i + 1)])
+        .collect()
+}
+
+/// Encodes a part of a Structured Append sequence to the bits.
+///
+/// If `version` is [`None`], the minimum version required to store the part
+/// will be chosen.
+fn structured_append_bits(
+    data: &[u8],
+    header: (usize, usize, u8),
+    eci: Option<u32>,
+    version: Option<Version>,
+    level: EcLevel,
+    mode: Option<&Mode>,
+) -> QrResult<Bits> {
+    let encode = |version| {
+        let mut bits = Bits::new(version);
+        push_structured_append_header(&mut bits, header.0, header.1, header.2)?;
+        if let Some(designator) = eci {
+            bits.push_eci_designator(designator)?;
+        }
+        push_data(&mut bits, data, mode)?;
+        bits.push_terminator(level)?;
+        Ok(bits)
+    };
+
+    if let Some(version) = version {
+        return encode(version);
+    }
+    // If the part does not fit in the largest version, there is no need to try
+    // the others.
+    let bits = encode(Version::Normal(40))?;
+    (1..40)
+        .map(Version::Normal)
+        .map(encode)
+        .find(|bits| !matches!(bits, Err(QrError::DataTooLong)))
+        .unwrap_or(Ok(bits))
+}
+
+/// Encodes the data in a Structured Append sequence.
+///
+/// The data is split into the fewest symbols that can store it, up to
+/// [`MAX_STRUCTURED_APPEND_SYMBOLS`]. If `eci` is not [`None`], each symbol
+/// contains the ECI designator.
+pub fn to_structured_append(
+    data: impl AsRef<[u8]>,
+    eci: Option<u32>,
+    version: Option<Version>,
+    level: EcLevel,
+    mode: Option<&Mode>,
+) -> QrResult<Vec<QrCode>> {
+    let data = data.as_ref();
+    let parity = structured_append_parity(data);
+    for count in 2..=MAX_STRUCTURED_APPEND_SYMBOLS {
+        let bits = split_data(data, count, eci, mode)
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                structured_append_bits(chunk, (index, count, parity), eci, version, level, mode)
+            })
+            .collect::<QrResult<Vec<_>>>();
+        match bits {
+            Ok(bits) => {
+                return bits
+                    .into_iter()
+                    .map(|bits| QrCode::with_bits(bits, level))
+                    .collect();
+            }
+            Err(QrError::DataTooLong) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Err(QrError::DataTooLong)
+}
+
+/// A symbol to be rendered.
+pub struct Symbol<'a> {
+    code: &'a QrCode,
+    colors: Vec<qrcode2::Color>,
+}
+
+impl<'a> Symbol<'a> {
+    /// Constructs a new symbol with the mask pattern chosen by the encoder.
+    pub fn new(code: &'a QrCode) -> Self {
+        let colors = code.to_colors();
+        Self { code, colors }
+    }
+
+    /// Constructs a new symbol with the mask pattern specified by the mask
+    /// pattern reference.
+    ///
+    /// Returns [`None`] if the mask pattern is not available for the symbol.
+    pub fn with_mask(code: &'a QrCode, reference: u8) -> Option<Self> {
+        let colors = mask::apply(code, reference)?;
+        Some(Self { code, colors })
+    }
+
+    /// Returns the default width of the quiet zone in modules.
+    const fn quiet_zone(&self) -> u32 {
+        if self.code.version().is_normal() {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Returns the renderer of the symbol.
+    fn render<P: Pixel>(&self) -> Renderer<'_, P> {
+        Renderer::new(
+            &self.colors,
+            self.code.width(),
+            self.code.height(),
+            self.quiet_zone(),
+        )
+    }
+}
+
 /// Renders the QR code into an image.
 pub fn to_image(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -77,7 +559,7 @@ pub fn to_image(
 
 /// Renders the QR code into a SVG image.
 pub fn to_svg(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -97,7 +579,7 @@ pub fn to_svg(
 
 /// Renders the QR code into an EPS image.
 pub fn to_eps(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -125,8 +607,198 @@ pub fn to_eps(
     renderer.build() + "\n"
 }
 
+/// The size of the symbol of the Swiss QR-bill in millimeters, excluding the
+/// quiet zone.
+const QR_BILL_SIZE: f64 = 46.0;
+
+/// The rectangles which make up the Swiss cross of the Swiss QR-bill.
+///
+/// Each rectangle is `(x, y, width, height, is_dark)` in millimeters, relative
+/// to the top-left corner of the 7 x 7 mm cross. The cross is a white square
+/// with a 6 x 6 mm black square, which has a white cross in the proportions of
+/// the Swiss flag.
+const SWISS_CROSS: [(f64, f64, f64, f64, bool); 4] = [
+    (0.0, 0.0, 7.0, 7.0, false),
+    (0.5, 0.5, 6.0, 6.0, true),
+    (1.625, 2.9375, 3.75, 1.125, false),
+    (2.9375, 1.625, 1.125, 3.75, false),
+];
+
+/// The layout of the image of the Swiss QR-bill in pixels.
+struct QrBillLayout {
+    module_size: f64,
+    quiet_zone: f64,
+    image_size: f64,
+
+    /// The number of pixels per millimeter.
+    scale: f64,
+}
+
+impl QrBillLayout {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(code: &Symbol<'_>, margin: Option<u32>, module_size: Option<u32>) -> Self {
+        let module_size = f64::from(module_size.unwrap_or(8));
+        let quiet_zone = f64::from(margin.unwrap_or_else(|| code.quiet_zone())) * module_size;
+        let symbol_size = code.code.width() as f64 * module_size;
+        Self {
+            module_size,
+            quiet_zone,
+            image_size: 2.0_f64.mul_add(quiet_zone, symbol_size),
+            scale: symbol_size / QR_BILL_SIZE,
+        }
+    }
+
+    /// Returns the rectangles of the Swiss cross in pixels, relative to the
+    /// top-left corner of the image.
+    fn swiss_cross(&self) -> impl Iterator<Item = (f64, f64, f64, f64, bool)> + '_ {
+        let origin = ((QR_BILL_SIZE - 7.0) / 2.0).mul_add(self.scale, self.quiet_zone);
+        SWISS_CROSS
+            .into_iter()
+            .map(move |(x, y, width, height, is_dark)| {
+                (
+                    x.mul_add(self.scale, origin),
+                    y.mul_add(self.scale, origin),
+                    width * self.scale,
+                    height * self.scale,
+                    is_dark,
+                )
+            })
+    }
+}
+
+/// Returns the resolution of the image of the Swiss QR-bill in pixels per
+/// meter.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn qr_bill_resolution(code: &Symbol<'_>, module_size: Option<u32>) -> u32 {
+    let layout = QrBillLayout::new(code, None, module_size);
+    (layout.scale * 1000.0).round() as u32
+}
+
+/// Renders the QR code of the Swiss QR-bill into an image.
+///
+/// The Swiss cross is drawn at the center of the symbol.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn to_qr_bill_image(
+    code: &Symbol<'_>,
+    margin: Option<u32>,
+    colors: &(Color, Color),
+    module_size: Option<u32>,
+) -> RgbaImage {
+    let mut image = to_image(code, margin, colors, module_size);
+    let layout = QrBillLayout::new(code, margin, module_size);
+    for (x, y, width, height, is_dark) in layout.swiss_cross() {
+        let color = Rgba(if is_dark { &colors.0 } else { &colors.1 }.to_rgba8());
+        let (left, top) = (x.round() as u32, y.round() as u32);
+        let (right, bottom) = ((x + width).round() as u32, (y + height).round() as u32);
+        for y in top..bottom {
+            for x in left..right {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+    image
+}
+
+/// Sets the physical resolution of the PNG image by inserting a `pHYs` chunk
+/// after the `IHDR` chunk.
+pub fn set_png_resolution(png: &mut Vec<u8>, pixels_per_meter: u32) {
+    const IHDR_END: usize = 33;
+
+    debug_assert_eq!(&png[12..16], b"IHDR");
+    let mut data = b"pHYs".to_vec();
+    data.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    data.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    // The unit is the meter.
+    data.push(1);
+    let crc = crc32fast::hash(&data);
+    let mut chunk = 9_u32.to_be_bytes().to_vec();
+    chunk.extend(data);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+    png.splice(IHDR_END..IHDR_END, chunk);
+}
+
+/// Renders the QR code of the Swiss QR-bill into a SVG image.
+///
+/// The Swiss cross is drawn at the center of the symbol, and the size of the
+/// image is set in millimeters so that the symbol is 46 x 46 mm.
+pub fn to_qr_bill_svg(
+    code: &Symbol<'_>,
+    margin: Option<u32>,
+    colors: &(Color, Color),
+    module_size: Option<u32>,
+) -> String {
+    let svg = to_svg(code, margin, colors, module_size);
+    let layout = QrBillLayout::new(code, margin, module_size);
+    let (size, scale) = (layout.image_size, layout.scale);
+    let mut svg = svg.trim_end().replacen(
+        &format!(r#"width="{size}" height="{size}""#),
+        &format!(r#"width="{0:.3}mm" height="{0:.3}mm""#, size / scale),
+        1,
+    );
+    let cross = layout
+        .swiss_cross()
+        .map(|(x, y, width, height, is_dark)| {
+            let color = if is_dark { &colors.0 } else { &colors.1 };
+            format!(
+                r#"<path fill="{}" d="M{x:.3} {y:.3}h{width:.3}v{height:.3}h-{width:.3}z"/>"#,
+                color.to_css_hex()
+            )
+        })
+        .collect::<Vec<_>>();
+    let position = svg.rfind("</svg>").expect("SVG should have the end tag");
+    svg.insert_str(position, &cross.join(""));
+    svg + "\n"
+}
+
+/// Renders the QR code of the Swiss QR-bill into an EPS image.
+///
+/// The Swiss cross is drawn at the center of the symbol, and the image is
+/// scaled so that the symbol is 46 x 46 mm.
+pub fn to_qr_bill_eps(
+    code: &Symbol<'_>,
+    margin: Option<u32>,
+    colors: &(Color, Color),
+    module_size: Option<u32>,
+) -> String {
+    let eps = to_eps(code, margin, colors, module_size);
+    let layout = QrBillLayout::new(code, margin, module_size);
+    let size = layout.image_size;
+    // The number of points per pixel.
+    let scale = 72.0 / 25.4 / layout.scale;
+    let mut eps = eps.trim_end().replacen(
+        &format!("%%BoundingBox: 0 0 {size} {size}\n"),
+        &format!(
+            "%%BoundingBox: 0 0 {0} {0}\n%%HiResBoundingBox: 0 0 {1:.3} {1:.3}\n",
+            (size * scale).ceil(),
+            size * scale
+        ),
+        1,
+    );
+    let header_end = eps
+        .find("%%EndComments\n")
+        .expect("EPS should have the header")
+        + "%%EndComments\n".len();
+    eps.insert_str(header_end, &format!("{scale} {scale} scale\n"));
+    let mut cross = vec![String::from("gsave")];
+    for (x, y, width, height, is_dark) in layout.swiss_cross() {
+        let color = if is_dark { &colors.0 } else { &colors.1 };
+        let [r, g, b, _] = color.to_array().map(f64::from);
+        // The renderer places each module at `height - top` from the bottom, so
+        // the rectangles are placed in the same way.
+        let bottom = size - y - height + layout.module_size;
+        cross.push(format!("{r} {g} {b} setrgbcolor"));
+        cross.push(format!(
+            "{x:.3} {bottom:.3} {width:.3} {height:.3} rectfill"
+        ));
+    }
+    cross.push(String::from("grestore\n"));
+    let position = eps.rfind("%%EOF").expect("EPS should have the trailer");
+    eps.insert_str(position, &cross.join("\n"));
+    eps + "\n"
+}
+
 /// Renders the QR code into a PIC image.
-pub fn to_pic(code: &QrCode, margin: Option<u32>, module_size: Option<u32>) -> String {
+pub fn to_pic(code: &Symbol<'_>, margin: Option<u32>, module_size: Option<u32>) -> String {
     let mut renderer = &mut code.render::<pic::Color>();
     if let Some(margin) = margin {
         renderer = renderer.quiet_zone(margin);
@@ -140,7 +812,7 @@ pub fn to_pic(code: &QrCode, margin: Option<u32>, module_size: Option<u32>) -> S
 /// Renders the QR code into the terminal using 4-bit ANSI escape sequences.
 #[cfg(feature = "output-as-ansi")]
 pub fn to_ansi(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -172,7 +844,7 @@ pub fn to_ansi(
 /// Renders the QR code into the terminal using 8-bit ANSI escape sequences.
 #[cfg(feature = "output-as-ansi")]
 pub fn to_ansi_256(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -198,7 +870,7 @@ pub fn to_ansi_256(
 /// Renders the QR code into the terminal using 24-bit ANSI escape sequences.
 #[cfg(feature = "output-as-ansi")]
 pub fn to_ansi_true_color(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     colors: &(Color, Color),
     module_size: Option<u32>,
@@ -226,7 +898,7 @@ pub fn to_ansi_true_color(
 
 /// Renders the QR code into the terminal as ASCII string.
 pub fn to_ascii(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     module_size: Option<u32>,
     invert: bool,
@@ -248,7 +920,7 @@ pub fn to_ascii(
 
 /// Renders the QR code into the terminal as UTF-8 string.
 pub fn to_unicode(
-    code: &QrCode,
+    code: &Symbol<'_>,
     margin: Option<u32>,
     module_size: Option<u32>,
     invert: bool,
@@ -342,6 +1014,327 @@ mod tests {
         assert!(set_version(&[7], &Variant::Rmqr).is_err());
     }
 
+    #[test]
+    fn validate_versions() {
+        assert_eq!(versions(&Variant::Normal).len(), 40);
+        assert_eq!(versions(&Variant::Normal)[0], Version::Normal(1));
+        assert_eq!(versions(&Variant::Micro).len(), 4);
+        let auto = versions(&Variant::Auto);
+        assert_eq!(auto.len(), 76);
+        assert_eq!(auto[0], Version::Micro(1));
+        let rmqr = versions(&Variant::Rmqr);
+        assert_eq!(rmqr.len(), 32);
+        assert_eq!(rmqr[0], Version::RectMicro(11, 27));
+        assert_eq!(rmqr[31], Version::RectMicro(17, 139));
+        assert!(rmqr.is_sorted_by_key(|v| v.width() * v.height()));
+    }
+
+    #[test]
+    fn validate_auto_eci() {
+        assert_eq!(auto_eci("QR code", &Variant::Normal, None), None);
+        assert_eq!(
+            auto_eci("QRコード", &Variant::Normal, None),
+            Some(eci::UTF_8)
+        );
+        assert_eq!(auto_eci("QRコード", &Variant::Rmqr, None), Some(eci::UTF_8));
+        assert_eq!(auto_eci("QRコード", &Variant::Micro, None), None);
+        assert_eq!(
+            auto_eci("QRコード", &Variant::Normal, Some(&Mode::Byte)),
+            None
+        );
+        assert_eq!(auto_eci(b"\xff\xfe", &Variant::Normal, None), None);
+    }
+
+    #[test]
+    fn validate_variant_of() {
+        assert_eq!(variant_of(Version::Normal(1)), Variant::Normal);
+        assert_eq!(variant_of(Version::Micro(1)), Variant::Micro);
+        assert_eq!(variant_of(Version::RectMicro(7, 43)), Variant::Rmqr);
+    }
+
+    #[test]
+    fn validate_symbol_size() {
+        assert_eq!(symbol_size(Version::Normal(1), None), (29, 29));
+        assert_eq!(symbol_size(Version::Normal(1), Some(0)), (21, 21));
+        assert_eq!(symbol_size(Version::Micro(2), None), (17, 17));
+        assert_eq!(symbol_size(Version::RectMicro(13, 27), None), (31, 17));
+    }
+
+    #[test]
+    fn validate_smallest() {
+        let encode =
+            |data: &'static [u8]| move |version| QrCode::with_version(data, version, EcLevel::M);
+        let code = smallest(encode(b"01234567"), Prefer::Area, None).unwrap();
+        assert_eq!(code.version(), Version::Micro(2));
+        let code = smallest(encode(b"01234567"), Prefer::Height, None).unwrap();
+        assert_eq!(code.version(), Version::RectMicro(7, 43));
+        let code = smallest(encode(b"https://example.com/"), Prefer::Width, None).unwrap();
+        assert_eq!(code.version(), Version::Normal(2));
+        assert_eq!(
+            smallest(encode(&[b'a'; 3000]), Prefer::Area, None).unwrap_err(),
+            QrError::DataTooLong
+        );
+    }
+
+    #[test]
+    fn validate_versions_within() {
+        assert_eq!(
+            versions_within(&Variant::Normal, Some(&[5]), Some(&[7])).unwrap(),
+            [Version::Normal(5), Version::Normal(6), Version::Normal(7)]
+        );
+        assert_eq!(
+            versions_within(&Variant::Normal, None, Some(&[2])).unwrap(),
+            [Version::Normal(1), Version::Normal(2)]
+        );
+        assert_eq!(
+            versions_within(&Variant::Micro, Some(&[3]), None).unwrap(),
+            [Version::Micro(3), Version::Micro(4)]
+        );
+        assert_eq!(
+            versions_within(&Variant::Rmqr, Some(&[11, 59]), Some(&[13, 77])).unwrap(),
+            [
+                Version::RectMicro(11, 59),
+                Version::RectMicro(13, 59),
+                Version::RectMicro(11, 77),
+                Version::RectMicro(13, 77)
+            ]
+        );
+        assert_eq!(
+            versions_within(&Variant::Rmqr, Some(&[15]), None)
+                .unwrap()
+                .len(),
+            10
+        );
+        assert_eq!(
+            versions_within(&Variant::Normal, Some(&[41]), None).unwrap_err(),
+            QrError::InvalidVersion
+        );
+        assert_eq!(
+            versions_within(&Variant::Micro, None, Some(&[5])).unwrap_err(),
+            QrError::InvalidVersion
+        );
+        assert_eq!(
+            versions_within(&Variant::Rmqr, Some(&[7, 30]), None).unwrap_err(),
+            QrError::InvalidVersion
+        );
+        assert_eq!(
+            versions_within(&Variant::Normal, Some(&[5]), Some(&[3])).unwrap_err(),
+            QrError::InvalidVersion
+        );
+        assert_eq!(
+            versions_within(&Variant::Auto, Some(&[1]), None).unwrap_err(),
+            QrError::InvalidVersion
+        );
+    }
+
+    #[test]
+    fn validate_first_fit() {
+        let encode =
+            |data: &'static [u8]| move |version| QrCode::with_version(data, version, EcLevel::M);
+        let code = first_fit(encode(b"QR code"), (3..=5).map(Version::Normal)).unwrap();
+        assert_eq!(code.version(), Version::Normal(3));
+        let code = first_fit(encode(&[b'a'; 70]), (3..=5).map(Version::Normal)).unwrap();
+        assert_eq!(code.version(), Version::Normal(5));
+        assert_eq!(
+            first_fit(encode(&[b'a'; 70]), (1..=4).map(Version::Normal)).unwrap_err(),
+            QrError::DataTooLong
+        );
+        assert_eq!(
+            first_fit(encode(b"QR code"), []).unwrap_err(),
+            QrError::DataTooLong
+        );
+    }
+
+    #[test]
+    fn validate_boost_ecc() {
+        let encode =
+            |data: &'static [u8]| move |version, level| QrCode::with_version(data, version, level);
+        let code = QrCode::with_version(b"QR code", Version::Normal(1), EcLevel::M).unwrap();
+        let code = boost_ecc(code, encode(b"QR code"));
+        assert_eq!(code.version(), Version::Normal(1));
+        assert_eq!(code.error_correction_level(), EcLevel::H);
+        let code = QrCode::with_version([b'a'; 20], Version::Normal(2), EcLevel::L).unwrap();
+        let code = boost_ecc(code, encode(&[b'a'; 20]));
+        assert_eq!(code.version(), Version::Normal(2));
+        assert_eq!(code.error_correction_level(), EcLevel::Q);
+        let code = QrCode::with_version([b'a'; 26], Version::Normal(2), EcLevel::M).unwrap();
+        let code = boost_ecc(code, encode(&[b'a'; 26]));
+        assert_eq!(code.error_correction_level(), EcLevel::M);
+        let code = QrCode::with_version(b"QR", Version::Micro(2), EcLevel::L).unwrap();
+        let code = boost_ecc(code, encode(b"QR"));
+        assert_eq!(code.version(), Version::Micro(2));
+        assert_eq!(code.error_correction_level(), EcLevel::M);
+    }
+
+    #[test]
+    fn validate_with_gs1() {
+        let code = with_gs1(b"0109501101530003", None, &Variant::Normal, EcLevel::M).unwrap();
+        assert_eq!(code.version(), Version::Normal(1));
+        let code = with_gs1(b"10AB%C\x1d2112", None, &Variant::Rmqr, EcLevel::M).unwrap();
+        assert!(code.version().is_rect_micro());
+        assert_eq!(
+            with_gs1(b"01", Some(Version::Micro(4)), &Variant::Micro, EcLevel::L).unwrap_err(),
+            QrError::UnsupportedCharacterSet
+        );
+    }
+
+    #[test]
+    fn validate_with_eci() {
+        let code = with_eci(
+            "QRコード",
+            eci::UTF_8,
+            None,
+            &Variant::Normal,
+            EcLevel::M,
+            None,
+        )
+        .unwrap();
+        assert_eq!(code.version(), Version::Normal(1));
+        let code = with_eci(
+            "0123456789".repeat(10),
+            eci::UTF_8,
+            None,
+            &Variant::Normal,
+            EcLevel::M,
+            None,
+        )
+        .unwrap();
+        assert_eq!(code.version(), Version::Normal(4));
+        let code = with_eci(
+            "QRコード",
+            eci::UTF_8,
+            None,
+            &Variant::Rmqr,
+            EcLevel::M,
+            None,
+        )
+        .unwrap();
+        assert!(code.version().is_rect_micro());
+        assert_eq!(
+            with_eci(
+                "QRコード",
+                eci::UTF_8,
+                None,
+                &Variant::Micro,
+                EcLevel::M,
+                None
+            )
+            .unwrap_err(),
+            QrError::UnsupportedCharacterSet
+        );
+        assert_eq!(
+            with_eci(
+                "0".repeat(7090),
+                eci::UTF_8,
+                None,
+                &Variant::Normal,
+                EcLevel::L,
+                None
+            )
+            .unwrap_err(),
+            QrError::DataTooLong
+        );
+    }
+
+    #[test]
+    fn validate_with_segments() {
+        let segments = [
+            Segment::Eci(eci::UTF_8),
+            Segment::Data(Mode::Byte, "コード".as_bytes().to_vec()),
+            Segment::Data(Mode::Numeric, b"0123456789".to_vec()),
+        ];
+        let code = with_segments(&segments, None, &Variant::Normal, EcLevel::M).unwrap();
+        assert_eq!(code.version(), Version::Normal(2));
+        let code = with_segments(
+            &segments,
+            Some(Version::Normal(5)),
+            &Variant::Normal,
+            EcLevel::M,
+        )
+        .unwrap();
+        assert_eq!(code.version(), Version::Normal(5));
+        let code = with_segments(&segments[1..], None, &Variant::Micro, EcLevel::L).unwrap();
+        assert_eq!(code.version(), Version::Micro(4));
+        assert_eq!(
+            with_segments(&segments, None, &Variant::Micro, EcLevel::L).unwrap_err(),
+            QrError::UnsupportedCharacterSet
+        );
+    }
+
+    #[test]
+    fn validate_structured_append_parity() {
+        assert_eq!(structured_append_parity([]), 0x00);
+        assert_eq!(structured_append_parity(b"QR code"), 0x2e);
+        assert_eq!(structured_append_parity([0xff, 0x0f]), 0xf0);
+    }
+
+    #[test]
+    fn validate_structured_append_header() {
+        let mut bits = Bits::new(Version::Normal(1));
+        push_structured_append_header(&mut bits, 1, 3, 0x6d).unwrap();
+        assert_eq!(bits.into_bytes(), [0b0011_0001, 0b0010_0110, 0b1101_0000]);
+
+        let mut bits = Bits::new(Version::Normal(1));
+        assert_eq!(
+            push_structured_append_header(&mut bits, 16, 16, 0x00),
+            Err(QrError::DataTooLong)
+        );
+
+        let mut bits = Bits::new(Version::Micro(4));
+        assert_eq!(
+            push_structured_append_header(&mut bits, 0, 2, 0x00),
+            Err(QrError::UnsupportedCharacterSet)
+        );
+    }
+
+    #[test]
+    fn validate_data_splitting() {
+        assert_eq!(
+            split_data(b"0123456789", 3, None, None),
+            [&b"012"[..], b"345", b"6789"]
+        );
+        assert_eq!(
+            split_data(b"0123456789", 3, None, Some(&Mode::Kanji)),
+            [&b"01"[..], b"2345", b"6789"]
+        );
+        assert_eq!(split_data(b"0", 2, None, None), [&b""[..], b"0"]);
+        assert_eq!(
+            split_data("aあいう".as_bytes(), 2, None, None),
+            ["aあ".as_bytes(), "いう".as_bytes()]
+        );
+        assert_eq!(
+            split_data(b"ab\xe3\x81\x82\xff", 2, Some(eci::UTF_8), None),
+            [&b"ab"[..], b"\xe3\x81\x82\xff"]
+        );
+        assert_eq!(
+            split_data(b"ab\xe3\x81\x82\xff", 2, None, None),
+            [&b"ab\xe3"[..], b"\x81\x82\xff"]
+        );
+    }
+
+    #[test]
+    fn validate_structured_append() {
+        let data = "0123456789".repeat(800);
+
+        let codes = to_structured_append(&data, None, None, EcLevel::L, None).unwrap();
+        assert_eq!(codes.len(), 2);
+        assert!(
+            codes
+                .iter()
+                .all(|code| code.version() == Version::Normal(30))
+        );
+
+        let codes =
+            to_structured_append(&data, None, Some(Version::Normal(10)), EcLevel::M, None).unwrap();
+        assert_eq!(codes.len(), 16);
+
+        assert_eq!(
+            to_structured_append(&data, None, Some(Version::Normal(1)), EcLevel::M, None)
+                .unwrap_err(),
+            QrError::DataTooLong
+        );
+    }
+
     #[test]
     fn validate_metadata_extraction() {
         const DATA: [u8; 0] = [];
@@ -0,0 +1,577 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    error, fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::bitstream::GROUP_SEPARATOR;
+
+/// The format of the data field of an application identifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// Digits.
+    Numeric,
+
+    /// Digits whose last digit is a GS1 check digit.
+    CheckDigit,
+
+    /// A date in `YYMMDD` format.
+    Date,
+
+    /// Characters in GS1 AI encodable character set 82.
+    Alphanumeric,
+}
+
+/// An application identifier.
+#[derive(Clone, Copy, Debug)]
+struct ApplicationIdentifier {
+    /// The pattern of the application identifier, where `n` matches any digit.
+    pattern: &'static str,
+
+    /// The minimum length of the data field.
+    min_len: usize,
+
+    /// The maximum length of the data field.
+    max_len: usize,
+
+    /// The format of the data field.
+    format: Format,
+}
+
+const fn ai(
+    pattern: &'static str,
+    min_len: usize,
+    max_len: usize,
+    format: Format,
+) -> ApplicationIdentifier {
+    ApplicationIdentifier {
+        pattern,
+        min_len,
+        max_len,
+        format,
+    }
+}
+
+// This table is based on the GS1 General Specifications.
+/// The application identifiers which can be encoded.
+const APPLICATION_IDENTIFIERS: [ApplicationIdentifier; 72] = {
+    use Format::{Alphanumeric as X, CheckDigit as C, Date as D, Numeric as N};
+
+    [
+        ai("00", 18, 18, C),
+        ai("01", 14, 14, C),
+        ai("02", 14, 14, C),
+        ai("10", 1, 20, X),
+        ai("11", 6, 6, D),
+        ai("12", 6, 6, D),
+        ai("13", 6, 6, D),
+        ai("15", 6, 6, D),
+        ai("16", 6, 6, D),
+        ai("17", 6, 6, D),
+        ai("20", 2, 2, N),
+        ai("21", 1, 20, X),
+        ai("22", 1, 20, X),
+        ai("235", 1, 28, X),
+        ai("240", 1, 30, X),
+        ai("241", 1, 30, X),
+        ai("242", 1, 6, N),
+        ai("243", 1, 20, X),
+        ai("250", 1, 30, X),
+        ai("251", 1, 30, X),
+        ai("253", 14, 30, X),
+        ai("254", 1, 20, X),
+        ai("255", 14, 25, N),
+        ai("30", 1, 8, N),
+        ai("31nn", 6, 6, N),
+        ai("32nn", 6, 6, N),
+        ai("33nn", 6, 6, N),
+        ai("34nn", 6, 6, N),
+        ai("35nn", 6, 6, N),
+        ai("36nn", 6, 6, N),
+        ai("37", 1, 8, N),
+        ai("390n", 1, 15, N),
+        ai("391n", 4, 18, N),
+        ai("392n", 1, 15, N),
+        ai("393n", 4, 18, N),
+        ai("394n", 4, 4, N),
+        ai("395n", 6, 6, N),
+        ai("400", 1, 30, X),
+        ai("401", 1, 30, X),
+        ai("402", 17, 17, C),
+        ai("403", 1, 30, X),
+        ai("41n", 13, 13, C),
+        ai("420", 1, 20, X),
+        ai("421", 4, 12, X),
+        ai("422", 3, 3, N),
+        ai("423", 3, 15, N),
+        ai("424", 3, 3, N),
+        ai("425", 3, 15, N),
+        ai("426", 3, 3, N),
+        ai("427", 1, 3, X),
+        ai("7001", 13, 13, N),
+        ai("7002", 1, 30, X),
+        ai("7003", 10, 10, N),
+        ai("7004", 1, 4, N),
+        ai("7005", 1, 12, X),
+        ai("7006", 6, 6, D),
+        ai("7007", 6, 12, N),
+        ai("703n", 4, 30, X),
+        ai("8001", 14, 14, N),
+        ai("8002", 1, 20, X),
+        ai("8003", 15, 30, X),
+        ai("8004", 1, 30, X),
+        ai("8005", 6, 6, N),
+        ai("8006", 18, 18, N),
+        ai("8007", 1, 34, X),
+        ai("8008", 8, 12, N),
+        ai("8017", 18, 18, C),
+        ai("8018", 18, 18, C),
+        ai("8020", 1, 25, X),
+        ai("8200", 1, 70, X),
+        ai("90", 1, 30, X),
+        ai("9n", 1, 90, X),
+    ]
+};
+
+/// The prefixes of the application identifiers whose data field has a
+/// predefined length and is therefore not terminated by a group separator.
+const PREDEFINED_LENGTH_PREFIXES: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+/// The characters other than alphanumerics in GS1 AI encodable character set
+/// 82.
+const CSET_82_SYMBOLS: &[u8; 20] = b"!\"%&'()*+,-./:;<=>?_";
+
+impl ApplicationIdentifier {
+    /// Returns the application identifier which matches the beginning of the
+    /// data.
+    fn find(data: &str) -> Option<Self> {
+        APPLICATION_IDENTIFIERS.into_iter().find(|ai| {
+            data.len() >= ai.pattern.len()
+                && ai
+                    .pattern
+                    .bytes()
+                    .zip(data.bytes())
+                    .all(|(p, d)| d.is_ascii_digit() && (p == b'n' || p == d))
+        })
+    }
+
+    /// Returns the length of the application identifier.
+    const fn len(&self) -> usize {
+        self.pattern.len()
+    }
+
+    /// Returns [`true`] if the data field has a predefined length.
+    fn has_predefined_length(ai: &str) -> bool {
+        PREDEFINED_LENGTH_PREFIXES
+            .iter()
+            .any(|prefix| ai.starts_with(prefix))
+    }
+
+    /// Validates the data field of the application identifier.
+    fn validate(&self, ai: &str, data: &str) -> Result<(), Error> {
+        let error = |kind| Error::InvalidData {
+            ai: ai.to_owned(),
+            kind,
+        };
+        if !(self.min_len..=self.max_len).contains(&data.len()) {
+            return Err(error(InvalidDataKind::Length {
+                min: self.min_len,
+                max: self.max_len,
+            }));
+        }
+        let is_valid_char = |b: &u8| match self.format {
+            Format::Alphanumeric => b.is_ascii_alphanumeric() || CSET_82_SYMBOLS.contains(b),
+            _ => b.is_ascii_digit(),
+        };
+        if !data.bytes().all(|b| is_valid_char(&b)) {
+            return Err(error(InvalidDataKind::Character));
+        }
+        match self.format {
+            Format::CheckDigit if !is_valid_check_digit(data) => {
+                Err(error(InvalidDataKind::CheckDigit))
+            }
+            Format::Date if !is_valid_date(data) => Err(error(InvalidDataKind::Date)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns [`true`] if the last digit is the valid GS1 check digit.
+fn is_valid_check_digit(digits: &str) -> bool {
+    let (body, check_digit) = digits.split_at(digits.len() - 1);
+    let sum = body
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| u32::from(d - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum::<u32>();
+    u32::from(check_digit.as_bytes()[0] - b'0') == (10 - sum % 10) % 10
+}
+
+/// Returns [`true`] if the digits are a valid date in `YYMMDD` format.
+///
+/// The day `00` means the last day of the month.
+fn is_valid_date(digits: &str) -> bool {
+    is_valid_date_in(digits, current_year())
+}
+
+/// Returns [`true`] if the digits are a valid date in `YYMMDD` format as of
+/// `current_year`.
+///
+/// The century is determined by the rule of the GS1 General Specifications:
+/// the year is in the previous century if it is 51 to 99 years ahead of the
+/// current year, in the next century if it is 50 to 99 years behind, and in
+/// the current century otherwise.
+fn is_valid_date_in(digits: &str, current_year: u32) -> bool {
+    let year = digits[..2].parse::<u32>().unwrap_or_default();
+    let month = digits[2..4].parse::<u32>().unwrap_or_default();
+    let day = digits[4..6].parse::<u32>().unwrap_or_default();
+    let century = match i64::from(year) - i64::from(current_year % 100) {
+        51..=99 => current_year / 100 - 1,
+        -99..=-50 => current_year / 100 + 1,
+        _ => current_year / 100,
+    };
+    (1..=12).contains(&month) && day <= days_in_month(century * 100 + year, month)
+}
+
+const fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the current year in UTC.
+fn current_year() -> u32 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86400);
+    // Converts the days since 1970-01-01 to the year of the proleptic Gregorian
+    // calendar, where a year starts on March 1 to put the leap day at the end.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let year = era * 400 + year_of_era + u64::from(day_of_year >= 306);
+    u32::try_from(year).unwrap_or(u32::MAX)
+}
+
+/// The reason why the data field of an application identifier is invalid.
+#[derive(Debug, Eq, PartialEq)]
+pub enum InvalidDataKind {
+    /// The length is out of range.
+    Length { min: usize, max: usize },
+
+    /// It contains an invalid character.
+    Character,
+
+    /// The check digit is wrong.
+    CheckDigit,
+
+    /// It is not a valid date.
+    Date,
+}
+
+/// An error which can be returned when parsing a GS1 element string.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The element string is not in `(AI)data` format.
+    InvalidSyntax,
+
+    /// The application identifier is unknown.
+    UnknownApplicationIdentifier(String),
+
+    /// The data field of the application identifier is invalid.
+    InvalidData { ai: String, kind: InvalidDataKind },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSyntax => write!(
+                f,
+                "GS1 element string must consist of application identifiers in parentheses followed by their data"
+            ),
+            Self::UnknownApplicationIdentifier(ai) => {
+                write!(f, "unknown GS1 application identifier ({ai})")
+            }
+            Self::InvalidData { ai, kind } => {
+                write!(f, "invalid data for GS1 application identifier ({ai}): ")?;
+                match kind {
+                    InvalidDataKind::Length { min, max } if min == max => {
+                        write!(f, "expected {min} characters")
+                    }
+                    InvalidDataKind::Length { min, max } => {
+                        write!(f, "expected {min} to {max} characters")
+                    }
+                    InvalidDataKind::Character => write!(f, "contains invalid characters"),
+                    InvalidDataKind::CheckDigit => write!(f, "invalid check digit"),
+                    InvalidDataKind::Date => write!(f, "invalid date"),
+                }
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Returns the data title of the application identifier.
+///
+/// Returns [`None`] if the application identifier is not commonly used.
+pub fn title(ai: &str) -> Option<&'static str> {
+    let title = match ai {
+        "00" => "SSCC",
+        "01" => "GTIN",
+        "02" => "CONTENT",
+        "10" => "BATCH/LOT",
+        "11" => "PROD DATE",
+        "12" => "DUE DATE",
+        "13" => "PACK DATE",
+        "15" => "BEST BEFORE or BEST BY",
+        "16" => "SELL BY",
+        "17" => "USE BY or EXPIRY",
+        "20" => "VARIANT",
+        "21" => "SERIAL",
+        "22" => "CPV",
+        "30" => "VAR. COUNT",
+        "37" => "COUNT",
+        "400" => "ORDER NUMBER",
+        "410" => "SHIP TO LOC",
+        "414" => "LOC No.",
+        "420" => "SHIP TO POST",
+        "422" => "ORIGIN",
+        "7003" => "EXPIRY TIME",
+        "8020" => "REF No.",
+        ai if ai.starts_with("310") => "NET WEIGHT (kg)",
+        ai if ai.starts_with("392") => "PRICE",
+        _ => return None,
+    };
+    Some(title)
+}
+
+/// Parses the GS1 element string in human readable form such as
+/// `(01)09501101530003(17)250101`, and returns the pairs of the application
+/// identifier and the data field.
+pub fn elements(element_string: &str) -> Result<Vec<(&str, &str)>, Error> {
+    let mut elements = Vec::new();
+    let mut rest = element_string
+        .trim_end_matches(['\n', '\r'])
+        .strip_prefix('(')
+        .ok_or(Error::InvalidSyntax)?;
+    loop {
+        let (ai, tail) = rest.split_once(')').ok_or(Error::InvalidSyntax)?;
+        let (data, tail) = tail
+            .split_once('(')
+            .map_or((tail, None), |(d, t)| (d, Some(t)));
+        let definition = ApplicationIdentifier::find(ai)
+            .filter(|definition| definition.len() == ai.len())
+            .ok_or_else(|| Error::UnknownApplicationIdentifier(ai.to_owned()))?;
+        definition.validate(ai, data)?;
+        elements.push((ai, data));
+        match tail {
+            Some(tail) => rest = tail,
+            None => break,
+        }
+    }
+    Ok(elements)
+}
+
+/// Parses the GS1 element string in human readable form such as
+/// `(01)09501101530003(17)250101`, and returns the data to be encoded.
+///
+/// The data fields of the application identifiers which do not have a
+/// predefined length are terminated by a group separator (GS) unless they are
+/// at the end.
+pub fn parse(element_string: &str) -> Result<Vec<u8>, Error> {
+    let elements = elements(element_string)?;
+    let mut buf = Vec::with_capacity(element_string.len());
+    for (i, (ai, data)) in elements.iter().enumerate() {
+        buf.extend_from_slice(ai.as_bytes());
+        buf.extend_from_slice(data.as_bytes());
+        if i + 1 < elements.len() && !ApplicationIdentifier::has_predefined_length(ai) {
+            buf.push(GROUP_SEPARATOR);
+        }
+    }
+    Ok(buf)
+}
+
+/// Converts the data encoded in FNC1 in first position to the GS1 element
+/// string in human readable form.
+///
+/// Returns [`None`] if the data is not a valid GS1 element string.
+pub fn to_element_string(data: &[u8]) -> Option<String> {
+    let mut data = std::str::from_utf8(data).ok()?;
+    let mut element_string = String::with_capacity(data.len() * 2);
+    while !data.is_empty() {
+        let definition = ApplicationIdentifier::find(data)?;
+        let (ai, rest) = data.split_at(definition.len());
+        let end = if ApplicationIdentifier::has_predefined_length(ai) {
+            definition.max_len.min(rest.len())
+        } else {
+            rest.find(char::from(GROUP_SEPARATOR)).unwrap_or(rest.len())
+        };
+        let (field, rest) = rest.split_at(end);
+        definition.validate(ai, field).ok()?;
+        element_string.push('(');
+        element_string.push_str(ai);
+        element_string.push(')');
+        element_string.push_str(field);
+        data = rest
+            .strip_prefix(char::from(GROUP_SEPARATOR))
+            .unwrap_or(rest);
+    }
+    Some(element_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_check_digit() {
+        assert!(is_valid_check_digit("09501101530003"));
+        assert!(is_valid_check_digit("00012345600012"));
+        assert!(is_valid_check_digit("376104250021234569"));
+        assert!(!is_valid_check_digit("09501101530004"));
+    }
+
+    #[test]
+    fn validate_date() {
+        assert!(is_valid_date("250101"));
+        assert!(is_valid_date("251200"));
+        assert!(!is_valid_date("251301"));
+        assert!(!is_valid_date("250132"));
+        assert!(!is_valid_date("250001"));
+        assert!(!is_valid_date("250231"));
+        assert!(!is_valid_date("250431"));
+        assert!(is_valid_date("250430"));
+        assert!(is_valid_date("250200"));
+    }
+
+    #[test]
+    fn validate_date_in_leap_year() {
+        assert!(is_valid_date_in("240229", 2026));
+        assert!(!is_valid_date_in("250229", 2026));
+        // 2000 is a leap year.
+        assert!(is_valid_date_in("000229", 2026));
+        // 2100 is not a leap year.
+        assert!(!is_valid_date_in("000229", 2060));
+        // 1900 is not a leap year.
+        assert!(!is_valid_date_in("000229", 1949));
+        assert!(is_valid_date_in("960229", 2046));
+    }
+
+    #[test]
+    fn parse_element_string() {
+        assert_eq!(
+            parse("(01)09501101530003(17)250101(10)ABC").unwrap(),
+            b"01095011015300031725010110ABC"
+        );
+        assert_eq!(parse("(10)ABC(21)12345\n").unwrap(), b"10ABC\x1d2112345");
+        assert_eq!(
+            parse("(3103)000189(30)12(3922)995").unwrap(),
+            b"31030001893012\x1d3922995"
+        );
+    }
+
+    #[test]
+    fn split_element_string() {
+        assert_eq!(
+            elements("(01)09501101530003(10)ABC123(3103)000189").unwrap(),
+            [
+                ("01", "09501101530003"),
+                ("10", "ABC123"),
+                ("3103", "000189")
+            ]
+        );
+        assert_eq!(title("01"), Some("GTIN"));
+        assert_eq!(title("3103"), Some("NET WEIGHT (kg)"));
+        assert_eq!(title("7001"), None);
+    }
+
+    #[test]
+    fn parse_invalid_element_string() {
+        assert_eq!(parse("0109501101530003").unwrap_err(), Error::InvalidSyntax);
+        assert_eq!(parse("(01").unwrap_err(), Error::InvalidSyntax);
+        assert_eq!(
+            parse("(05)123").unwrap_err(),
+            Error::UnknownApplicationIdentifier("05".to_owned())
+        );
+        assert_eq!(
+            parse("(010)9501101530003").unwrap_err(),
+            Error::UnknownApplicationIdentifier("010".to_owned())
+        );
+        assert_eq!(
+            parse("(01)09501101530004").unwrap_err(),
+            Error::InvalidData {
+                ai: "01".to_owned(),
+                kind: InvalidDataKind::CheckDigit
+            }
+        );
+        assert_eq!(
+            parse("(01)0950110153000").unwrap_err(),
+            Error::InvalidData {
+                ai: "01".to_owned(),
+                kind: InvalidDataKind::Length { min: 14, max: 14 }
+            }
+        );
+        assert_eq!(
+            parse("(17)251301").unwrap_err(),
+            Error::InvalidData {
+                ai: "17".to_owned(),
+                kind: InvalidDataKind::Date
+            }
+        );
+        assert_eq!(
+            parse("(10)AB C").unwrap_err(),
+            Error::InvalidData {
+                ai: "10".to_owned(),
+                kind: InvalidDataKind::Character
+            }
+        );
+    }
+
+    #[test]
+    fn convert_to_element_string() {
+        assert_eq!(
+            to_element_string(b"01095011015300031725010110ABC").unwrap(),
+            "(01)09501101530003(17)250101(10)ABC"
+        );
+        assert_eq!(
+            to_element_string(b"10ABC\x1d2112345").unwrap(),
+            "(10)ABC(21)12345"
+        );
+        assert_eq!(to_element_string(b"0509501101530003"), None);
+        assert_eq!(to_element_string(b"0109501101530004"), None);
+    }
+
+    #[test]
+    fn display_error() {
+        assert_eq!(
+            Error::InvalidData {
+                ai: "01".to_owned(),
+                kind: InvalidDataKind::CheckDigit
+            }
+            .to_string(),
+            "invalid data for GS1 application identifier (01): invalid check digit"
+        );
+        assert_eq!(
+            Error::InvalidData {
+                ai: "10".to_owned(),
+                kind: InvalidDataKind::Length { min: 1, max: 20 }
+            }
+            .to_string(),
+            "invalid data for GS1 application identifier (10): expected 1 to 20 characters"
+        );
+    }
+}
@@ -3,11 +3,20 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 mod app;
+mod bitstream;
+mod capacity;
 mod cli;
 mod decode;
+mod eci;
 mod encode;
+mod gs1;
 mod input;
+mod mask;
 mod metadata;
+mod parse;
+mod payload;
+mod reed_solomon;
+mod segment;
 
 use std::{io, process::ExitCode};
 
@@ -17,6 +26,8 @@ use qrcode2::types::QrError;
 use resvg::usvg;
 use rqrr::DeQRError;
 
+use crate::decode::StructuredAppendError;
+
 fn main() -> ExitCode {
     match app::run() {
         Ok(()) => ExitCode::SUCCESS,
@@ -25,7 +36,18 @@ fn main() -> ExitCode {
             if let Some(e) = err.downcast_ref::<io::Error>() {
                 return sysexits::ExitCode::from(e.kind()).into();
             }
-            if err.is::<QrError>() {
+            if err.is::<QrError>()
+                || err.is::<gs1::Error>()
+                || err.is::<capacity::DataTooLongError>()
+                || err.is::<parse::Error>()
+                || err.is::<payload::contact::Error>()
+                || err.is::<payload::emv::Error>()
+                || err.is::<payload::epc::Error>()
+                || err.is::<payload::event::Error>()
+                || err.is::<payload::otp::Error>()
+                || err.is::<payload::qr_bill::Error>()
+                || err.is::<payload::wifi::Error>()
+            {
                 return sysexits::ExitCode::DataErr.into();
             }
             if let Some(e) = err.downcast_ref::<DeQRError>() {
@@ -35,6 +57,17 @@ fn main() -> ExitCode {
                     sysexits::ExitCode::DataErr.into()
                 };
             }
+            if let Some(e) = err.downcast_ref::<StructuredAppendError>() {
+                return match e {
+                    StructuredAppendError::MissingSymbols { .. }
+                    | StructuredAppendError::DuplicatedSymbol { .. } => {
+                        sysexits::ExitCode::NoInput.into()
+                    }
+                    StructuredAppendError::ParityMismatch { .. } => {
+                        sysexits::ExitCode::DataErr.into()
+                    }
+                };
+            }
             if let Some(e) = err.downcast_ref::<ImageError>() {
                 return match e {
                     ImageError::Limits(_) => sysexits::ExitCode::OsErr.into(),
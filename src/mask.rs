@@ -0,0 +1,384 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fmt;
+
+use qrcode2::{
+    Color, QrCode, Version,
+    canvas::{Canvas, MaskPattern, Module},
+};
+
+/// The mask patterns of QR code in order of the mask pattern reference.
+const PATTERNS: [MaskPattern; 8] = [
+    MaskPattern::Checkerboard,
+    MaskPattern::HorizontalLines,
+    MaskPattern::VerticalLines,
+    MaskPattern::DiagonalLines,
+    MaskPattern::LargeCheckerboard,
+    MaskPattern::Fields,
+    MaskPattern::Diamonds,
+    MaskPattern::Meadow,
+];
+
+/// The mask patterns of Micro QR code in order of the mask pattern reference.
+const MICRO_PATTERNS: [MaskPattern; 4] = [
+    MaskPattern::HorizontalLines,
+    MaskPattern::LargeCheckerboard,
+    MaskPattern::Diamonds,
+    MaskPattern::Meadow,
+];
+
+/// Returns the mask patterns available for the version.
+///
+/// rMQR code has only one fixed mask pattern.
+const fn patterns(version: Version) -> &'static [MaskPattern] {
+    match version {
+        Version::Normal(_) => &PATTERNS,
+        Version::Micro(_) => &MICRO_PATTERNS,
+        Version::RectMicro(..) => &[MaskPattern::LargeCheckerboard],
+    }
+}
+
+/// Returns the number of the mask patterns available for the version.
+pub const fn count(version: Version) -> u8 {
+    match version {
+        Version::Normal(_) => 8,
+        Version::Micro(_) => 4,
+        Version::RectMicro(..) => 1,
+    }
+}
+
+/// Returns [`true`] if the module at the given coordinates is inverted by the
+/// mask pattern.
+///
+/// `x` is the column and `y` is the row.
+const fn is_inverted(pattern: MaskPattern, x: usize, y: usize) -> bool {
+    match pattern {
+        MaskPattern::Checkerboard => (y + x) % 2 == 0,
+        MaskPattern::HorizontalLines => y % 2 == 0,
+        MaskPattern::VerticalLines => x % 3 == 0,
+        MaskPattern::DiagonalLines => (y + x) % 3 == 0,
+        MaskPattern::LargeCheckerboard => (y / 2 + x / 3) % 2 == 0,
+        MaskPattern::Fields => (y * x) % 2 + (y * x) % 3 == 0,
+        MaskPattern::Diamonds => ((y * x) % 2 + (y * x) % 3) % 2 == 0,
+        MaskPattern::Meadow => ((y + x) % 2 + (y * x) % 3) % 2 == 0,
+    }
+}
+
+/// Draws the symbol with the mask pattern, assuming that the modules of
+/// `code` are masked with `current`.
+fn remask(code: &QrCode, current: MaskPattern, pattern: MaskPattern) -> Vec<Color> {
+    let mut canvas = Canvas::new(code.version(), code.error_correction_level());
+    canvas.draw_all_functional_patterns();
+    for y in 0..code.height() {
+        for x in 0..code.width() {
+            if code.is_functional(x, y) {
+                continue;
+            }
+            let color = if is_inverted(current, x, y) {
+                !code[(x, y)]
+            } else {
+                code[(x, y)]
+            };
+            let (i, j) = (
+                i16::try_from(x).expect("invalid coordinate"),
+                i16::try_from(y).expect("invalid coordinate"),
+            );
+            *canvas.get_mut(i, j) = Module::Unmasked(color);
+        }
+    }
+    canvas.apply_mask(pattern);
+    canvas.into_colors()
+}
+
+/// Returns the mask pattern reference of the symbol.
+pub fn detect(code: &QrCode) -> Option<u8> {
+    if code.version().is_rect_micro() {
+        return Some(0);
+    }
+    let colors = code.to_colors();
+    patterns(code.version())
+        .iter()
+        .position(|&pattern| remask(code, pattern, pattern) == colors)
+        .and_then(|reference| u8::try_from(reference).ok())
+}
+
+/// Returns the modules of the symbol masked with the mask pattern specified by
+/// the mask pattern reference.
+///
+/// Returns [`None`] if the mask pattern is not available for the symbol.
+pub fn apply(code: &QrCode, reference: u8) -> Option<Vec<Color>> {
+    if code.version().is_rect_micro() {
+        return (reference == 0).then(|| code.to_colors());
+    }
+    let patterns = patterns(code.version());
+    let current = patterns[usize::from(detect(code)?)];
+    let pattern = *patterns.get(usize::from(reference))?;
+    Some(remask(code, current, pattern))
+}
+
+/// The evaluation result of a masked symbol.
+///
+/// ISO/IEC 18004 recommends choosing the mask pattern with the best total, but
+/// the encoder chooses the mask pattern by its own heuristic. So the mask
+/// pattern chosen by the encoder does not necessarily have the best total.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Evaluation {
+    /// The penalty points of QR code. The lowest total is the best according
+    /// to ISO/IEC 18004.
+    Penalty {
+        /// Adjacent modules in row/column in same color.
+        n1: u32,
+
+        /// Block of modules in same color.
+        n2: u32,
+
+        /// Finder-like patterns in row/column.
+        n3: u32,
+
+        /// Proportion of dark modules in entire symbol.
+        n4: u32,
+    },
+
+    /// The score of Micro QR code. The highest total is the best according to
+    /// ISO/IEC 18004.
+    Score {
+        /// The number of dark modules in the right side edge.
+        sum1: u32,
+
+        /// The number of dark modules in the lower side edge.
+        sum2: u32,
+    },
+}
+
+impl Evaluation {
+    /// Returns the total of the evaluation result.
+    pub const fn total(self) -> u32 {
+        match self {
+            Self::Penalty { n1, n2, n3, n4 } => n1 + n2 + n3 + n4,
+            Self::Score { sum1, sum2 } if sum1 <= sum2 => sum1 * 16 + sum2,
+            Self::Score { sum1, sum2 } => sum2 * 16 + sum1,
+        }
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Penalty { n1, n2, n3, n4 } => write!(
+                f,
+                "N1 {n1}, N2 {n2}, N3 {n3}, N4 {n4}, penalty {}",
+                self.total()
+            ),
+            Self::Score { sum1, sum2 } => {
+                write!(f, "SUM1 {sum1}, SUM2 {sum2}, score {}", self.total())
+            }
+        }
+    }
+}
+
+// The penalty rules are based on ISO/IEC 18004:2015 7.8.3.1.
+/// Computes the penalty points for the adjacent modules in the same color.
+fn adjacent_penalty(lines: &[Vec<bool>]) -> u32 {
+    lines
+        .iter()
+        .flat_map(|line| line.chunk_by(|a, b| a == b))
+        .filter(|run| run.len() >= 5)
+        .map(|run| u32::try_from(run.len()).expect("run is too long") - 2)
+        .sum()
+}
+
+/// Computes the penalty points for the 2x2 blocks of modules in the same color.
+fn block_penalty(rows: &[Vec<bool>]) -> u32 {
+    let blocks = rows
+        .windows(2)
+        .flat_map(|pair| {
+            pair[0]
+                .windows(2)
+                .zip(pair[1].windows(2))
+                .filter(|(upper, lower)| upper[0] == upper[1] && upper == lower)
+        })
+        .count();
+    u32::try_from(blocks).expect("too many blocks") * 3
+}
+
+/// Computes the penalty points for the 1:1:3:1:1 patterns preceded or followed
+/// by 4 light modules.
+///
+/// The area outside the symbol is regarded as light.
+fn finder_penalty(lines: &[Vec<bool>]) -> u32 {
+    const BEFORE: [bool; 11] = [
+        false, false, false, false, true, false, true, true, true, false, true,
+    ];
+    const AFTER: [bool; 11] = [
+        true, false, true, true, true, false, true, false, false, false, false,
+    ];
+
+    let patterns = lines
+        .iter()
+        .map(|line| {
+            let padded = [&[false; 4][..], line, &[false; 4]].concat();
+            padded
+                .windows(11)
+                .filter(|window| *window == BEFORE || *window == AFTER)
+                .count()
+        })
+        .sum::<usize>();
+    u32::try_from(patterns).expect("too many patterns") * 40
+}
+
+/// Computes the penalty points for the proportion of dark modules.
+fn balance_penalty(rows: &[Vec<bool>]) -> u32 {
+    let total = rows.iter().map(Vec::len).sum::<usize>();
+    let dark = rows.iter().flatten().filter(|&&is_dark| is_dark).count();
+    let deviation = (dark * 100).abs_diff(total * 50) / total;
+    u32::try_from(deviation / 5).expect("invalid deviation") * 10
+}
+
+/// Evaluates the modules of the symbol of the version.
+///
+/// Returns [`None`] for rMQR code since it does not evaluate the mask pattern.
+pub fn evaluate(version: Version, colors: &[Color], width: usize) -> Option<Evaluation> {
+    let rows = colors
+        .chunks(width)
+        .map(|row| row.iter().map(|&c| c == Color::Dark).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    match version {
+        Version::Normal(_) => {
+            let columns = (0..width)
+                .map(|x| rows.iter().map(|row| row[x]).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            Some(Evaluation::Penalty {
+                n1: adjacent_penalty(&rows) + adjacent_penalty(&columns),
+                n2: block_penalty(&rows),
+                n3: finder_penalty(&rows) + finder_penalty(&columns),
+                n4: balance_penalty(&rows),
+            })
+        }
+        Version::Micro(_) => {
+            let count = |modules: &mut dyn Iterator<Item = bool>| {
+                u32::try_from(modules.filter(|&is_dark| is_dark).count()).expect("too many modules")
+            };
+            Some(Evaluation::Score {
+                sum1: count(&mut rows.iter().skip(1).map(|row| row[width - 1])),
+                sum2: count(&mut rows[rows.len() - 1].iter().skip(1).copied()),
+            })
+        }
+        Version::RectMicro(..) => None,
+    }
+}
+
+/// Evaluates the symbol with every mask pattern.
+///
+/// Returns an empty vector for rMQR code since it does not evaluate the mask
+/// pattern.
+pub fn evaluate_all(code: &QrCode) -> Vec<(u8, Evaluation)> {
+    if code.version().is_rect_micro() {
+        return Vec::new();
+    }
+    (0..count(code.version()))
+        .filter_map(|reference| {
+            let colors = apply(code, reference)?;
+            let evaluation = evaluate(code.version(), &colors, code.width())?;
+            Some((reference, evaluation))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use qrcode2::EcLevel;
+
+    use super::*;
+
+    #[test]
+    fn detect_mask_pattern() {
+        for data in ["QR code", "0123456789", "HTTPS://A.JP"] {
+            let code = QrCode::new(data).unwrap();
+            assert!(detect(&code).is_some());
+            let code = QrCode::new_micro(data).unwrap();
+            assert!(detect(&code).is_some());
+        }
+        let code = QrCode::new_rect_micro(b"QR code").unwrap();
+        assert_eq!(detect(&code), Some(0));
+    }
+
+    #[test]
+    fn apply_mask_pattern() {
+        let code = QrCode::with_error_correction_level(b"QR code", EcLevel::M).unwrap();
+        let current = detect(&code).unwrap();
+        assert_eq!(apply(&code, current).unwrap(), code.to_colors());
+        for reference in (0..8).filter(|&r| r != current) {
+            let colors = apply(&code, reference).unwrap();
+            assert_ne!(colors, code.to_colors());
+        }
+        assert_eq!(apply(&code, 8), None);
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        assert!(apply(&code, 3).is_some());
+        assert_eq!(apply(&code, 4), None);
+    }
+
+    #[test]
+    fn compute_penalty() {
+        let rows = [
+            vec![true, true, true, true, true, true, false],
+            vec![true, true, false, false, false, false, false],
+        ];
+        assert_eq!(adjacent_penalty(&rows), 4 + 3);
+        assert_eq!(block_penalty(&rows), 3);
+        assert_eq!(block_penalty(&[vec![true; 3], vec![true; 3]]), 6);
+        assert_eq!(
+            finder_penalty(&[vec![true, false, true, true, true, false, true]]),
+            80
+        );
+        assert_eq!(
+            finder_penalty(&[vec![
+                true, false, true, true, true, false, true, true, false, false, false, false
+            ]]),
+            40
+        );
+        assert_eq!(balance_penalty(&[vec![true, false]]), 0);
+        assert_eq!(balance_penalty(&[vec![true, true, true, false]]), 50);
+        assert_eq!(balance_penalty(&[vec![true; 4]]), 100);
+    }
+
+    #[test]
+    fn evaluate_symbol() {
+        let code = QrCode::new(b"QR code").unwrap();
+        let evaluations = evaluate_all(&code);
+        assert_eq!(evaluations.len(), 8);
+        assert!(
+            evaluations
+                .iter()
+                .all(|(_, evaluation)| matches!(evaluation, Evaluation::Penalty { .. }))
+        );
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let evaluations = evaluate_all(&code);
+        assert_eq!(evaluations.len(), 4);
+        assert!(
+            evaluations
+                .iter()
+                .all(|(_, evaluation)| matches!(evaluation, Evaluation::Score { .. }))
+        );
+        let code = QrCode::new_rect_micro(b"QR code").unwrap();
+        assert!(evaluate_all(&code).is_empty());
+    }
+
+    #[test]
+    fn total_evaluation() {
+        let penalty = Evaluation::Penalty {
+            n1: 180,
+            n2: 90,
+            n3: 80,
+            n4: 0,
+        };
+        assert_eq!(penalty.total(), 350);
+        assert_eq!(
+            penalty.to_string(),
+            "N1 180, N2 90, N3 80, N4 0, penalty 350"
+        );
+        assert_eq!(Evaluation::Score { sum1: 4, sum2: 5 }.total(), 69);
+        assert_eq!(Evaluation::Score { sum1: 5, sum2: 4 }.total(), 69);
+    }
+}
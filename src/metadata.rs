@@ -4,6 +4,8 @@
 
 use std::fmt;
 
+use serde::{Serialize, Serializer};
+
 use crate::cli::Ecc;
 
 /// Version for a QR code.
@@ -34,11 +36,43 @@ impl fmt::Display for Version {
     }
 }
 
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// FNC1 mode of a QR code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fnc1 {
+    /// The data is formatted according to the GS1 General Specifications.
+    First,
+
+    /// The data is formatted according to an industry application specified
+    /// by the application indicator.
+    Second(u8),
+}
+
+impl fmt::Display for Fnc1 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::First => write!(f, "first position (GS1)"),
+            Self::Second(indicator) => {
+                write!(f, "second position (application indicator {indicator})")
+            }
+        }
+    }
+}
+
 /// Metadata for a QR code.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Serialize)]
 pub struct Metadata {
     symbol_version: Version,
     error_correction_level: Ecc,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fnc1: Option<Fnc1>,
 }
 
 pub trait Extractor {
@@ -52,9 +86,16 @@ impl Metadata {
         Self {
             symbol_version,
             error_correction_level,
+            fnc1: None,
         }
     }
 
+    /// Sets the FNC1 mode.
+    pub const fn with_fnc1(mut self, fnc1: Option<Fnc1>) -> Self {
+        self.fnc1 = fnc1;
+        self
+    }
+
     /// Gets the symbol version.
     pub const fn symbol_version(&self) -> Version {
         self.symbol_version
@@ -64,4 +105,9 @@ impl Metadata {
     pub const fn error_correction_level(&self) -> Ecc {
         self.error_correction_level
     }
+
+    /// Gets the FNC1 mode.
+    pub const fn fnc1(&self) -> Option<Fnc1> {
+        self.fnc1
+    }
 }
@@ -0,0 +1,544 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+use serde::Serialize;
+
+use crate::{
+    gs1,
+    payload::{self, contact, emv, epc, otp, wifi},
+};
+
+/// The format of the decoded data.
+#[allow(clippy::doc_markdown)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// A URL of a website.
+    Url,
+
+    /// The credentials of a Wi-Fi network.
+    Wifi,
+
+    /// A vCard.
+    Vcard,
+
+    /// A MeCard.
+    Mecard,
+
+    /// A `geo` URI.
+    Geo,
+
+    /// A `tel` URI.
+    Tel,
+
+    /// A `mailto` URI or a `MATMSG`.
+    Email,
+
+    /// An `sms` URI or an `SMSTO`.
+    Sms,
+
+    /// An `otpauth` URI.
+    Otpauth,
+
+    /// An EPC QR code.
+    Epc,
+
+    /// An EMV QR code for the merchant-presented mode.
+    Emv,
+
+    /// A GS1 element string.
+    Gs1,
+
+    /// Plain text.
+    Text,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Url => "URL",
+            Self::Wifi => "Wi-Fi",
+            Self::Vcard => "vCard",
+            Self::Mecard => "MeCard",
+            Self::Geo => "geo URI",
+            Self::Tel => "tel URI",
+            Self::Email => "Email",
+            Self::Sms => "SMS",
+            Self::Otpauth => "otpauth URI",
+            Self::Epc => "EPC QR code",
+            Self::Emv => "EMV QR code",
+            Self::Gs1 => "GS1 element string",
+            Self::Text => "Text",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// An error which can be returned when parsing the decoded data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The data is malformed for the format.
+    Malformed(Format, &'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(format, reason) => write!(f, "invalid {format}: {reason}"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// The value of a field.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    /// A text value.
+    Text(String),
+
+    /// The nested fields.
+    Fields(Vec<Field>),
+}
+
+/// A field extracted from the decoded data.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Field {
+    /// The name of the field.
+    pub name: String,
+
+    /// The value of the field.
+    pub value: Value,
+}
+
+impl Field {
+    fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: Value::Text(value.into()),
+        }
+    }
+}
+
+/// The decoded data and the fields extracted from it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Parsed {
+    /// The format of the decoded data.
+    pub format: Format,
+
+    /// The fields extracted from the decoded data.
+    pub fields: Vec<Field>,
+}
+
+impl fmt::Display for Parsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_fields(f: &mut fmt::Formatter<'_>, fields: &[Field], depth: usize) -> fmt::Result {
+            let indent = "  ".repeat(depth);
+            for field in fields {
+                match field.value {
+                    Value::Text(ref value) => writeln!(f, "{indent}{}: {value}", field.name)?,
+                    Value::Fields(ref fields) => {
+                        writeln!(f, "{indent}{}:", field.name)?;
+                        write_fields(f, fields, depth + 1)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        writeln!(f, "Format: {}", self.format)?;
+        write_fields(f, &self.fields, 0)
+    }
+}
+
+fn from_pairs(pairs: Vec<(&'static str, String)>) -> Vec<Field> {
+    pairs
+        .into_iter()
+        .map(|(name, value)| Field::new(name, value))
+        .collect()
+}
+
+/// Returns [`true`] if the data begins with the prefix, ignoring ASCII case.
+fn has_prefix(data: &str, prefix: &str) -> bool {
+    data.get(..prefix.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+/// Classifies the decoded data by its content.
+pub fn detect(data: &str) -> Format {
+    let has_prefix = |prefix| has_prefix(data, prefix);
+    if emv::is_emv(data) {
+        Format::Emv
+    } else if data.starts_with("BCD\n") || data.starts_with("BCD\r\n") {
+        Format::Epc
+    } else if has_prefix("WIFI:") {
+        Format::Wifi
+    } else if has_prefix("BEGIN:VCARD") {
+        Format::Vcard
+    } else if has_prefix("MECARD:") {
+        Format::Mecard
+    } else if has_prefix("otpauth://") {
+        Format::Otpauth
+    } else if has_prefix("geo:") {
+        Format::Geo
+    } else if has_prefix("tel:") {
+        Format::Tel
+    } else if has_prefix("mailto:") || has_prefix("MATMSG:") {
+        Format::Email
+    } else if has_prefix("sms:") || has_prefix("smsto:") {
+        Format::Sms
+    } else if has_prefix("http://") || has_prefix("https://") {
+        Format::Url
+    } else if data.starts_with('(') && gs1::elements(data).is_ok() {
+        Format::Gs1
+    } else {
+        Format::Text
+    }
+}
+
+/// Splits the query of the URI into the pairs of the name and the decoded
+/// value.
+fn query_pairs(query: &str, format: Format) -> Result<Vec<(String, String)>, Error> {
+    query
+        .split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            let value = otp::percent_decode(value)
+                .map_err(|_| Error::Malformed(format, "malformed percent-encoding"))?;
+            Ok((name.to_ascii_lowercase(), value))
+        })
+        .collect()
+}
+
+fn parse_url(data: &str) -> Vec<Field> {
+    let (_, rest) = data.split_once("://").unwrap_or_default();
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    vec![Field::new("URL", data), Field::new("Host", host)]
+}
+
+fn parse_geo(data: &str) -> Result<Vec<Field>, Error> {
+    let err = |reason| Error::Malformed(Format::Geo, reason);
+    let rest = &data[4..];
+    let (coordinates, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let coordinates = coordinates.split(';').next().unwrap_or_default();
+    let coordinates = coordinates.split(',').collect::<Vec<_>>();
+    if !(2..=3).contains(&coordinates.len()) {
+        return Err(err("expected latitude, longitude and optional altitude"));
+    }
+    let is_within =
+        |value: &str, limit: f64| value.parse::<f64>().is_ok_and(|value| value.abs() <= limit);
+    if !is_within(coordinates[0], 90.0) {
+        return Err(err("latitude must be between -90 and 90"));
+    }
+    if !is_within(coordinates[1], 180.0) {
+        return Err(err("longitude must be between -180 and 180"));
+    }
+    let mut fields = vec![
+        Field::new("Latitude", coordinates[0]),
+        Field::new("Longitude", coordinates[1]),
+    ];
+    if let Some(altitude) = coordinates.get(2) {
+        if !is_within(altitude, f64::MAX) {
+            return Err(err("altitude is not a number"));
+        }
+        fields.push(Field::new("Altitude", *altitude));
+    }
+    for (name, value) in query_pairs(query, Format::Geo)? {
+        if name == "q" {
+            fields.push(Field::new("Query", value));
+        }
+    }
+    Ok(fields)
+}
+
+fn parse_tel(data: &str) -> Result<Vec<Field>, Error> {
+    let number = otp::percent_decode(&data[4..])
+        .map_err(|_| Error::Malformed(Format::Tel, "malformed percent-encoding"))?;
+    if number.is_empty() {
+        return Err(Error::Malformed(Format::Tel, "missing number"));
+    }
+    Ok(vec![Field::new("Number", number)])
+}
+
+fn parse_email(data: &str) -> Result<Vec<Field>, Error> {
+    let mut fields = Vec::new();
+    if has_prefix(data, "MATMSG:") {
+        for (key, value) in payload::split_fields(&data[7..]) {
+            let name = match key.to_ascii_uppercase().as_str() {
+                "TO" => "To",
+                "SUB" => "Subject",
+                "BODY" => "Body",
+                _ => continue,
+            };
+            fields.push(Field::new(name, payload::unescape_field(value)));
+        }
+    } else {
+        let rest = &data[7..];
+        let (to, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let to = otp::percent_decode(to)
+            .map_err(|_| Error::Malformed(Format::Email, "malformed percent-encoding"))?;
+        if !to.is_empty() {
+            fields.push(Field::new("To", to));
+        }
+        for (name, value) in query_pairs(query, Format::Email)? {
+            let name = match name.as_str() {
+                "to" => "To",
+                "cc" => "Cc",
+                "bcc" => "Bcc",
+                "subject" => "Subject",
+                "body" => "Body",
+                _ => continue,
+            };
+            fields.push(Field::new(name, value));
+        }
+    }
+    if fields.is_empty() {
+        return Err(Error::Malformed(Format::Email, "missing recipient"));
+    }
+    Ok(fields)
+}
+
+fn parse_sms(data: &str) -> Result<Vec<Field>, Error> {
+    let (number, message) = if has_prefix(data, "smsto:") {
+        let rest = &data[6..];
+        let (number, message) = rest.split_once(':').unwrap_or((rest, ""));
+        (number.to_owned(), message.to_owned())
+    } else {
+        let rest = &data[4..];
+        let (number, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let number = otp::percent_decode(number)
+            .map_err(|_| Error::Malformed(Format::Sms, "malformed percent-encoding"))?;
+        let message = query_pairs(query, Format::Sms)?
+            .into_iter()
+            .find(|(name, _)| name == "body")
+            .map(|(_, body)| body)
+            .unwrap_or_default();
+        (number, message)
+    };
+    if number.is_empty() {
+        return Err(Error::Malformed(Format::Sms, "missing number"));
+    }
+    let mut fields = vec![Field::new("Number", number)];
+    if !message.is_empty() {
+        fields.push(Field::new("Message", message));
+    }
+    Ok(fields)
+}
+
+fn emv_fields(template: &emv::Template, parent: Option<u8>) -> Vec<Field> {
+    template
+        .iter()
+        .map(|(&id, value)| {
+            let name = emv::name(parent, id)
+                .map_or_else(|| format!("{id:02}"), |name| format!("{id:02} {name}"));
+            let value = match value {
+                emv::Value::Primitive(value) => Value::Text(value.clone()),
+                emv::Value::Template(nested) => Value::Fields(emv_fields(nested, Some(id))),
+            };
+            Field { name, value }
+        })
+        .collect()
+}
+
+fn gs1_fields(data: &str) -> Result<Vec<Field>, gs1::Error> {
+    let elements = gs1::elements(data)?;
+    let fields = elements
+        .into_iter()
+        .map(|(ai, data)| {
+            let name =
+                gs1::title(ai).map_or_else(|| format!("({ai})"), |title| format!("({ai}) {title}"));
+            Field::new(name, data)
+        })
+        .collect();
+    Ok(fields)
+}
+
+/// Classifies the decoded data, and extracts and validates its fields.
+///
+/// If the data is not in any known format, it is returned as plain text.
+pub fn parse(data: &str) -> anyhow::Result<Parsed> {
+    let format = detect(data);
+    let fields = match format {
+        Format::Url => parse_url(data),
+        Format::Wifi => from_pairs(wifi::parse(data)?.fields()),
+        Format::Vcard => from_pairs(contact::parse_vcard(data)?.fields()),
+        Format::Mecard => from_pairs(contact::parse_mecard(data)?.fields()),
+        Format::Geo => parse_geo(data)?,
+        Format::Tel => parse_tel(data)?,
+        Format::Email => parse_email(data)?,
+        Format::Sms => parse_sms(data)?,
+        Format::Otpauth => from_pairs(otp::parse(data)?.fields()),
+        Format::Epc => from_pairs(epc::parse(data)?.fields()),
+        Format::Emv => emv_fields(&emv::parse(data)?, None),
+        Format::Gs1 => gs1_fields(data)?,
+        Format::Text => vec![Field::new("Text", data)],
+    };
+    Ok(Parsed { format, fields })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(parsed: &Parsed) -> String {
+        parsed.to_string()
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(detect("https://example.com/"), Format::Url);
+        assert_eq!(detect("HTTP://EXAMPLE.COM/"), Format::Url);
+        assert_eq!(detect("WIFI:S:example;;"), Format::Wifi);
+        assert_eq!(detect("BEGIN:VCARD\r\n"), Format::Vcard);
+        assert_eq!(detect("MECARD:N:a;;"), Format::Mecard);
+        assert_eq!(detect("geo:0,0"), Format::Geo);
+        assert_eq!(detect("tel:+81312345678"), Format::Tel);
+        assert_eq!(detect("mailto:a@example.com"), Format::Email);
+        assert_eq!(detect("MATMSG:TO:a@example.com;;"), Format::Email);
+        assert_eq!(detect("SMSTO:+81312345678:hello"), Format::Sms);
+        assert_eq!(detect("sms:+81312345678"), Format::Sms);
+        assert_eq!(detect("otpauth://totp/a?secret=A"), Format::Otpauth);
+        assert_eq!(detect("BCD\n002\n1\nSCT"), Format::Epc);
+        assert_eq!(detect("000201010211"), Format::Emv);
+        assert_eq!(detect("(01)09501101530003"), Format::Gs1);
+        assert_eq!(detect("(not GS1)"), Format::Text);
+        assert_eq!(detect("QR code"), Format::Text);
+    }
+
+    #[test]
+    fn parse_wifi() {
+        assert_eq!(
+            text(&parse(r"WIFI:T:WPA;S:my\;network;P:pass\:word;H:true;;").unwrap()),
+            "Format: Wi-Fi\nSSID: my;network\nSecurity: WPA\nPassword: pass:word\nHidden: true\n"
+        );
+    }
+
+    #[test]
+    fn parse_uris() {
+        assert_eq!(
+            text(&parse("https://user@example.com:8080/path?q=1").unwrap()),
+            "Format: URL\nURL: https://user@example.com:8080/path?q=1\nHost: example.com:8080\n"
+        );
+        assert_eq!(
+            text(&parse("geo:35.6812,139.7671,40;u=10?q=Tokyo%20Station").unwrap()),
+            "Format: geo URI\nLatitude: 35.6812\nLongitude: 139.7671\nAltitude: 40\nQuery: Tokyo Station\n"
+        );
+        assert_eq!(
+            text(&parse("tel:+81-3-1234-5678").unwrap()),
+            "Format: tel URI\nNumber: +81-3-1234-5678\n"
+        );
+        assert_eq!(
+            text(&parse("mailto:a@example.com?cc=b@example.com&subject=Hello%2C%20world").unwrap()),
+            "Format: Email\nTo: a@example.com\nCc: b@example.com\nSubject: Hello, world\n"
+        );
+        assert_eq!(
+            text(&parse("MATMSG:TO:a@example.com;SUB:Hello;BODY:a\\;b;;").unwrap()),
+            "Format: Email\nTo: a@example.com\nSubject: Hello\nBody: a;b\n"
+        );
+        assert_eq!(
+            text(&parse("SMSTO:+81312345678:Hello: world").unwrap()),
+            "Format: SMS\nNumber: +81312345678\nMessage: Hello: world\n"
+        );
+        assert_eq!(
+            text(&parse("sms:+81312345678?body=Hello").unwrap()),
+            "Format: SMS\nNumber: +81312345678\nMessage: Hello\n"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_uris() {
+        assert_eq!(
+            parse("geo:91,0").unwrap_err().downcast::<Error>().unwrap(),
+            Error::Malformed(Format::Geo, "latitude must be between -90 and 90")
+        );
+        assert_eq!(
+            parse("geo:0").unwrap_err().to_string(),
+            "invalid geo URI: expected latitude, longitude and optional altitude"
+        );
+        assert_eq!(
+            parse("tel:").unwrap_err().to_string(),
+            "invalid tel URI: missing number"
+        );
+        assert_eq!(
+            parse("mailto:?subject=%zz").unwrap_err().to_string(),
+            "invalid Email: malformed percent-encoding"
+        );
+        assert_eq!(
+            parse("sms:?body=Hello").unwrap_err().to_string(),
+            "invalid SMS: missing number"
+        );
+    }
+
+    #[test]
+    fn parse_emv_tree() {
+        let payload = concat!(
+            "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000",
+            "5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+        );
+        assert_eq!(
+            text(&parse(payload).unwrap()),
+            concat!(
+                "Format: EMV QR code\n",
+                "00 Payload Format Indicator: 01\n",
+                "26 Merchant Account Information:\n",
+                "  00 Globally Unique Identifier: br.gov.bcb.pix\n",
+                "  01: 123e4567-e12b-12d1-a456-426655440000\n",
+                "52 Merchant Category Code: 0000\n",
+                "53 Transaction Currency: 986\n",
+                "58 Country Code: BR\n",
+                "59 Merchant Name: Fulano de Tal\n",
+                "60 Merchant City: BRASILIA\n",
+                "62 Additional Data Field Template:\n",
+                "  05 Reference Label: ***\n",
+                "63 CRC: 1D3D\n"
+            )
+        );
+    }
+
+    #[test]
+    fn parse_gs1() {
+        assert_eq!(
+            text(&parse("(01)09501101530003(17)250101(7001)1234567890123").unwrap()),
+            "Format: GS1 element string\n(01) GTIN: 09501101530003\n(17) USE BY or EXPIRY: 250101\n(7001): 1234567890123\n"
+        );
+    }
+
+    #[test]
+    fn serialize_to_json() {
+        let parsed = parse("WIFI:S:example;;").unwrap();
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            concat!(
+                r#"{"format":"wifi","fields":["#,
+                r#"{"name":"SSID","value":"example"},"#,
+                r#"{"name":"Security","value":"None"},"#,
+                r#"{"name":"Hidden","value":"false"}]}"#
+            )
+        );
+        let parsed = Parsed {
+            format: Format::Emv,
+            fields: vec![Field {
+                name: String::from("62 Additional Data Field Template"),
+                value: Value::Fields(vec![Field::new("05 Reference Label", "***")]),
+            }],
+        };
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            r#"{"format":"emv","fields":[{"name":"62 Additional Data Field Template","value":[{"name":"05 Reference Label","value":"***"}]}]}"#
+        );
+    }
+
+    #[test]
+    fn parse_text() {
+        assert_eq!(
+            text(&parse("QR code").unwrap()),
+            "Format: Text\nText: QR code\n"
+        );
+    }
+}
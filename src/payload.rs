@@ -0,0 +1,374 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+pub mod contact;
+pub mod emv;
+pub mod epc;
+pub mod event;
+pub mod otp;
+pub mod qr_bill;
+pub mod wifi;
+
+use std::{fs, io};
+
+use anyhow::Context;
+use qrcode2::{EcLevel, Version};
+
+use crate::{
+    capacity,
+    cli::{self, ContactFormat, Eci, Mode, Payload},
+};
+
+/// The symbols in which the payload will be encoded.
+#[derive(Clone, Copy, Debug)]
+pub struct Target<'a> {
+    /// The versions which can be chosen.
+    pub versions: &'a [Version],
+
+    /// The error correction level.
+    pub level: EcLevel,
+
+    /// The mode used to encode the payload, or [`None`] for the optimal
+    /// encoding.
+    pub mode: Option<&'a Mode>,
+
+    /// The ECI designator specified in the same way as `--eci`.
+    pub eci: Option<Eci>,
+}
+
+/// Splits the fields in the `KEY:value;` format, which is used by the Wi-Fi
+/// network and the MeCard payloads.
+///
+/// The values are returned as they are, without being unescaped. The splitting
+/// stops at the empty field which terminates the payload.
+#[allow(clippy::doc_markdown)]
+pub fn split_fields(data: &str) -> Vec<(&str, &str)> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut chars = data.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            ';' => {
+                let field = &data[start..i];
+                if field.is_empty() {
+                    return fields;
+                }
+                fields.extend(field.split_once(':'));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.extend(data[start..].split_once(':'));
+    fields
+}
+
+/// Unescapes the value of the field in the `KEY:value;` format.
+pub fn unescape_field(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Returns the capacity of the target symbols which can store the data.
+fn fitting_entries(data: &str, target: Target<'_>) -> Vec<capacity::Entry> {
+    target
+        .versions
+        .iter()
+        .filter_map(|&version| {
+            capacity::Entry::new(
+                version,
+                target.level,
+                Some(capacity::Payload::Data(
+                    data.as_bytes(),
+                    target.mode,
+                    target.eci,
+                )),
+            )
+        })
+        .filter(capacity::Entry::fits)
+        .collect()
+}
+
+/// Returns the area of the smallest target symbol which can store the data.
+///
+/// Returns [`None`] if no symbol can store the data.
+fn smallest_area(data: &str, target: Target<'_>) -> Option<i16> {
+    let entries = fitting_entries(data, target);
+    capacity::smallest(&entries).map(|entry| entry.width * entry.height)
+}
+
+/// Returns the candidate which can be stored in the smallest symbol.
+///
+/// If multiple candidates can be stored in the symbol of the same size, the
+/// first one is returned.
+fn smallest(candidates: Vec<String>, target: Target<'_>) -> Option<String> {
+    candidates
+        .into_iter()
+        .min_by_key(|data| smallest_area(data, target).unwrap_or(i16::MAX))
+}
+
+/// Builds the contact payload in the format which can be stored in the
+/// smallest symbol.
+fn build_contact(arg: &cli::Contact, target: Target<'_>) -> anyhow::Result<String> {
+    let contact = if let Some(ref path) = arg.input {
+        let vcard = if path.as_os_str() == "-" {
+            io::read_to_string(io::stdin()).context("could not read data from standard input")?
+        } else {
+            fs::read_to_string(path)
+                .with_context(|| format!("could not read data from {}", path.display()))?
+        };
+        contact::parse_vcard(&vcard).context("could not parse the vCard")?
+    } else {
+        contact::Contact {
+            name: arg.name.clone().unwrap_or_default(),
+            organization: arg.organization.clone(),
+            phones: arg.phones.clone(),
+            emails: arg.emails.clone(),
+            addresses: arg.addresses.clone(),
+            urls: arg.urls.clone(),
+        }
+    };
+    if contact.name.trim().is_empty() {
+        return Err(contact::Error::MissingName).context("could not build the contact payload");
+    }
+    let candidates = match arg.format {
+        ContactFormat::Auto => vec![
+            contact.to_vcard(contact::VcardVersion::V3),
+            contact.to_mecard(),
+        ],
+        ContactFormat::Vcard3 => vec![contact.to_vcard(contact::VcardVersion::V3)],
+        ContactFormat::Vcard4 => vec![contact.to_vcard(contact::VcardVersion::V4)],
+        ContactFormat::Mecard => vec![contact.to_mecard()],
+    };
+    Ok(smallest(candidates, target).expect("candidates should not be empty"))
+}
+
+/// Builds the calendar event payload.
+///
+/// If the event is read from the iCalendar file, it is stripped down to what
+/// can be stored in any of the target symbols.
+fn build_event(arg: &cli::Event, target: Target<'_>) -> anyhow::Result<String> {
+    if let Some(ref path) = arg.input {
+        let ics = if path.as_os_str() == "-" {
+            io::read_to_string(io::stdin()).context("could not read data from standard input")?
+        } else {
+            fs::read_to_string(path)
+                .with_context(|| format!("could not read data from {}", path.display()))?
+        };
+        let event = event::parse_ics(&ics).context("could not parse the iCalendar data")?;
+        let (payload, is_stripped) = event::build_within(&event, |payload| {
+            !fitting_entries(payload, target).is_empty()
+        })
+        .context("could not build the calendar event payload")?;
+        if is_stripped {
+            eprintln!("Warning: the event was stripped to fit in the symbol");
+        }
+        Ok(payload)
+    } else {
+        let event = event::Event {
+            summary: arg.summary.clone().unwrap_or_default(),
+            start: arg.start.clone().unwrap_or_default(),
+            end: arg.end.clone(),
+            timezone: arg.timezone.clone(),
+            location: arg.location.clone(),
+            description: arg.description.clone(),
+        };
+        event::build(&event).context("could not build the calendar event payload")
+    }
+}
+
+/// Builds the EMV QR code payload from the options or the JSON file.
+fn build_emv(arg: &cli::Emv) -> anyhow::Result<String> {
+    let template = if let Some(ref path) = arg.input {
+        let json = if path.as_os_str() == "-" {
+            io::read_to_string(io::stdin()).context("could not read data from standard input")?
+        } else {
+            fs::read_to_string(path)
+                .with_context(|| format!("could not read data from {}", path.display()))?
+        };
+        let object = serde_json::from_str::<serde_json::Map<_, _>>(&json)
+            .context("could not parse the JSON object")?;
+        emv::from_json(&object).context("could not build the EMV QR code payload")?
+    } else {
+        let point_of_initiation = if arg.dynamic { "12" } else { "11" };
+        let fields = [
+            (emv::POINT_OF_INITIATION_METHOD, Some(point_of_initiation)),
+            (
+                emv::MERCHANT_CATEGORY_CODE,
+                Some(arg.merchant_category_code.as_str()),
+            ),
+            (emv::TRANSACTION_CURRENCY, arg.currency.as_deref()),
+            (emv::TRANSACTION_AMOUNT, arg.amount.as_deref()),
+            (emv::COUNTRY_CODE, arg.country.as_deref()),
+            (emv::MERCHANT_NAME, arg.merchant_name.as_deref()),
+            (emv::MERCHANT_CITY, arg.merchant_city.as_deref()),
+            (emv::POSTAL_CODE, arg.postal_code.as_deref()),
+        ];
+        let mut template = fields
+            .into_iter()
+            .filter_map(|(id, value)| Some((id, emv::Value::Primitive(value?.to_owned()))))
+            .collect::<emv::Template>();
+        if let Some(ref reference) = arg.reference {
+            let path = format!("{:02}.{:02}", emv::ADDITIONAL_DATA, emv::REFERENCE_LABEL);
+            emv::insert(&mut template, &path, reference.clone())
+                .context("could not build the EMV QR code payload")?;
+        }
+        for field in &arg.fields {
+            field
+                .split_once('=')
+                .ok_or_else(|| emv::Error::InvalidField(field.clone()))
+                .and_then(|(path, value)| emv::insert(&mut template, path, value.to_owned()))
+                .context("could not build the EMV QR code payload")?;
+        }
+        template
+    };
+    emv::build(&template).context("could not build the EMV QR code payload")
+}
+
+/// Builds the Swiss QR-bill payload.
+fn build_qr_bill(arg: &cli::QrBill) -> anyhow::Result<String> {
+    let debtor = arg.debtor_name.as_deref().map(|name| qr_bill::Address {
+        name,
+        street: arg.debtor_street.as_deref(),
+        building_number: arg.debtor_building_number.as_deref(),
+        postal_code: arg.debtor_postal_code.as_deref().unwrap_or_default(),
+        town: arg.debtor_town.as_deref().unwrap_or_default(),
+        country: &arg.debtor_country,
+    });
+    let bill = qr_bill::Bill {
+        account: &arg.account,
+        creditor: qr_bill::Address {
+            name: &arg.creditor_name,
+            street: arg.creditor_street.as_deref(),
+            building_number: arg.creditor_building_number.as_deref(),
+            postal_code: &arg.creditor_postal_code,
+            town: &arg.creditor_town,
+            country: &arg.creditor_country,
+        },
+        amount: arg.amount.as_deref(),
+        currency: arg.currency,
+        debtor,
+        reference: arg.reference.as_deref(),
+        message: arg.message.as_deref(),
+        billing_information: arg.billing_information.as_deref(),
+    };
+    qr_bill::build(&bill).context("could not build the Swiss QR-bill payload")
+}
+
+/// Builds the `otpauth` URI.
+///
+/// If the secret is generated, it is output to stderr.
+fn build_otp(arg: &cli::Otp) -> anyhow::Result<String> {
+    let secret = if let Some(ref secret) = arg.secret {
+        otp::decode_base32(secret)
+            .ok_or(otp::Error::InvalidSecret)
+            .context("could not build the otpauth URI")?
+    } else {
+        let mut secret = vec![0; 20];
+        getrandom::fill(&mut secret).context("could not generate a secret")?;
+        eprintln!("Secret: {}", otp::encode_base32(&secret));
+        secret
+    };
+    let key = otp::Key {
+        issuer: arg.issuer.clone(),
+        account: arg.account.clone(),
+        secret,
+        algorithm: arg.algorithm,
+        digits: arg.digits,
+        period: arg.period,
+        counter: arg.counter,
+    };
+    key.to_uri().context("could not build the otpauth URI")
+}
+
+/// Builds the input data from the payload of the application.
+///
+/// If the payload can be built in multiple formats, the one which can be
+/// stored in the smallest target symbol is chosen.
+pub fn build(payload: &Payload, target: Target<'_>) -> anyhow::Result<String> {
+    match payload {
+        Payload::Wifi(arg) => {
+            wifi::build(&arg.ssid, arg.password.as_deref(), arg.security, arg.hidden)
+                .context("could not build the Wi-Fi network payload")
+        }
+        Payload::Contact(arg) => build_contact(arg, target),
+        Payload::Epc(arg) => {
+            let transfer = epc::Transfer {
+                bic: arg.bic.as_deref(),
+                name: &arg.name,
+                iban: &arg.iban,
+                amount: arg.amount.as_deref(),
+                purpose: arg.purpose.as_deref(),
+                reference: arg.reference.as_deref(),
+                remittance: arg.remittance.as_deref(),
+                information: arg.information.as_deref(),
+            };
+            epc::build(&transfer).context("could not build the EPC QR code payload")
+        }
+        Payload::Event(arg) => build_event(arg, target),
+        Payload::Emv(arg) => build_emv(arg),
+        Payload::QrBill(arg) => build_qr_bill(arg),
+        Payload::Otp(arg) => build_otp(arg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::Variant, encode};
+
+    #[test]
+    fn split_and_unescape_fields() {
+        assert_eq!(
+            split_fields(r"T:WPA;S:my\;net;P:a\:b;;ignored:x;"),
+            [("T", "WPA"), ("S", r"my\;net"), ("P", r"a\:b")]
+        );
+        assert_eq!(split_fields("N:Shun Sakai"), [("N", "Shun Sakai")]);
+        assert_eq!(unescape_field(r"my\;net\\\,"), r"my;net\,");
+    }
+
+    #[test]
+    fn choose_smallest_candidate() {
+        let versions = encode::versions(&Variant::Normal);
+        let target = |level| Target {
+            versions: &versions,
+            level,
+            mode: None,
+            eci: None,
+        };
+        let candidates = vec!["a".repeat(20), "b".repeat(10)];
+        assert_eq!(
+            smallest(candidates, target(EcLevel::M)).unwrap(),
+            "b".repeat(10)
+        );
+        let candidates = vec!["a".repeat(10), "b".repeat(5)];
+        assert_eq!(
+            smallest(candidates, target(EcLevel::M)).unwrap(),
+            "a".repeat(10)
+        );
+        let candidates = vec!["a".repeat(3000), "b".repeat(20)];
+        assert_eq!(
+            smallest(candidates, target(EcLevel::H)).unwrap(),
+            "b".repeat(20)
+        );
+        // Both are 17 bytes, but the ECI designator for UTF-8 is inserted into the
+        // first one.
+        let candidates = vec![format!("a{}", "é".repeat(8)), "b".repeat(17)];
+        assert_eq!(
+            smallest(candidates, target(EcLevel::L)).unwrap(),
+            "b".repeat(17)
+        );
+    }
+}
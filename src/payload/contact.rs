@@ -0,0 +1,486 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+use super::{split_fields, unescape_field};
+
+/// An error which can be returned when building or parsing the contact
+/// payload.
+#[allow(clippy::doc_markdown)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The input does not contain a vCard.
+    MissingVcard,
+
+    /// The input is not a MeCard.
+    InvalidMecard,
+
+    /// The contact does not have a name.
+    MissingName,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingVcard => write!(f, "input does not contain a vCard"),
+            Self::InvalidMecard => write!(f, "input is not a MeCard"),
+            Self::MissingName => write!(f, "contact must have a name"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Contact information.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Contact {
+    /// The name of the person.
+    pub name: String,
+
+    /// The organization which the person belongs to.
+    pub organization: Option<String>,
+
+    /// The telephone numbers.
+    pub phones: Vec<String>,
+
+    /// The email addresses.
+    pub emails: Vec<String>,
+
+    /// The postal addresses.
+    pub addresses: Vec<String>,
+
+    /// The URLs of the websites.
+    pub urls: Vec<String>,
+}
+
+/// The version of vCard.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VcardVersion {
+    /// vCard 3.0 (RFC 2426).
+    V3,
+
+    /// vCard 4.0 (RFC 6350).
+    V4,
+}
+
+/// Escapes the text value of a vCard property.
+fn escape_vcard(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes the field of a MeCard.
+///
+/// The reserved characters are the same as those escaped by ZXing.
+#[allow(clippy::doc_markdown)]
+fn escape_mecard(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ':' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Contact {
+    /// Returns the contact as a vCard.
+    ///
+    /// The free-form postal address is stored in the street address component
+    /// of `ADR`.
+    pub fn to_vcard(&self, version: VcardVersion) -> String {
+        let mut lines = vec![String::from("BEGIN:VCARD")];
+        let name = escape_vcard(&self.name);
+        match version {
+            VcardVersion::V3 => {
+                lines.push(String::from("VERSION:3.0"));
+                // `N` is required in vCard 3.0.
+                lines.push(format!("N:{name};;;;"));
+            }
+            VcardVersion::V4 => lines.push(String::from("VERSION:4.0")),
+        }
+        lines.push(format!("FN:{name}"));
+        if let Some(ref organization) = self.organization {
+            lines.push(format!("ORG:{}", escape_vcard(organization)));
+        }
+        lines.extend(
+            self.phones
+                .iter()
+                .map(|phone| format!("TEL:{}", escape_vcard(phone))),
+        );
+        lines.extend(
+            self.emails
+                .iter()
+                .map(|email| format!("EMAIL:{}", escape_vcard(email))),
+        );
+        lines.extend(
+            self.addresses
+                .iter()
+                .map(|address| format!("ADR:;;{};;;;", escape_vcard(address))),
+        );
+        lines.extend(self.urls.iter().map(|url| format!("URL:{url}")));
+        lines.push(String::from("END:VCARD"));
+        lines.push(String::new());
+        lines.join("\r\n")
+    }
+
+    /// Returns the contact as a MeCard.
+    #[allow(clippy::doc_markdown)]
+    pub fn to_mecard(&self) -> String {
+        let mut fields = vec![format!("N:{}", escape_mecard(&self.name))];
+        if let Some(ref organization) = self.organization {
+            fields.push(format!("ORG:{}", escape_mecard(organization)));
+        }
+        fields.extend(
+            self.phones
+                .iter()
+                .map(|phone| format!("TEL:{}", escape_mecard(phone))),
+        );
+        fields.extend(
+            self.emails
+                .iter()
+                .map(|email| format!("EMAIL:{}", escape_mecard(email))),
+        );
+        fields.extend(
+            self.addresses
+                .iter()
+                .map(|address| format!("ADR:{}", escape_mecard(address))),
+        );
+        fields.extend(
+            self.urls
+                .iter()
+                .map(|url| format!("URL:{}", escape_mecard(url))),
+        );
+        format!("MECARD:{};;", fields.join(";"))
+    }
+
+    /// Returns the fields of the contact as pairs of the name and the value.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![("Name", self.name.clone())];
+        if let Some(ref organization) = self.organization {
+            fields.push(("Organization", organization.clone()));
+        }
+        for (name, values) in [
+            ("Phone", &self.phones),
+            ("Email", &self.emails),
+            ("Address", &self.addresses),
+            ("URL", &self.urls),
+        ] {
+            fields.extend(values.iter().map(|value| (name, value.clone())));
+        }
+        fields
+    }
+}
+
+/// Unescapes the text value of a vCard property.
+fn unescape_vcard(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push(c),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Splits the structured value of a vCard property by unescaped `;`, and
+/// unescapes each component.
+fn split_vcard(value: &str) -> Vec<String> {
+    let mut components = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let component = components
+            .last_mut()
+            .expect("components should not be empty");
+        match c {
+            '\\' => {
+                component.push(c);
+                component.extend(chars.next());
+            }
+            ';' => components.push(String::new()),
+            c => component.push(c),
+        }
+    }
+    components
+        .iter()
+        .map(|component| unescape_vcard(component))
+        .collect()
+}
+
+/// Joins the non-empty components with `sep`.
+fn join_non_empty(components: &[String], sep: &str) -> String {
+    components
+        .iter()
+        .map(|component| component.trim())
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+/// Parses the first vCard in the input.
+///
+/// The folded lines are unfolded, and the parameters and the groups of the
+/// properties are ignored. If `FN` is missing, the name is formatted from `N`.
+pub fn parse_vcard(input: &str) -> Result<Contact, Error> {
+    let input = input.replace("\r\n", "\n");
+    let mut lines = Vec::<String>::new();
+    for line in input.split('\n') {
+        match line.strip_prefix([' ', '\t']) {
+            Some(continuation) if !lines.is_empty() => {
+                lines
+                    .last_mut()
+                    .expect("lines should not be empty")
+                    .push_str(continuation);
+            }
+            _ => lines.push(line.to_owned()),
+        }
+    }
+
+    let mut lines = lines
+        .iter()
+        .skip_while(|line| !line.trim_end().eq_ignore_ascii_case("BEGIN:VCARD"));
+    if lines.next().is_none() {
+        return Err(Error::MissingVcard);
+    }
+    let mut contact = Contact::default();
+    let mut structured_name = None;
+    for line in lines {
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            continue;
+        };
+        let name = name.split(';').next().unwrap_or_default();
+        let name = name
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match name.as_str() {
+            "END" if value.eq_ignore_ascii_case("VCARD") => break,
+            "FN" => contact.name = unescape_vcard(value),
+            "N" => {
+                // The components are the family name, the given name, the
+                // additional names, the honorific prefixes and the honorific
+                // suffixes.
+                let components = split_vcard(value);
+                let order = [3, 1, 2, 0, 4];
+                let components = order
+                    .iter()
+                    .filter_map(|&i| components.get(i).cloned())
+                    .collect::<Vec<_>>();
+                structured_name = Some(join_non_empty(&components, " "));
+            }
+            "ORG" => {
+                contact.organization = Some(join_non_empty(&split_vcard(value), ", "));
+            }
+            "TEL" => contact
+                .phones
+                .push(unescape_vcard(value).trim_start_matches("tel:").to_owned()),
+            "EMAIL" => contact.emails.push(unescape_vcard(value)),
+            "ADR" => contact
+                .addresses
+                .push(join_non_empty(&split_vcard(value), ", ")),
+            "URL" => contact.urls.push(value.to_owned()),
+            _ => {}
+        }
+    }
+    if contact.name.trim().is_empty() {
+        contact.name = structured_name.unwrap_or_default();
+    }
+    if contact.name.trim().is_empty() {
+        return Err(Error::MissingName);
+    }
+    Ok(contact)
+}
+
+/// Parses the MeCard.
+///
+/// The unknown fields are ignored.
+#[allow(clippy::doc_markdown)]
+pub fn parse_mecard(input: &str) -> Result<Contact, Error> {
+    let rest = input
+        .get(..7)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("MECARD:"))
+        .map(|_| &input[7..])
+        .ok_or(Error::InvalidMecard)?;
+    let mut contact = Contact::default();
+    for (key, value) in split_fields(rest) {
+        let value = unescape_field(value);
+        match key.to_ascii_uppercase().as_str() {
+            "N" => contact.name = value,
+            "ORG" => contact.organization = Some(value),
+            "TEL" => contact.phones.push(value),
+            "EMAIL" => contact.emails.push(value),
+            "ADR" => contact.addresses.push(value),
+            "URL" => contact.urls.push(value),
+            _ => {}
+        }
+    }
+    if contact.name.trim().is_empty() {
+        return Err(Error::MissingName);
+    }
+    Ok(contact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact() -> Contact {
+        Contact {
+            name: String::from("Shun Sakai"),
+            organization: Some(String::from("Example, Inc.")),
+            phones: vec![String::from("+81-3-1234-5678")],
+            emails: vec![String::from("sorairolake@example.com")],
+            addresses: vec![String::from("1-2-3 Chiyoda, Tokyo")],
+            urls: vec![String::from("https://example.com/")],
+        }
+    }
+
+    #[test]
+    fn to_vcard() {
+        assert_eq!(
+            contact().to_vcard(VcardVersion::V3),
+            concat!(
+                "BEGIN:VCARD\r\n",
+                "VERSION:3.0\r\n",
+                "N:Shun Sakai;;;;\r\n",
+                "FN:Shun Sakai\r\n",
+                "ORG:Example\\, Inc.\r\n",
+                "TEL:+81-3-1234-5678\r\n",
+                "EMAIL:sorairolake@example.com\r\n",
+                "ADR:;;1-2-3 Chiyoda\\, Tokyo;;;;\r\n",
+                "URL:https://example.com/\r\n",
+                "END:VCARD\r\n"
+            )
+        );
+        let contact = Contact {
+            name: String::from("a;b\\c\nd"),
+            ..Default::default()
+        };
+        assert_eq!(
+            contact.to_vcard(VcardVersion::V4),
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:a\\;b\\\\c\\nd\r\nEND:VCARD\r\n"
+        );
+    }
+
+    #[test]
+    fn to_mecard() {
+        assert_eq!(
+            contact().to_mecard(),
+            concat!(
+                "MECARD:N:Shun Sakai;ORG:Example, Inc.;TEL:+81-3-1234-5678;",
+                "EMAIL:sorairolake@example.com;ADR:1-2-3 Chiyoda, Tokyo;",
+                "URL:https\\://example.com/;;"
+            )
+        );
+        let contact = Contact {
+            name: String::from("a;b\\c:d"),
+            ..Default::default()
+        };
+        assert_eq!(contact.to_mecard(), "MECARD:N:a\\;b\\\\c\\:d;;");
+    }
+
+    #[test]
+    fn parse_vcard_round_trip() {
+        assert_eq!(
+            parse_vcard(&contact().to_vcard(VcardVersion::V3)).unwrap(),
+            contact()
+        );
+        assert_eq!(
+            parse_vcard(&contact().to_vcard(VcardVersion::V4)).unwrap(),
+            contact()
+        );
+    }
+
+    #[test]
+    fn parse_vcard_properties() {
+        let input = concat!(
+            "BEGIN:VCARD\n",
+            "VERSION:4.0\n",
+            "N:Sakai;Shun;;Dr.;\n",
+            "item1.TEL;TYPE=work,voice;VALUE=uri:tel:+81-3-1234-5678\n",
+            "EMAIL;TYPE=home:sorairolake@exam\n",
+            " ple.com\n",
+            "ADR;TYPE=work:;;1-2-3 Chiyoda;Chiyoda-ku;Tokyo;100-0001;Japan\n",
+            "PHOTO:data:image/png;base64,AAAA\n",
+            "END:VCARD\n",
+            "BEGIN:VCARD\n",
+            "FN:Other\n",
+            "END:VCARD\n"
+        );
+        assert_eq!(
+            parse_vcard(input).unwrap(),
+            Contact {
+                name: String::from("Dr. Shun Sakai"),
+                phones: vec![String::from("+81-3-1234-5678")],
+                emails: vec![String::from("sorairolake@example.com")],
+                addresses: vec![String::from(
+                    "1-2-3 Chiyoda, Chiyoda-ku, Tokyo, 100-0001, Japan"
+                )],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mecard_round_trip() {
+        assert_eq!(parse_mecard(&contact().to_mecard()).unwrap(), contact());
+        let contact = Contact {
+            name: String::from("a;b\\c:d"),
+            ..Default::default()
+        };
+        assert_eq!(parse_mecard(&contact.to_mecard()).unwrap(), contact);
+        assert_eq!(parse_mecard("BEGIN:VCARD\n"), Err(Error::InvalidMecard));
+        assert_eq!(parse_mecard("MECARD:TEL:123;;"), Err(Error::MissingName));
+    }
+
+    #[test]
+    fn contact_fields() {
+        assert_eq!(
+            contact().fields(),
+            [
+                ("Name", String::from("Shun Sakai")),
+                ("Organization", String::from("Example, Inc.")),
+                ("Phone", String::from("+81-3-1234-5678")),
+                ("Email", String::from("sorairolake@example.com")),
+                ("Address", String::from("1-2-3 Chiyoda, Tokyo")),
+                ("URL", String::from("https://example.com/"))
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_vcard() {
+        assert_eq!(parse_vcard("FN:Shun Sakai\n"), Err(Error::MissingVcard));
+        assert_eq!(
+            parse_vcard("BEGIN:VCARD\nVERSION:4.0\nEND:VCARD\n"),
+            Err(Error::MissingName)
+        );
+    }
+}
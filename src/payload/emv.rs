@@ -0,0 +1,600 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{collections::BTreeMap, error, fmt};
+
+/// The maximum length of the payload in characters.
+const MAX_PAYLOAD_LEN: usize = 512;
+
+/// The maximum length of the value of a field in characters.
+const MAX_VALUE_LEN: usize = 99;
+
+/// The ID of the payload format indicator.
+const PAYLOAD_FORMAT_INDICATOR: u8 = 0;
+
+/// The ID of the point of initiation method.
+pub const POINT_OF_INITIATION_METHOD: u8 = 1;
+
+/// The ID of the merchant category code.
+pub const MERCHANT_CATEGORY_CODE: u8 = 52;
+
+/// The ID of the transaction currency.
+pub const TRANSACTION_CURRENCY: u8 = 53;
+
+/// The ID of the transaction amount.
+pub const TRANSACTION_AMOUNT: u8 = 54;
+
+/// The ID of the country code.
+pub const COUNTRY_CODE: u8 = 58;
+
+/// The ID of the merchant name.
+pub const MERCHANT_NAME: u8 = 59;
+
+/// The ID of the merchant city.
+pub const MERCHANT_CITY: u8 = 60;
+
+/// The ID of the postal code.
+pub const POSTAL_CODE: u8 = 61;
+
+/// The ID of the additional data field template.
+pub const ADDITIONAL_DATA: u8 = 62;
+
+/// The ID of the reference label in the additional data field template.
+pub const REFERENCE_LABEL: u8 = 5;
+
+/// The ID of the CRC.
+const CRC: u8 = 63;
+
+/// An error which can be returned when building or parsing the EMV QR code
+/// payload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The field is not in the form `ID=VALUE`.
+    InvalidField(String),
+
+    /// The ID is not two digits.
+    InvalidId(String),
+
+    /// The ID is set automatically.
+    ReservedId(u8),
+
+    /// The ID is specified more than once.
+    DuplicateId(String),
+
+    /// The required field is missing.
+    MissingField(&'static str),
+
+    /// The value of the field is malformed.
+    InvalidValue {
+        /// The ID of the field.
+        id: u8,
+
+        /// The description of the expected value.
+        expected: &'static str,
+    },
+
+    /// The value of the field is empty or too long.
+    InvalidLength(String),
+
+    /// The field is a template, but it is not allowed.
+    UnexpectedTemplate(String),
+
+    /// The field is not a template, but it is required.
+    ExpectedTemplate(String),
+
+    /// The payload is too long.
+    PayloadTooLong(usize),
+
+    /// The payload is malformed.
+    Malformed(&'static str),
+
+    /// The CRC does not match the payload.
+    CrcMismatch {
+        /// The CRC computed from the payload.
+        expected: u16,
+
+        /// The CRC in the payload.
+        actual: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidField(field) => {
+                write!(f, "invalid field `{field}`: expected ID=VALUE")
+            }
+            Self::InvalidId(id) => write!(f, "invalid ID `{id}`: expected two digits"),
+            Self::ReservedId(id) => write!(f, "field {id:02} is set automatically"),
+            Self::DuplicateId(id) => write!(f, "field {id} is specified more than once"),
+            Self::MissingField(name) => write!(f, "payload must have {name}"),
+            Self::InvalidValue { id, expected } => {
+                write!(f, "invalid value of field {id:02}: expected {expected}")
+            }
+            Self::InvalidLength(id) => write!(
+                f,
+                "value of field {id} must be between 1 and {MAX_VALUE_LEN} characters"
+            ),
+            Self::UnexpectedTemplate(id) => write!(f, "field {id} cannot be a template"),
+            Self::ExpectedTemplate(id) => write!(f, "field {id} must be a template"),
+            Self::PayloadTooLong(len) => write!(
+                f,
+                "payload must be at most {MAX_PAYLOAD_LEN} characters, but it is {len} characters"
+            ),
+            Self::Malformed(reason) => write!(f, "invalid EMV QR code payload: {reason}"),
+            Self::CrcMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "CRC mismatch: expected {expected:04X}, but found {actual}"
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// The value of a field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// A primitive value.
+    Primitive(String),
+
+    /// A template which contains the nested fields.
+    Template(Template),
+}
+
+/// The fields ordered by their IDs.
+pub type Template = BTreeMap<u8, Value>;
+
+/// Returns [`true`] if the field of the top level is a template.
+const fn is_template(id: u8) -> bool {
+    matches!(id, 26..=51 | ADDITIONAL_DATA | 64 | 80..=99)
+}
+
+/// Parses the two-digit ID.
+fn parse_id(id: &str) -> Result<u8, Error> {
+    if id.len() == 2 && id.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(id.parse().expect("ID should be two digits"))
+    } else {
+        Err(Error::InvalidId(id.to_owned()))
+    }
+}
+
+/// Inserts the value into the field at the path.
+///
+/// The path is the IDs of the fields separated by `.` (e.g. `26.01`), and the
+/// templates on the path are created as needed.
+pub fn insert(template: &mut Template, path: &str, value: String) -> Result<(), Error> {
+    let ids = path
+        .split('.')
+        .map(parse_id)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (&last, parents) = ids.split_last().expect("path should not be empty");
+    let mut template = template;
+    for &id in parents {
+        let entry = template
+            .entry(id)
+            .or_insert_with(|| Value::Template(Template::new()));
+        let Value::Template(nested) = entry else {
+            return Err(Error::DuplicateId(path.to_owned()));
+        };
+        template = nested;
+    }
+    if template.insert(last, Value::Primitive(value)).is_some() {
+        return Err(Error::DuplicateId(path.to_owned()));
+    }
+    Ok(())
+}
+
+/// Converts the JSON object to the fields.
+///
+/// The keys of the object are the IDs, and the values are strings or nested
+/// objects.
+pub fn from_json(object: &serde_json::Map<String, serde_json::Value>) -> Result<Template, Error> {
+    object
+        .iter()
+        .map(|(id, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => Value::Primitive(value.clone()),
+                serde_json::Value::Object(object) => Value::Template(from_json(object)?),
+                _ => return Err(Error::Malformed("value must be a string or an object")),
+            };
+            Ok((parse_id(id)?, value))
+        })
+        .collect()
+}
+
+/// Computes the CRC-16/CCITT-FALSE checksum of the data.
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, &byte| {
+        (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+            if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            }
+        })
+    })
+}
+
+/// Validates the value of the field of the top level.
+fn validate_value(id: u8, value: &str) -> Result<(), Error> {
+    let is_digits = |len: usize| value.len() == len && value.bytes().all(|b| b.is_ascii_digit());
+    let (is_valid, expected) = match id {
+        POINT_OF_INITIATION_METHOD => (
+            matches!(value, "11" | "12"),
+            "\"11\" (static) or \"12\" (dynamic)",
+        ),
+        MERCHANT_CATEGORY_CODE => (is_digits(4), "4 digits"),
+        TRANSACTION_CURRENCY => (is_digits(3), "ISO 4217 numeric code"),
+        TRANSACTION_AMOUNT => {
+            let (integer, fraction) = value.split_once('.').unwrap_or((value, "0"));
+            (
+                value.len() <= 13
+                    && !integer.is_empty()
+                    && !fraction.is_empty()
+                    && integer
+                        .bytes()
+                        .chain(fraction.bytes())
+                        .all(|b| b.is_ascii_digit()),
+                "a decimal number up to 13 characters",
+            )
+        }
+        COUNTRY_CODE => (
+            value.len() == 2 && value.bytes().all(|b| b.is_ascii_uppercase()),
+            "ISO 3166-1 alpha-2 code",
+        ),
+        MERCHANT_NAME => (value.chars().count() <= 25, "up to 25 characters"),
+        MERCHANT_CITY => (value.chars().count() <= 15, "up to 15 characters"),
+        _ => (true, ""),
+    };
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue { id, expected })
+    }
+}
+
+/// Encodes the fields in the TLV format.
+///
+/// `prefix` is the path of the template, which is used in the error messages.
+fn encode(template: &Template, prefix: Option<&str>) -> Result<String, Error> {
+    let mut fields = Vec::with_capacity(template.len());
+    for (&id, value) in template {
+        let path = prefix.map_or_else(|| format!("{id:02}"), |p| format!("{p}.{id:02}"));
+        let value = match value {
+            Value::Primitive(value) => {
+                if prefix.is_none() && is_template(id) {
+                    return Err(Error::ExpectedTemplate(path));
+                }
+                value.clone()
+            }
+            Value::Template(_) if prefix.is_some() || !is_template(id) => {
+                return Err(Error::UnexpectedTemplate(path));
+            }
+            Value::Template(nested) => encode(nested, Some(&path))?,
+        };
+        let len = value.chars().count();
+        if !(1..=MAX_VALUE_LEN).contains(&len) {
+            return Err(Error::InvalidLength(path));
+        }
+        fields.push(format!("{id:02}{len:02}{value}"));
+    }
+    Ok(fields.concat())
+}
+
+/// Builds the payload of the EMV QR code for the merchant-presented mode.
+///
+/// The payload format indicator and the CRC are added automatically. The
+/// fields are ordered by their IDs.
+pub fn build(template: &Template) -> Result<String, Error> {
+    for id in [PAYLOAD_FORMAT_INDICATOR, CRC] {
+        if template.contains_key(&id) {
+            return Err(Error::ReservedId(id));
+        }
+    }
+    if template.range(2..=51).next().is_none() {
+        return Err(Error::MissingField(
+            "merchant account information (02 to 51)",
+        ));
+    }
+    for (id, name) in [
+        (MERCHANT_CATEGORY_CODE, "a merchant category code (52)"),
+        (TRANSACTION_CURRENCY, "a transaction currency (53)"),
+        (COUNTRY_CODE, "a country code (58)"),
+        (MERCHANT_NAME, "a merchant name (59)"),
+        (MERCHANT_CITY, "a merchant city (60)"),
+    ] {
+        if !template.contains_key(&id) {
+            return Err(Error::MissingField(name));
+        }
+    }
+    for (&id, value) in template {
+        if let Value::Primitive(value) = value {
+            validate_value(id, value)?;
+        }
+    }
+
+    let mut template = template.clone();
+    template.insert(
+        PAYLOAD_FORMAT_INDICATOR,
+        Value::Primitive(String::from("01")),
+    );
+    let mut payload = encode(&template, None)?;
+    payload.push_str("6304");
+    let len = payload.chars().count() + 4;
+    if len > MAX_PAYLOAD_LEN {
+        return Err(Error::PayloadTooLong(len));
+    }
+    let crc = crc16(payload.as_bytes());
+    Ok(format!("{payload}{crc:04X}"))
+}
+
+/// Splits the string after the first `n` characters.
+///
+/// Returns [`None`] if the string is shorter than `n` characters.
+fn split_chars(s: &str, n: usize) -> Option<(&str, &str)> {
+    match s.char_indices().nth(n) {
+        Some((i, _)) => Some(s.split_at(i)),
+        None => (s.chars().count() == n).then_some((s, "")),
+    }
+}
+
+/// Decodes the fields in the TLV format.
+fn decode(data: &str) -> Result<Template, Error> {
+    let mut template = Template::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (header, remaining) =
+            split_chars(rest, 4).ok_or(Error::Malformed("field is truncated"))?;
+        let (id, len) = header.split_at(2);
+        let id = parse_id(id).map_err(|_| Error::Malformed("ID must be two digits"))?;
+        let len = parse_id(len).map_err(|_| Error::Malformed("length must be two digits"))?;
+        let (value, remaining) =
+            split_chars(remaining, len.into()).ok_or(Error::Malformed("field is truncated"))?;
+        if template
+            .insert(id, Value::Primitive(value.to_owned()))
+            .is_some()
+        {
+            return Err(Error::DuplicateId(format!("{id:02}")));
+        }
+        rest = remaining;
+    }
+    Ok(template)
+}
+
+/// Returns [`true`] if the data looks like the EMV QR code payload.
+pub fn is_emv(data: &str) -> bool {
+    data.starts_with("000201")
+}
+
+/// Parses the payload of the EMV QR code and validates its CRC.
+///
+/// The templates in the top level are parsed as the nested fields. If the
+/// value of a template cannot be parsed, it is left as a primitive value.
+pub fn parse(data: &str) -> Result<Template, Error> {
+    if !is_emv(data) {
+        return Err(Error::Malformed(
+            "payload must begin with the payload format indicator",
+        ));
+    }
+    let (body, crc) = data
+        .len()
+        .checked_sub(4)
+        .filter(|&i| data.is_char_boundary(i) && data[..i].ends_with("6304"))
+        .map(|i| data.split_at(i))
+        .ok_or(Error::Malformed("payload must end with the CRC"))?;
+    let expected = crc16(body.as_bytes());
+    if !crc.eq_ignore_ascii_case(&format!("{expected:04X}")) {
+        return Err(Error::CrcMismatch {
+            expected,
+            actual: crc.to_owned(),
+        });
+    }
+    let mut template = decode(data)?;
+    for (&id, value) in &mut template {
+        if !is_template(id) {
+            continue;
+        }
+        if let Value::Primitive(data) = value {
+            if let Ok(nested) = decode(data) {
+                *value = Value::Template(nested);
+            }
+        }
+    }
+    Ok(template)
+}
+
+/// Returns the name of the field.
+///
+/// `parent` is the ID of the template which contains the field.
+pub const fn name(parent: Option<u8>, id: u8) -> Option<&'static str> {
+    let name = match (parent, id) {
+        (None, 0) => "Payload Format Indicator",
+        (None, 1) => "Point of Initiation Method",
+        (None, 2..=51) => "Merchant Account Information",
+        (None, 52) => "Merchant Category Code",
+        (None, 53) => "Transaction Currency",
+        (None, 54) => "Transaction Amount",
+        (None, 55) => "Tip or Convenience Indicator",
+        (None, 56) => "Value of Convenience Fee Fixed",
+        (None, 57) => "Value of Convenience Fee Percentage",
+        (None, 58) => "Country Code",
+        (None, 59) => "Merchant Name",
+        (None, 60) => "Merchant City",
+        (None, 61) => "Postal Code",
+        (None, 62) => "Additional Data Field Template",
+        (None, 63) => "CRC",
+        (None, 64) => "Merchant Information - Language Template",
+        (None, 80..=99) => "Unreserved Template",
+        (Some(26..=51 | 80..=99), 0) => "Globally Unique Identifier",
+        (Some(62), 1) => "Bill Number",
+        (Some(62), 2) => "Mobile Number",
+        (Some(62), 3) => "Store Label",
+        (Some(62), 4) => "Loyalty Number",
+        (Some(62), 5) => "Reference Label",
+        (Some(62), 6) => "Customer Label",
+        (Some(62), 7) => "Terminal Label",
+        (Some(62), 8) => "Purpose of Transaction",
+        (Some(62), 9) => "Additional Consumer Data Request",
+        (Some(64), 0) => "Language Preference",
+        (Some(64), 1) => "Merchant Name - Alternate Language",
+        (Some(64), 2) => "Merchant City - Alternate Language",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An example of the payload of Pix.
+    const PIX: &str = concat!(
+        "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000",
+        "5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+    );
+
+    fn pix() -> Template {
+        let mut template = Template::new();
+        for (path, value) in [
+            ("26.00", "br.gov.bcb.pix"),
+            ("26.01", "123e4567-e12b-12d1-a456-426655440000"),
+            ("52", "0000"),
+            ("53", "986"),
+            ("58", "BR"),
+            ("59", "Fulano de Tal"),
+            ("60", "BRASILIA"),
+            ("62.05", "***"),
+        ] {
+            insert(&mut template, path, value.to_owned()).unwrap();
+        }
+        template
+    }
+
+    #[test]
+    fn compute_crc() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        assert_eq!(crc16(b""), 0xFFFF);
+    }
+
+    #[test]
+    fn build_payload() {
+        assert_eq!(build(&pix()).unwrap(), PIX);
+        let mut template = pix();
+        insert(&mut template, "54", "10.50".to_owned()).unwrap();
+        let payload = build(&template).unwrap();
+        assert!(payload.contains("540510.50"));
+        assert_eq!(
+            parse(&payload).unwrap()[&TRANSACTION_AMOUNT],
+            Value::Primitive("10.50".to_owned())
+        );
+    }
+
+    #[test]
+    fn build_invalid_payload() {
+        let mut template = pix();
+        assert_eq!(
+            insert(&mut template, "26.01", "a".to_owned()).unwrap_err(),
+            Error::DuplicateId("26.01".to_owned())
+        );
+        assert_eq!(
+            insert(&mut template, "2", "a".to_owned()).unwrap_err(),
+            Error::InvalidId("2".to_owned())
+        );
+        let mut template = pix();
+        template.remove(&26);
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::MissingField("merchant account information (02 to 51)")
+        );
+        let mut template = pix();
+        template.remove(&MERCHANT_CITY);
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::MissingField("a merchant city (60)")
+        );
+        let mut template = pix();
+        insert(&mut template, "63", "0000".to_owned()).unwrap();
+        assert_eq!(build(&template).unwrap_err(), Error::ReservedId(63));
+        let mut template = pix();
+        insert(&mut template, "54", "1,00".to_owned()).unwrap();
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::InvalidValue {
+                id: 54,
+                expected: "a decimal number up to 13 characters"
+            }
+        );
+        let mut template = pix();
+        insert(&mut template, "61", "a".repeat(100)).unwrap();
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::InvalidLength("61".to_owned())
+        );
+        let mut template = pix();
+        insert(&mut template, "27", "a".to_owned()).unwrap();
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::ExpectedTemplate("27".to_owned())
+        );
+        let mut template = pix();
+        insert(&mut template, "59.00", "a".to_owned()).unwrap_err();
+        insert(&mut template, "61.00", "a".to_owned()).unwrap();
+        assert_eq!(
+            build(&template).unwrap_err(),
+            Error::UnexpectedTemplate("61".to_owned())
+        );
+    }
+
+    #[test]
+    fn convert_json() {
+        let json = serde_json::json!({
+            "26": { "00": "br.gov.bcb.pix", "01": "123e4567-e12b-12d1-a456-426655440000" },
+            "52": "0000",
+            "53": "986",
+            "58": "BR",
+            "59": "Fulano de Tal",
+            "60": "BRASILIA",
+            "62": { "05": "***" }
+        });
+        assert_eq!(from_json(json.as_object().unwrap()).unwrap(), pix());
+        let json = serde_json::json!({ "01": 11 });
+        assert_eq!(
+            from_json(json.as_object().unwrap()).unwrap_err(),
+            Error::Malformed("value must be a string or an object")
+        );
+    }
+
+    #[test]
+    fn parse_payload() {
+        let mut expected = pix();
+        expected.insert(0, Value::Primitive("01".to_owned()));
+        expected.insert(CRC, Value::Primitive("1D3D".to_owned()));
+        assert_eq!(parse(PIX).unwrap(), expected);
+        assert!(parse(&PIX.replace("1D3D", "1d3d")).is_ok());
+        assert_eq!(
+            parse(&PIX.replace("BRASILIA", "BRASILIO")).unwrap_err(),
+            Error::CrcMismatch {
+                expected: crc16(&PIX.replace("BRASILIA", "BRASILIO").as_bytes()[..PIX.len() - 4]),
+                actual: "1D3D".to_owned()
+            }
+        );
+        assert_eq!(
+            parse(&PIX[..PIX.len() - 8]).unwrap_err(),
+            Error::Malformed("payload must end with the CRC")
+        );
+        assert_eq!(
+            parse("https://example.com/").unwrap_err(),
+            Error::Malformed("payload must begin with the payload format indicator")
+        );
+        let payload = "0002015999Fulano6304";
+        let payload = format!("{payload}{:04X}", crc16(payload.as_bytes()));
+        assert_eq!(
+            parse(&payload).unwrap_err(),
+            Error::Malformed("field is truncated")
+        );
+    }
+}
@@ -0,0 +1,482 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+/// The maximum version of the symbol allowed by EPC069-12.
+pub const MAX_VERSION: i16 = 13;
+
+/// The maximum length of the payload in bytes.
+const MAX_PAYLOAD_LEN: usize = 331;
+
+/// An error which can be returned when building the EPC QR code payload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The IBAN is malformed or its check digits are wrong.
+    InvalidIban(String),
+
+    /// The BIC is malformed.
+    InvalidBic(String),
+
+    /// The amount is malformed or out of range.
+    InvalidAmount(String),
+
+    /// The purpose code is not four uppercase letters.
+    InvalidPurpose(String),
+
+    /// The creditor reference is malformed or its check digits are wrong.
+    InvalidReference(String),
+
+    /// The field is empty or too long.
+    InvalidLength {
+        /// The name of the field.
+        field: &'static str,
+
+        /// The maximum number of characters.
+        max: usize,
+    },
+
+    /// The payload is too long.
+    PayloadTooLong(usize),
+
+    /// The payload is malformed.
+    InvalidPayload(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidIban(iban) => write!(f, "invalid IBAN `{iban}`"),
+            Self::InvalidBic(bic) => write!(f, "invalid BIC `{bic}`"),
+            Self::InvalidAmount(amount) => write!(
+                f,
+                "invalid amount `{amount}`: expected a number between 0.01 and 999999999.99 with up to 2 decimal places"
+            ),
+            Self::InvalidPurpose(purpose) => write!(
+                f,
+                "invalid purpose code `{purpose}`: expected 4 uppercase letters"
+            ),
+            Self::InvalidReference(reference) => {
+                write!(f, "invalid creditor reference `{reference}`")
+            }
+            Self::InvalidLength { field, max } => {
+                write!(f, "{field} must be between 1 and {max} characters")
+            }
+            Self::PayloadTooLong(len) => write!(
+                f,
+                "payload must be at most {MAX_PAYLOAD_LEN} bytes, but it is {len} bytes"
+            ),
+            Self::InvalidPayload(reason) => write!(f, "invalid EPC QR code payload: {reason}"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A SEPA credit transfer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Transfer<'a> {
+    /// The BIC of the bank of the beneficiary.
+    pub bic: Option<&'a str>,
+
+    /// The name of the beneficiary.
+    pub name: &'a str,
+
+    /// The IBAN of the account of the beneficiary.
+    pub iban: &'a str,
+
+    /// The amount in euros.
+    pub amount: Option<&'a str>,
+
+    /// The purpose code.
+    pub purpose: Option<&'a str>,
+
+    /// The structured creditor reference (ISO 11649).
+    pub reference: Option<&'a str>,
+
+    /// The unstructured remittance information.
+    pub remittance: Option<&'a str>,
+
+    /// The information from the beneficiary to the originator.
+    pub information: Option<&'a str>,
+}
+
+/// Computes the remainder of the number obtained by replacing the letters of
+/// `s` with two digits (A = 10, ..., Z = 35) divided by 97.
+///
+/// Returns [`None`] if `s` contains characters other than ASCII alphanumerics.
+pub fn mod97(s: &str) -> Option<u32> {
+    s.chars().try_fold(0, |remainder, c| {
+        let value = c.to_digit(36)?;
+        let shift = if value < 10 { 10 } else { 100 };
+        Some((remainder * shift + value) % 97)
+    })
+}
+
+/// Removes the spaces from the IBAN and validates its check digits.
+fn normalize_iban(iban: &str) -> Result<String, Error> {
+    let normalized = iban.replace(' ', "").to_ascii_uppercase();
+    let is_valid = (15..=34).contains(&normalized.len())
+        && normalized.is_ascii()
+        && normalized[..2].bytes().all(|b| b.is_ascii_uppercase())
+        && normalized[2..4].bytes().all(|b| b.is_ascii_digit())
+        && mod97(&format!("{}{}", &normalized[4..], &normalized[..4])) == Some(1);
+    if is_valid {
+        Ok(normalized)
+    } else {
+        Err(Error::InvalidIban(iban.to_owned()))
+    }
+}
+
+fn validate_bic(bic: &str) -> Result<(), Error> {
+    let is_valid = matches!(bic.len(), 8 | 11)
+        && bic.is_ascii()
+        && bic[..6].bytes().all(|b| b.is_ascii_uppercase())
+        && bic[6..]
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidBic(bic.to_owned()))
+    }
+}
+
+fn validate_amount(amount: &str) -> Result<(), Error> {
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_valid = (1..=9).contains(&integer.len())
+        && integer.bytes().all(|b| b.is_ascii_digit())
+        && fraction.len() <= 2
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+        && !amount.ends_with('.')
+        && integer.bytes().chain(fraction.bytes()).any(|b| b != b'0');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidAmount(amount.to_owned()))
+    }
+}
+
+fn validate_purpose(purpose: &str) -> Result<(), Error> {
+    if purpose.len() == 4 && purpose.bytes().all(|b| b.is_ascii_uppercase()) {
+        Ok(())
+    } else {
+        Err(Error::InvalidPurpose(purpose.to_owned()))
+    }
+}
+
+fn validate_reference(reference: &str) -> Result<(), Error> {
+    let is_valid = (5..=25).contains(&reference.len())
+        && reference.is_ascii()
+        && reference.starts_with("RF")
+        && reference[2..4].bytes().all(|b| b.is_ascii_digit())
+        && mod97(&format!("{}{}", &reference[4..], &reference[..4])) == Some(1);
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidReference(reference.to_owned()))
+    }
+}
+
+fn validate_length(field: &'static str, value: &str, max: usize) -> Result<(), Error> {
+    if (1..=max).contains(&value.chars().count()) {
+        Ok(())
+    } else {
+        Err(Error::InvalidLength { field, max })
+    }
+}
+
+/// Builds the payload of the EPC QR code (EPC069-12) for the SEPA credit
+/// transfer.
+///
+/// The payload is in version 002, which allows the BIC to be omitted, and is
+/// encoded in UTF-8. The trailing empty fields are omitted.
+pub fn build(transfer: &Transfer<'_>) -> Result<String, Error> {
+    if let Some(bic) = transfer.bic {
+        validate_bic(bic)?;
+    }
+    validate_length("name of the beneficiary", transfer.name, 70)?;
+    let iban = normalize_iban(transfer.iban)?;
+    if let Some(amount) = transfer.amount {
+        validate_amount(amount)?;
+    }
+    if let Some(purpose) = transfer.purpose {
+        validate_purpose(purpose)?;
+    }
+    if let Some(reference) = transfer.reference {
+        validate_reference(reference)?;
+    }
+    if let Some(remittance) = transfer.remittance {
+        validate_length("remittance information", remittance, 140)?;
+    }
+    if let Some(information) = transfer.information {
+        validate_length("beneficiary to originator information", information, 70)?;
+    }
+
+    let amount = transfer.amount.map(|amount| format!("EUR{amount}"));
+    let mut fields = vec![
+        "BCD",
+        "002",
+        "1",
+        "SCT",
+        transfer.bic.unwrap_or_default(),
+        transfer.name,
+        &iban,
+        amount.as_deref().unwrap_or_default(),
+        transfer.purpose.unwrap_or_default(),
+        transfer.reference.unwrap_or_default(),
+        transfer.remittance.unwrap_or_default(),
+        transfer.information.unwrap_or_default(),
+    ];
+    while fields.last().is_some_and(|field| field.is_empty()) {
+        fields.pop();
+    }
+    let payload = fields.join("\n");
+    if payload.len() > MAX_PAYLOAD_LEN {
+        return Err(Error::PayloadTooLong(payload.len()));
+    }
+    Ok(payload)
+}
+
+impl Transfer<'_> {
+    /// Returns the fields of the transfer as pairs of the name and the value.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("Beneficiary", self.name.to_owned()),
+            ("IBAN", self.iban.to_owned()),
+        ];
+        for (name, value) in [
+            ("BIC", self.bic),
+            (
+                "Amount",
+                self.amount.map(|amount| format!("EUR {amount}")).as_deref(),
+            ),
+            ("Purpose", self.purpose),
+            ("Reference", self.reference),
+            ("Remittance", self.remittance),
+            ("Information", self.information),
+        ] {
+            fields.extend(value.map(|value| (name, value.to_owned())));
+        }
+        fields
+    }
+}
+
+/// Parses and validates the payload of the EPC QR code.
+///
+/// Both version 001 and 002 are accepted, and the BIC is required for version
+/// 001.
+pub fn parse(payload: &str) -> Result<Transfer<'_>, Error> {
+    let lines = payload
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect::<Vec<_>>();
+    if lines.first() != Some(&"BCD") {
+        return Err(Error::InvalidPayload("service tag is not BCD"));
+    }
+    let version = lines.get(1).copied().unwrap_or_default();
+    if !matches!(version, "001" | "002") {
+        return Err(Error::InvalidPayload("version must be 001 or 002"));
+    }
+    if !matches!(
+        lines.get(2),
+        Some(&("1" | "2" | "3" | "4" | "5" | "6" | "7" | "8"))
+    ) {
+        return Err(Error::InvalidPayload("unknown character set"));
+    }
+    if lines.get(3) != Some(&"SCT") {
+        return Err(Error::InvalidPayload("identification is not SCT"));
+    }
+    if lines.len() > 12 {
+        return Err(Error::InvalidPayload("too many lines"));
+    }
+    let field = |index: usize| lines.get(index).copied().filter(|line| !line.is_empty());
+    let amount = field(7)
+        .map(|amount| {
+            amount
+                .strip_prefix("EUR")
+                .ok_or_else(|| Error::InvalidAmount(amount.to_owned()))
+        })
+        .transpose()?;
+    let transfer = Transfer {
+        bic: field(4),
+        name: field(5).unwrap_or_default(),
+        iban: field(6).unwrap_or_default(),
+        amount,
+        purpose: field(8),
+        reference: field(9),
+        remittance: field(10),
+        information: field(11),
+    };
+    if version == "001" && transfer.bic.is_none() {
+        return Err(Error::InvalidPayload("BIC is required for version 001"));
+    }
+    if transfer.reference.is_some() && transfer.remittance.is_some() {
+        return Err(Error::InvalidPayload(
+            "reference and remittance cannot be both specified",
+        ));
+    }
+    build(&transfer)?;
+    Ok(transfer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IBAN: &str = "DE89370400440532013000";
+
+    #[test]
+    fn parse_payload() {
+        let payload = "BCD\n002\n1\nSCT\nCOBADEFFXXX\nExample GmbH\nDE89370400440532013000\nEUR12.3\n\n\nInvoice 123";
+        let transfer = parse(payload).unwrap();
+        assert_eq!(
+            transfer,
+            Transfer {
+                bic: Some("COBADEFFXXX"),
+                name: "Example GmbH",
+                iban: IBAN,
+                amount: Some("12.3"),
+                remittance: Some("Invoice 123"),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            transfer.fields(),
+            [
+                ("Beneficiary", String::from("Example GmbH")),
+                ("IBAN", String::from(IBAN)),
+                ("BIC", String::from("COBADEFFXXX")),
+                ("Amount", String::from("EUR 12.3")),
+                ("Remittance", String::from("Invoice 123"))
+            ]
+        );
+        assert_eq!(parse(&payload.replace('\n', "\r\n")).unwrap(), transfer);
+        assert_eq!(
+            parse("BCD\n001\n1\nSCT\n\nExample GmbH\nDE89370400440532013000").unwrap_err(),
+            Error::InvalidPayload("BIC is required for version 001")
+        );
+        assert_eq!(
+            parse("BCD\n002\n1\nSCT\n\nExample GmbH\nDE89370400440532013001").unwrap_err(),
+            Error::InvalidIban(String::from("DE89370400440532013001"))
+        );
+        assert_eq!(
+            parse("BCD\n003\n1\nSCT").unwrap_err(),
+            Error::InvalidPayload("version must be 001 or 002")
+        );
+        assert_eq!(
+            parse("https://example.com/").unwrap_err(),
+            Error::InvalidPayload("service tag is not BCD")
+        );
+    }
+
+    #[test]
+    fn build_payload() {
+        let transfer = Transfer {
+            bic: Some("COBADEFFXXX"),
+            name: "Example GmbH",
+            iban: "DE89 3704 0044 0532 0130 00",
+            amount: Some("12.3"),
+            remittance: Some("Invoice 123"),
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&transfer).unwrap(),
+            "BCD\n002\n1\nSCT\nCOBADEFFXXX\nExample GmbH\nDE89370400440532013000\nEUR12.3\n\n\nInvoice 123"
+        );
+        let transfer = Transfer {
+            name: "Example GmbH",
+            iban: IBAN,
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&transfer).unwrap(),
+            "BCD\n002\n1\nSCT\n\nExample GmbH\nDE89370400440532013000"
+        );
+        let transfer = Transfer {
+            name: "Example GmbH",
+            iban: IBAN,
+            purpose: Some("GDDS"),
+            reference: Some("RF18539007547034"),
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&transfer).unwrap(),
+            "BCD\n002\n1\nSCT\n\nExample GmbH\nDE89370400440532013000\n\nGDDS\nRF18539007547034"
+        );
+    }
+
+    #[test]
+    fn validate_iban() {
+        assert_eq!(
+            normalize_iban("GB82 WEST 1234 5698 7654 32").unwrap(),
+            "GB82WEST12345698765432"
+        );
+        assert_eq!(normalize_iban("de89370400440532013000").unwrap(), IBAN);
+        assert!(normalize_iban("DE89370400440532013001").is_err());
+        assert!(normalize_iban("DE8937040044").is_err());
+        assert!(normalize_iban("DEXX370400440532013000").is_err());
+        assert!(normalize_iban("DE89-370400440532013000").is_err());
+    }
+
+    #[test]
+    fn validate_fields() {
+        assert!(validate_bic("COBADEFF").is_ok());
+        assert!(validate_bic("COBADEFF1").is_err());
+        assert!(validate_bic("C0BADEFFXXX").is_err());
+        assert!(validate_amount("0.01").is_ok());
+        assert!(validate_amount("999999999.99").is_ok());
+        assert!(validate_amount("1000000000").is_err());
+        assert!(validate_amount("0.00").is_err());
+        assert!(validate_amount("1.").is_err());
+        assert!(validate_amount(".5").is_err());
+        assert!(validate_amount("1.234").is_err());
+        assert!(validate_amount("1,23").is_err());
+        assert!(validate_purpose("GDDS").is_ok());
+        assert!(validate_purpose("gdds").is_err());
+        assert!(validate_reference("RF18539007547034").is_ok());
+        assert!(validate_reference("RF19539007547034").is_err());
+        assert!(validate_reference("XX18539007547034").is_err());
+    }
+
+    #[test]
+    fn build_invalid_payload() {
+        let transfer = Transfer {
+            name: &"a".repeat(71),
+            iban: IBAN,
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&transfer).unwrap_err(),
+            Error::InvalidLength {
+                field: "name of the beneficiary",
+                max: 70
+            }
+        );
+        let transfer = Transfer {
+            name: "Example GmbH",
+            iban: IBAN,
+            remittance: Some(""),
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&transfer).unwrap_err(),
+            Error::InvalidLength {
+                field: "remittance information",
+                max: 140
+            }
+        );
+        let remittance = "ä".repeat(140);
+        let transfer = Transfer {
+            name: &"ä".repeat(70),
+            iban: IBAN,
+            remittance: Some(&remittance),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build(&transfer).unwrap_err(),
+            Error::PayloadTooLong(_)
+        ));
+    }
+}
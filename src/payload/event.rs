@@ -0,0 +1,559 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+/// The maximum length of a content line in octets, excluding the line break.
+const MAX_LINE_LEN: usize = 75;
+
+/// An error which can be returned when building the calendar event payload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The input does not contain a `VEVENT` component.
+    MissingEvent,
+
+    /// The required property is missing.
+    MissingProperty(&'static str),
+
+    /// The date or the date-time is malformed.
+    InvalidDateTime(String),
+
+    /// The time zone is specified for a date or a UTC date-time.
+    UnexpectedTimezone,
+
+    /// The start and the end are of different types.
+    MismatchedEnd,
+
+    /// The end is before the start.
+    EndBeforeStart,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEvent => write!(f, "input does not contain a VEVENT component"),
+            Self::MissingProperty(name) => write!(f, "event must have {name}"),
+            Self::InvalidDateTime(value) => write!(
+                f,
+                "invalid date or date-time `{value}`: expected YYYY-MM-DD or YYYY-MM-DDTHH:MM[:SS][Z]"
+            ),
+            Self::UnexpectedTimezone => write!(
+                f,
+                "time zone cannot be specified for a date or a UTC date-time"
+            ),
+            Self::MismatchedEnd => {
+                write!(f, "start and end must be both dates or both date-times")
+            }
+            Self::EndBeforeStart => write!(f, "end must not be before start"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A calendar event.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Event {
+    /// The summary of the event.
+    pub summary: String,
+
+    /// The start of the event.
+    pub start: String,
+
+    /// The end of the event.
+    pub end: Option<String>,
+
+    /// The time zone identifier of the start and the end.
+    pub timezone: Option<String>,
+
+    /// The location of the event.
+    pub location: Option<String>,
+
+    /// The description of the event.
+    pub description: Option<String>,
+}
+
+/// A date or a date-time in the basic format of iCalendar.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum DateTime {
+    /// A date (e.g. `20261017`).
+    Date(String),
+
+    /// A local date-time (e.g. `20261017T090000`).
+    Local(String),
+
+    /// A UTC date-time (e.g. `20261017T090000Z`).
+    Utc(String),
+}
+
+impl DateTime {
+    fn value(&self) -> &str {
+        match self {
+            Self::Date(value) | Self::Local(value) | Self::Utc(value) => value,
+        }
+    }
+
+    const fn is_same_type(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Date(_), Self::Date(_))
+                | (Self::Local(_), Self::Local(_))
+                | (Self::Utc(_), Self::Utc(_))
+        )
+    }
+}
+
+const fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses the date or the date-time in the extended format of ISO 8601 or the
+/// basic format of iCalendar.
+fn parse_date_time(value: &str) -> Result<DateTime, Error> {
+    let err = || Error::InvalidDateTime(value.to_owned());
+    let basic = value
+        .trim()
+        .replace(' ', "T")
+        .replace(['-', ':'], "")
+        .to_ascii_uppercase();
+    let (basic, is_utc) = basic
+        .strip_suffix('Z')
+        .map_or((basic.as_str(), false), |basic| (basic, true));
+    if !basic.is_ascii() {
+        return Err(err());
+    }
+    let (date, time) = basic
+        .split_once('T')
+        .map_or((basic, None), |(d, t)| (d, Some(t)));
+    let number = |s: &str| -> Result<u32, Error> {
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().map_err(|_| err())
+        } else {
+            Err(err())
+        }
+    };
+    if date.len() != 8 {
+        return Err(err());
+    }
+    let (year, month, day) = (
+        number(&date[..4])?,
+        number(&date[4..6])?,
+        number(&date[6..])?,
+    );
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return Err(err());
+    }
+    let Some(time) = time else {
+        return if is_utc {
+            Err(err())
+        } else {
+            Ok(DateTime::Date(date.to_owned()))
+        };
+    };
+    let time = match time.len() {
+        4 => format!("{time}00"),
+        6 => time.to_owned(),
+        _ => return Err(err()),
+    };
+    let (hour, minute, second) = (
+        number(&time[..2])?,
+        number(&time[2..4])?,
+        number(&time[4..])?,
+    );
+    if hour > 23 || minute > 59 || second > 60 {
+        return Err(err());
+    }
+    let value = format!("{date}T{time}");
+    if is_utc {
+        Ok(DateTime::Utc(value + "Z"))
+    } else {
+        Ok(DateTime::Local(value))
+    }
+}
+
+/// Escapes the text value of an iCalendar property.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Unescapes the text value of an iCalendar property.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push(c),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Folds the content line so that each line is at most 75 octets.
+///
+/// The line is not split in the middle of a UTF-8 character, and the
+/// continuation lines begin with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded
+}
+
+/// Builds the payload of the calendar event.
+///
+/// The payload is a `VEVENT` component of iCalendar (RFC 5545) without the
+/// enclosing `VCALENDAR` component. The lines are separated by CRLF, and are
+/// folded at 75 octets. If a time zone is specified, it is referenced by the
+/// `TZID` parameter without the `VTIMEZONE` component.
+pub fn build(event: &Event) -> Result<String, Error> {
+    if event.summary.is_empty() {
+        return Err(Error::MissingProperty("a summary"));
+    }
+    let start = parse_date_time(&event.start)?;
+    let end = event.end.as_deref().map(parse_date_time).transpose()?;
+    if event.timezone.is_some() && !matches!(start, DateTime::Local(_)) {
+        return Err(Error::UnexpectedTimezone);
+    }
+    if let Some(ref end) = end {
+        if !start.is_same_type(end) {
+            return Err(Error::MismatchedEnd);
+        }
+        if end.value() < start.value() {
+            return Err(Error::EndBeforeStart);
+        }
+    }
+
+    let parameter = |date_time: &DateTime| match (date_time, &event.timezone) {
+        (DateTime::Date(_), _) => String::from(";VALUE=DATE"),
+        (_, Some(timezone)) => format!(";TZID={timezone}"),
+        _ => String::new(),
+    };
+    let mut lines = vec![
+        String::from("BEGIN:VEVENT"),
+        format!("SUMMARY:{}", escape(&event.summary)),
+        format!("DTSTART{}:{}", parameter(&start), start.value()),
+    ];
+    if let Some(ref end) = end {
+        lines.push(format!("DTEND{}:{}", parameter(end), end.value()));
+    }
+    if let Some(ref location) = event.location {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    if let Some(ref description) = event.description {
+        lines.push(format!("DESCRIPTION:{}", escape(description)));
+    }
+    lines.push(String::from("END:VEVENT"));
+    lines.push(String::new());
+    Ok(lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n"))
+}
+
+/// Builds the payload of the calendar event which satisfies `fits`.
+///
+/// If the payload does not satisfy `fits`, the description and then the
+/// location are truncated, or are removed if they do not satisfy it even with
+/// one character. If the payload does not satisfy it even without them, the
+/// payload without them is returned.
+///
+/// Returns the payload and whether the event was stripped.
+pub fn build_within(event: &Event, fits: impl Fn(&str) -> bool) -> Result<(String, bool), Error> {
+    let payload = build(event)?;
+    if fits(&payload) {
+        return Ok((payload, false));
+    }
+    let mut event = event.clone();
+    let fields: [fn(&mut Event) -> &mut Option<String>; 2] =
+        [|event| &mut event.description, |event| &mut event.location];
+    for field in fields {
+        let Some(value) = field(&mut event).take() else {
+            continue;
+        };
+        let chars = value.chars().collect::<Vec<_>>();
+        // The longest prefix which fits is found by the binary search.
+        let (mut low, mut high) = (0, chars.len());
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            *field(&mut event) = Some(chars[..mid].iter().collect());
+            if fits(&build(&event)?) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        *field(&mut event) = (low > 0).then(|| chars[..low].iter().collect());
+        let payload = build(&event)?;
+        if fits(&payload) {
+            return Ok((payload, true));
+        }
+    }
+    Ok((build(&event)?, true))
+}
+
+/// Parses the first `VEVENT` component in the iCalendar data.
+///
+/// The folded lines are unfolded, and only the properties which can be
+/// specified for [`Event`] are read. The time zone is taken from the `TZID`
+/// parameter of `DTSTART`.
+pub fn parse_ics(input: &str) -> Result<Event, Error> {
+    let input = input.replace("\r\n", "\n");
+    let mut lines = Vec::<String>::new();
+    for line in input.split('\n') {
+        match line.strip_prefix([' ', '\t']) {
+            Some(continuation) if !lines.is_empty() => {
+                lines
+                    .last_mut()
+                    .expect("lines should not be empty")
+                    .push_str(continuation);
+            }
+            _ => lines.push(line.to_owned()),
+        }
+    }
+
+    let mut lines = lines
+        .iter()
+        .skip_while(|line| !line.trim_end().eq_ignore_ascii_case("BEGIN:VEVENT"));
+    if lines.next().is_none() {
+        return Err(Error::MissingEvent);
+    }
+    let mut event = Event::default();
+    let mut depth = 0;
+    for line in lines {
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            continue;
+        };
+        let mut parameters = name.split(';');
+        let name = parameters.next().unwrap_or_default().to_ascii_uppercase();
+        // The nested components such as `VALARM` are skipped.
+        match name.as_str() {
+            "BEGIN" => depth += 1,
+            "END" if depth == 0 => break,
+            "END" => depth -= 1,
+            _ if depth > 0 => {}
+            "SUMMARY" => event.summary = unescape(value),
+            "DTSTART" => {
+                value.clone_into(&mut event.start);
+                event.timezone = parameters
+                    .filter_map(|parameter| parameter.split_once('='))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("TZID"))
+                    .map(|(_, tzid)| tzid.trim_matches('"').to_owned());
+            }
+            "DTEND" => event.end = Some(value.to_owned()),
+            "LOCATION" => event.location = Some(unescape(value)),
+            "DESCRIPTION" => event.description = Some(unescape(value)),
+            _ => {}
+        }
+    }
+    if event.summary.is_empty() {
+        return Err(Error::MissingProperty("a summary"));
+    }
+    if event.start.is_empty() {
+        return Err(Error::MissingProperty("a start"));
+    }
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> Event {
+        Event {
+            summary: "Meeting; Q4, planning".to_owned(),
+            start: "2026-10-17T09:00".to_owned(),
+            end: Some("2026-10-17 10:30:00".to_owned()),
+            timezone: Some("Europe/Zurich".to_owned()),
+            location: Some("Room 1".to_owned()),
+            description: Some("Agenda:\n1. Budget".to_owned()),
+        }
+    }
+
+    #[test]
+    fn build_payload() {
+        assert_eq!(
+            build(&event()).unwrap(),
+            concat!(
+                "BEGIN:VEVENT\r\n",
+                "SUMMARY:Meeting\\; Q4\\, planning\r\n",
+                "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+                "DTEND;TZID=Europe/Zurich:20261017T103000\r\n",
+                "LOCATION:Room 1\r\n",
+                "DESCRIPTION:Agenda:\\n1. Budget\r\n",
+                "END:VEVENT\r\n"
+            )
+        );
+        let event = Event {
+            summary: "Holiday".to_owned(),
+            start: "20261224".to_owned(),
+            end: Some("2026-12-26".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&event).unwrap(),
+            "BEGIN:VEVENT\r\nSUMMARY:Holiday\r\nDTSTART;VALUE=DATE:20261224\r\nDTEND;VALUE=DATE:20261226\r\nEND:VEVENT\r\n"
+        );
+        let event = Event {
+            summary: "Call".to_owned(),
+            start: "2026-10-17T07:00Z".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(
+            build(&event).unwrap(),
+            "BEGIN:VEVENT\r\nSUMMARY:Call\r\nDTSTART:20261017T070000Z\r\nEND:VEVENT\r\n"
+        );
+    }
+
+    #[test]
+    fn fold_line() {
+        let line = format!("DESCRIPTION:{}", "a".repeat(100));
+        let folded = fold(&line);
+        let lines = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "a".repeat(37)));
+        let folded = fold(&format!("SUMMARY:{}", "あ".repeat(30)));
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(
+            folded.replace("\r\n ", ""),
+            format!("SUMMARY:{}", "あ".repeat(30))
+        );
+    }
+
+    #[test]
+    fn validate_date_time() {
+        assert_eq!(
+            parse_date_time("2024-02-29").unwrap(),
+            DateTime::Date("20240229".to_owned())
+        );
+        assert_eq!(
+            parse_date_time("20261017T0900").unwrap(),
+            DateTime::Local("20261017T090000".to_owned())
+        );
+        assert_eq!(
+            parse_date_time("2026-10-17t09:00:30z").unwrap(),
+            DateTime::Utc("20261017T090030Z".to_owned())
+        );
+        assert!(parse_date_time("2026-02-29").is_err());
+        assert!(parse_date_time("2026-13-01").is_err());
+        assert!(parse_date_time("2026-10-17T24:00").is_err());
+        assert!(parse_date_time("2026-10-17Z").is_err());
+        assert!(parse_date_time("17/10/2026").is_err());
+    }
+
+    #[test]
+    fn build_invalid_payload() {
+        let event = Event {
+            end: Some("2026-10-17T08:00".to_owned()),
+            ..self::event()
+        };
+        assert_eq!(build(&event).unwrap_err(), Error::EndBeforeStart);
+        let event = Event {
+            end: Some("2026-10-18".to_owned()),
+            ..self::event()
+        };
+        assert_eq!(build(&event).unwrap_err(), Error::MismatchedEnd);
+        let event = Event {
+            start: "2026-10-17".to_owned(),
+            end: None,
+            ..self::event()
+        };
+        assert_eq!(build(&event).unwrap_err(), Error::UnexpectedTimezone);
+    }
+
+    #[test]
+    fn strip_payload() {
+        let event = Event {
+            description: Some("a".repeat(100)),
+            ..self::event()
+        };
+        let (payload, is_stripped) = build_within(&event, |_| true).unwrap();
+        assert_eq!(payload, build(&event).unwrap());
+        assert!(!is_stripped);
+        let (payload, is_stripped) = build_within(&event, |payload| payload.len() <= 200).unwrap();
+        assert!(is_stripped);
+        assert_eq!(payload.len(), 200);
+        assert!(payload.contains("LOCATION:Room 1\r\n"));
+        let (payload, is_stripped) = build_within(&event, |payload| payload.len() <= 160).unwrap();
+        assert!(is_stripped);
+        assert!(!payload.contains("DESCRIPTION"));
+        assert!(payload.contains("LOCATION:Room\r\n"));
+        let (payload, _) = build_within(&event, |_| false).unwrap();
+        assert!(!payload.contains("DESCRIPTION") && !payload.contains("LOCATION"));
+    }
+
+    #[test]
+    fn parse_ics_file() {
+        let ics = concat!(
+            "BEGIN:VCALENDAR\r\n",
+            "VERSION:2.0\r\n",
+            "BEGIN:VEVENT\r\n",
+            "UID:1@example.com\r\n",
+            "DTSTAMP:20261001T000000Z\r\n",
+            "DTSTART;TZID=\"Europe/Zurich\":20261017T090000\r\n",
+            "DTEND;TZID=Europe/Zurich:20261017T103000\r\n",
+            "SUMMARY:Meeting\\; Q4\\, planning\r\n",
+            "LOCATION:Room 1\r\n",
+            "DESCRIPTION:Agenda:\\n1.\r\n",
+            "  Budget\r\n",
+            "BEGIN:VALARM\r\n",
+            "DESCRIPTION:Reminder\r\n",
+            "END:VALARM\r\n",
+            "END:VEVENT\r\n",
+            "BEGIN:VEVENT\r\n",
+            "SUMMARY:Other\r\n",
+            "END:VEVENT\r\n",
+            "END:VCALENDAR\r\n"
+        );
+        let event = parse_ics(ics).unwrap();
+        assert_eq!(
+            event,
+            Event {
+                start: "20261017T090000".to_owned(),
+                end: Some("20261017T103000".to_owned()),
+                ..self::event()
+            }
+        );
+        assert_eq!(build(&event).unwrap(), build(&self::event()).unwrap());
+        assert_eq!(
+            parse_ics("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").unwrap_err(),
+            Error::MissingEvent
+        );
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\r\nSUMMARY:a\r\nEND:VEVENT\r\n").unwrap_err(),
+            Error::MissingProperty("a start")
+        );
+    }
+}
@@ -0,0 +1,487 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+use crate::cli::OtpAlgorithm;
+
+/// The alphabet of Base32 (RFC 4648).
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The digits of hexadecimal for percent-encoding.
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// The default number of digits.
+const DEFAULT_DIGITS: u8 = 6;
+
+/// The default period of TOTP in seconds.
+const DEFAULT_PERIOD: u64 = 30;
+
+/// An error which can be returned when building or parsing the `otpauth` URI.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The secret is empty or is not a valid Base32 string.
+    InvalidSecret,
+
+    /// The account name is empty.
+    MissingAccount,
+
+    /// The issuer or the account name contains a colon.
+    ColonInLabel(&'static str),
+
+    /// The number of digits is out of range.
+    InvalidDigits(u8),
+
+    /// The period is zero.
+    InvalidPeriod,
+
+    /// The issuer in the label does not match the issuer parameter.
+    IssuerMismatch,
+
+    /// The URI is malformed.
+    InvalidUri(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSecret => write!(f, "secret must be a non-empty Base32 string"),
+            Self::MissingAccount => write!(f, "account name must not be empty"),
+            Self::ColonInLabel(field) => write!(f, "{field} must not contain a colon"),
+            Self::InvalidDigits(digits) => write!(
+                f,
+                "number of digits must be between 6 and 8, but it is {digits}"
+            ),
+            Self::InvalidPeriod => write!(f, "period must be at least 1 second"),
+            Self::IssuerMismatch => {
+                write!(f, "issuer in the label does not match the issuer parameter")
+            }
+            Self::InvalidUri(reason) => write!(f, "invalid otpauth URI: {reason}"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A key for generating one-time passwords.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Key {
+    /// The provider or service which the account belongs to.
+    pub issuer: Option<String>,
+
+    /// The name of the account.
+    pub account: String,
+
+    /// The shared secret.
+    pub secret: Vec<u8>,
+
+    /// The hash algorithm.
+    pub algorithm: OtpAlgorithm,
+
+    /// The number of digits of the password.
+    pub digits: u8,
+
+    /// The period of TOTP in seconds.
+    pub period: u64,
+
+    /// The initial counter of HOTP.
+    ///
+    /// If this is [`None`], the key is for TOTP.
+    pub counter: Option<u64>,
+}
+
+/// Encodes the bytes in Base32 (RFC 4648) without padding.
+pub fn encode_base32(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for chunk in bytes.chunks(5) {
+        let mut buf = [0; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = buf
+            .iter()
+            .fold(0_u64, |bits, &b| (bits << 8) | u64::from(b));
+        let len = (chunk.len() * 8).div_ceil(5);
+        for i in 0..len {
+            let index = (bits >> (35 - i * 5)) & 0x1f;
+            encoded.push(char::from(BASE32_ALPHABET[index as usize]));
+        }
+    }
+    encoded
+}
+
+/// Decodes the Base32 (RFC 4648) string.
+///
+/// The string is case-insensitive, and the spaces and the padding are ignored.
+///
+/// Returns [`None`] if the string contains characters other than the alphabet.
+pub fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(s.len() * 5 / 8);
+    let (mut bits, mut len) = (0_u32, 0);
+    for c in s.bytes().filter(|&c| !matches!(c, b' ' | b'=')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())?;
+        bits = (bits << 5) | u32::try_from(value).expect("index should be less than 32");
+        len += 5;
+        if len >= 8 {
+            len -= 8;
+            decoded.push(u8::try_from((bits >> len) & 0xff).expect("value should be a byte"));
+        }
+    }
+    Some(decoded)
+}
+
+/// Percent-encodes the characters other than the unreserved characters of RFC
+/// 3986.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(b));
+        } else {
+            encoded.push('%');
+            encoded.push(char::from(HEX_DIGITS[usize::from(b >> 4)]));
+            encoded.push(char::from(HEX_DIGITS[usize::from(b & 0x0f)]));
+        }
+    }
+    encoded
+}
+
+/// Decodes the percent-encoded string.
+pub fn percent_decode(s: &str) -> Result<String, Error> {
+    let mut decoded = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next(), bytes.next()]
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .and_then(|hex| String::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(&hex, 16).ok())
+                .ok_or(Error::InvalidUri("malformed percent-encoding"))?;
+            decoded.push(hex);
+        } else {
+            decoded.push(b);
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| Error::InvalidUri("label or parameter is not UTF-8"))
+}
+
+const fn algorithm_name(algorithm: OtpAlgorithm) -> &'static str {
+    match algorithm {
+        OtpAlgorithm::Sha1 => "SHA1",
+        OtpAlgorithm::Sha256 => "SHA256",
+        OtpAlgorithm::Sha512 => "SHA512",
+    }
+}
+
+impl Key {
+    fn validate(&self) -> Result<(), Error> {
+        if self.secret.is_empty() {
+            return Err(Error::InvalidSecret);
+        }
+        if self.account.is_empty() {
+            return Err(Error::MissingAccount);
+        }
+        if self
+            .issuer
+            .as_ref()
+            .is_some_and(|issuer| issuer.contains(':'))
+        {
+            return Err(Error::ColonInLabel("issuer"));
+        }
+        if self.account.contains(':') {
+            return Err(Error::ColonInLabel("account name"));
+        }
+        if !(6..=8).contains(&self.digits) {
+            return Err(Error::InvalidDigits(self.digits));
+        }
+        if self.period == 0 {
+            return Err(Error::InvalidPeriod);
+        }
+        Ok(())
+    }
+
+    /// Builds the `otpauth` URI of the key.
+    ///
+    /// The issuer is put in both the label and the parameter. The parameters
+    /// with the default values are omitted.
+    pub fn to_uri(&self) -> Result<String, Error> {
+        self.validate()?;
+
+        let mut uri = String::from("otpauth://");
+        uri.push_str(if self.counter.is_some() {
+            "hotp/"
+        } else {
+            "totp/"
+        });
+        if let Some(ref issuer) = self.issuer {
+            uri.push_str(&percent_encode(issuer));
+            uri.push(':');
+        }
+        uri.push_str(&percent_encode(&self.account));
+        uri.push_str("?secret=");
+        uri.push_str(&encode_base32(&self.secret));
+        if let Some(ref issuer) = self.issuer {
+            uri.push_str("&issuer=");
+            uri.push_str(&percent_encode(issuer));
+        }
+        if self.algorithm != OtpAlgorithm::Sha1 {
+            uri.push_str("&algorithm=");
+            uri.push_str(algorithm_name(self.algorithm));
+        }
+        if self.digits != DEFAULT_DIGITS {
+            uri.push_str("&digits=");
+            uri.push_str(&self.digits.to_string());
+        }
+        if let Some(counter) = self.counter {
+            uri.push_str("&counter=");
+            uri.push_str(&counter.to_string());
+        } else if self.period != DEFAULT_PERIOD {
+            uri.push_str("&period=");
+            uri.push_str(&self.period.to_string());
+        }
+        Ok(uri)
+    }
+
+    /// Returns the fields of the key as pairs of the name and the value.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![(
+            "Type",
+            String::from(if self.counter.is_some() {
+                "HOTP"
+            } else {
+                "TOTP"
+            }),
+        )];
+        if let Some(ref issuer) = self.issuer {
+            fields.push(("Issuer", issuer.clone()));
+        }
+        fields.extend([
+            ("Account", self.account.clone()),
+            ("Secret", encode_base32(&self.secret)),
+            ("Algorithm", algorithm_name(self.algorithm).to_owned()),
+            ("Digits", self.digits.to_string()),
+        ]);
+        if let Some(counter) = self.counter {
+            fields.push(("Counter", counter.to_string()));
+        } else {
+            fields.push(("Period", self.period.to_string()));
+        }
+        fields
+    }
+}
+
+/// Parses and validates the `otpauth` URI.
+///
+/// The unknown parameters are ignored.
+pub fn parse(uri: &str) -> Result<Key, Error> {
+    let rest = uri
+        .get(..10)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("otpauth://"))
+        .map(|_| &uri[10..])
+        .ok_or(Error::InvalidUri("scheme is not otpauth"))?;
+    let (kind, rest) = rest
+        .split_once('/')
+        .ok_or(Error::InvalidUri("missing type"))?;
+    let is_hotp = match kind.to_ascii_lowercase().as_str() {
+        "totp" => false,
+        "hotp" => true,
+        _ => return Err(Error::InvalidUri("type must be totp or hotp")),
+    };
+    let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let label = percent_decode(label)?;
+    let (label_issuer, account) = match label.split_once(':') {
+        Some((issuer, account)) => (Some(issuer.to_owned()), account.trim_start().to_owned()),
+        None => (None, label),
+    };
+
+    let (mut secret, mut issuer, mut algorithm, mut digits, mut period, mut counter) = (
+        None,
+        None,
+        OtpAlgorithm::Sha1,
+        DEFAULT_DIGITS,
+        DEFAULT_PERIOD,
+        None,
+    );
+    for parameter in query.split('&').filter(|p| !p.is_empty()) {
+        let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+        let value = percent_decode(value)?;
+        match name {
+            "secret" => secret = Some(decode_base32(&value).ok_or(Error::InvalidSecret)?),
+            "issuer" => issuer = Some(value),
+            "algorithm" => {
+                algorithm = match value.to_ascii_uppercase().as_str() {
+                    "SHA1" => OtpAlgorithm::Sha1,
+                    "SHA256" => OtpAlgorithm::Sha256,
+                    "SHA512" => OtpAlgorithm::Sha512,
+                    _ => return Err(Error::InvalidUri("unknown algorithm")),
+                };
+            }
+            "digits" => {
+                digits = value
+                    .parse()
+                    .map_err(|_| Error::InvalidUri("digits is not a number"))?;
+            }
+            "period" => {
+                period = value
+                    .parse()
+                    .map_err(|_| Error::InvalidUri("period is not a number"))?;
+            }
+            "counter" => {
+                counter = Some(
+                    value
+                        .parse()
+                        .map_err(|_| Error::InvalidUri("counter is not a number"))?,
+                );
+            }
+            _ => {}
+        }
+    }
+    if let (Some(label_issuer), Some(issuer)) = (&label_issuer, &issuer) {
+        if label_issuer != issuer {
+            return Err(Error::IssuerMismatch);
+        }
+    }
+    if is_hotp && counter.is_none() {
+        return Err(Error::InvalidUri("counter is required for HOTP"));
+    }
+    let key = Key {
+        issuer: issuer.or(label_issuer),
+        account,
+        secret: secret.ok_or(Error::InvalidUri("missing secret"))?,
+        algorithm,
+        digits,
+        period,
+        counter: counter.filter(|_| is_hotp),
+    };
+    key.validate()?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Key {
+        Key {
+            issuer: Some("Example Co".to_owned()),
+            account: "alice@example.com".to_owned(),
+            secret: b"Hello!\xde\xad\xbe\xef".to_vec(),
+            algorithm: OtpAlgorithm::Sha1,
+            digits: 6,
+            period: 30,
+            counter: None,
+        }
+    }
+
+    #[test]
+    fn base32() {
+        assert_eq!(encode_base32(b""), "");
+        assert_eq!(encode_base32(b"f"), "MY");
+        assert_eq!(encode_base32(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(decode_base32("MZXW6YTBOI======").unwrap(), b"foobar");
+        assert_eq!(decode_base32("mzxw 6ytb oi").unwrap(), b"foobar");
+        assert!(decode_base32("MZXW1").is_none());
+    }
+
+    #[test]
+    fn build_uri() {
+        assert_eq!(
+            key().to_uri().unwrap(),
+            "otpauth://totp/Example%20Co:alice%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example%20Co"
+        );
+        let key = Key {
+            issuer: None,
+            algorithm: OtpAlgorithm::Sha256,
+            digits: 8,
+            counter: Some(1),
+            ..key()
+        };
+        assert_eq!(
+            key.to_uri().unwrap(),
+            "otpauth://hotp/alice%40example.com?secret=JBSWY3DPEHPK3PXP&algorithm=SHA256&digits=8&counter=1"
+        );
+        let key = Key { period: 60, ..key };
+        assert!(key.to_uri().unwrap().ends_with("&counter=1"));
+    }
+
+    #[test]
+    fn build_invalid_uri() {
+        let key = Key {
+            issuer: Some("a:b".to_owned()),
+            ..key()
+        };
+        assert_eq!(key.to_uri().unwrap_err(), Error::ColonInLabel("issuer"));
+        let key = Key {
+            secret: Vec::new(),
+            ..key
+        };
+        assert_eq!(key.to_uri().unwrap_err(), Error::InvalidSecret);
+        let key = Key {
+            digits: 9,
+            ..self::key()
+        };
+        assert_eq!(key.to_uri().unwrap_err(), Error::InvalidDigits(9));
+    }
+
+    #[test]
+    fn parse_uri() {
+        assert_eq!(parse(&key().to_uri().unwrap()).unwrap(), key());
+        assert_eq!(
+            parse("otpauth://totp/Example%20Co:%20alice%40example.com?secret=jbswy3dpehpk3pxp")
+                .unwrap(),
+            key()
+        );
+        let key = parse(
+            "OTPAUTH://hotp/alice@example.com?secret=JBSWY3DPEHPK3PXP&algorithm=sha512&digits=8&counter=5&image=x",
+        )
+        .unwrap();
+        assert_eq!(key.issuer, None);
+        assert_eq!(key.algorithm, OtpAlgorithm::Sha512);
+        assert_eq!((key.digits, key.counter), (8, Some(5)));
+        assert_eq!(
+            key.fields(),
+            [
+                ("Type", "HOTP".to_owned()),
+                ("Account", "alice@example.com".to_owned()),
+                ("Secret", "JBSWY3DPEHPK3PXP".to_owned()),
+                ("Algorithm", "SHA512".to_owned()),
+                ("Digits", "8".to_owned()),
+                ("Counter", "5".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_uri() {
+        assert_eq!(
+            parse("https://example.com/").unwrap_err(),
+            Error::InvalidUri("scheme is not otpauth")
+        );
+        assert_eq!(
+            parse("otpauth://totp/alice").unwrap_err(),
+            Error::InvalidUri("missing secret")
+        );
+        assert_eq!(
+            parse("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP").unwrap_err(),
+            Error::InvalidUri("counter is required for HOTP")
+        );
+        assert_eq!(
+            parse("otpauth://totp/A:alice?secret=JBSWY3DPEHPK3PXP&issuer=B").unwrap_err(),
+            Error::IssuerMismatch
+        );
+        assert_eq!(
+            parse("otpauth://totp/alice?secret=JBSWY3DPEHPK3PX1").unwrap_err(),
+            Error::InvalidSecret
+        );
+        assert_eq!(
+            parse("otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&digits=4").unwrap_err(),
+            Error::InvalidDigits(4)
+        );
+        assert_eq!(
+            parse("otpauth://totp/alice%2?secret=JBSWY3DPEHPK3PXP").unwrap_err(),
+            Error::InvalidUri("malformed percent-encoding")
+        );
+    }
+}
@@ -0,0 +1,528 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt, ops::RangeInclusive};
+
+use super::epc;
+use crate::cli::Currency;
+
+/// The maximum version of the symbol allowed by the Swiss QR-bill.
+pub const MAX_VERSION: i16 = 25;
+
+/// The maximum length of the payload in characters.
+const MAX_PAYLOAD_LEN: usize = 997;
+
+/// The range of the institution identification of QR-IBANs.
+const QR_IID_RANGE: RangeInclusive<u32> = 30000..=31999;
+
+/// The names of the fields of the address of the creditor.
+const CREDITOR_FIELDS: [&str; 5] = [
+    "name of the creditor",
+    "street of the creditor",
+    "building number of the creditor",
+    "postal code of the creditor",
+    "town of the creditor",
+];
+
+/// The names of the fields of the address of the ultimate debtor.
+const DEBTOR_FIELDS: [&str; 5] = [
+    "name of the debtor",
+    "street of the debtor",
+    "building number of the debtor",
+    "postal code of the debtor",
+    "town of the debtor",
+];
+
+/// An error which can be returned when building the Swiss QR-bill payload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The IBAN is malformed, its check digits are wrong, or it is not a Swiss
+    /// or Liechtenstein IBAN.
+    InvalidIban(String),
+
+    /// The amount is malformed or out of range.
+    InvalidAmount(String),
+
+    /// The country code is not two uppercase letters.
+    InvalidCountry(String),
+
+    /// The QR-IBAN is specified without a QR reference.
+    MissingQrReference,
+
+    /// The QR reference is specified with an IBAN other than a QR-IBAN.
+    UnexpectedQrReference,
+
+    /// The QR reference is malformed or its check digit is wrong.
+    InvalidQrReference(String),
+
+    /// The creditor reference is malformed or its check digits are wrong.
+    InvalidCreditorReference(String),
+
+    /// The billing information does not start with "//".
+    InvalidBillingInformation,
+
+    /// The field is empty or too long.
+    InvalidLength {
+        /// The name of the field.
+        field: &'static str,
+
+        /// The maximum number of characters.
+        max: usize,
+    },
+
+    /// The field contains a line break.
+    LineBreak(&'static str),
+
+    /// The payload is too long.
+    PayloadTooLong(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidIban(iban) => write!(
+                f,
+                "invalid IBAN `{iban}`: expected a Swiss or Liechtenstein IBAN"
+            ),
+            Self::InvalidAmount(amount) => write!(
+                f,
+                "invalid amount `{amount}`: expected a number between 0.01 and 999999999.99 with up to 2 decimal places"
+            ),
+            Self::InvalidCountry(country) => write!(
+                f,
+                "invalid country code `{country}`: expected 2 uppercase letters"
+            ),
+            Self::MissingQrReference => write!(f, "QR reference is required for a QR-IBAN"),
+            Self::UnexpectedQrReference => {
+                write!(f, "QR reference can only be used with a QR-IBAN")
+            }
+            Self::InvalidQrReference(reference) => write!(
+                f,
+                "invalid QR reference `{reference}`: expected 27 digits with a valid check digit"
+            ),
+            Self::InvalidCreditorReference(reference) => {
+                write!(f, "invalid creditor reference `{reference}`")
+            }
+            Self::InvalidBillingInformation => {
+                write!(f, "billing information must start with \"//\"")
+            }
+            Self::InvalidLength { field, max } => {
+                write!(f, "{field} must be between 1 and {max} characters")
+            }
+            Self::LineBreak(field) => write!(f, "{field} must not contain line breaks"),
+            Self::PayloadTooLong(len) => write!(
+                f,
+                "payload must be at most {MAX_PAYLOAD_LEN} characters, but it is {len} characters"
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A structured address.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Address<'a> {
+    /// The name of the person or the company.
+    pub name: &'a str,
+
+    /// The street.
+    pub street: Option<&'a str>,
+
+    /// The building number.
+    pub building_number: Option<&'a str>,
+
+    /// The postal code.
+    pub postal_code: &'a str,
+
+    /// The town.
+    pub town: &'a str,
+
+    /// The ISO 3166-1 alpha-2 country code.
+    pub country: &'a str,
+}
+
+/// A payment part of the Swiss QR-bill.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bill<'a> {
+    /// The IBAN or the QR-IBAN of the account of the creditor.
+    pub account: &'a str,
+
+    /// The address of the creditor.
+    pub creditor: Address<'a>,
+
+    /// The amount.
+    pub amount: Option<&'a str>,
+
+    /// The currency of the amount.
+    pub currency: Currency,
+
+    /// The address of the ultimate debtor.
+    pub debtor: Option<Address<'a>>,
+
+    /// The QR reference or the structured creditor reference (ISO 11649).
+    pub reference: Option<&'a str>,
+
+    /// The unstructured message.
+    pub message: Option<&'a str>,
+
+    /// The structured billing information for the debtor.
+    pub billing_information: Option<&'a str>,
+}
+
+/// Removes the spaces from the IBAN and validates it.
+///
+/// Returns the IBAN and whether it is a QR-IBAN.
+fn normalize_iban(iban: &str) -> Result<(String, bool), Error> {
+    let normalized = iban.replace(' ', "").to_ascii_uppercase();
+    let is_valid = normalized.len() == 21
+        && normalized.is_ascii()
+        && matches!(&normalized[..2], "CH" | "LI")
+        && normalized[2..].bytes().all(|b| b.is_ascii_alphanumeric())
+        && epc::mod97(&format!("{}{}", &normalized[4..], &normalized[..4])) == Some(1);
+    if !is_valid {
+        return Err(Error::InvalidIban(iban.to_owned()));
+    }
+    let is_qr_iban = normalized[4..9]
+        .parse()
+        .is_ok_and(|iid| QR_IID_RANGE.contains(&iid));
+    Ok((normalized, is_qr_iban))
+}
+
+/// Computes the check digit of the QR reference using the recursive modulo 10
+/// algorithm.
+///
+/// Returns [`None`] if `digits` contains characters other than ASCII digits.
+fn mod10_check_digit(digits: &str) -> Option<u32> {
+    const TABLE: [u32; 10] = [0, 9, 4, 6, 8, 2, 7, 1, 3, 5];
+
+    let carry = digits.chars().try_fold(0, |carry, c| {
+        let digit = c.to_digit(10)?;
+        Some(TABLE[((carry + digit) % 10) as usize])
+    })?;
+    Some((10 - carry) % 10)
+}
+
+/// Removes the spaces from the QR reference and validates its check digit.
+fn normalize_qr_reference(reference: &str) -> Result<String, Error> {
+    let normalized = reference.replace(' ', "");
+    let is_valid = normalized.len() == 27
+        && normalized.is_ascii()
+        && mod10_check_digit(&normalized[..26])
+            .is_some_and(|digit| normalized[26..].parse() == Ok(digit));
+    if is_valid {
+        Ok(normalized)
+    } else {
+        Err(Error::InvalidQrReference(reference.to_owned()))
+    }
+}
+
+/// Removes the spaces from the creditor reference and validates its check
+/// digits.
+fn normalize_creditor_reference(reference: &str) -> Result<String, Error> {
+    let normalized = reference.replace(' ', "").to_ascii_uppercase();
+    let is_valid = (5..=25).contains(&normalized.len())
+        && normalized.is_ascii()
+        && normalized.starts_with("RF")
+        && normalized[2..4].bytes().all(|b| b.is_ascii_digit())
+        && epc::mod97(&format!("{}{}", &normalized[4..], &normalized[..4])) == Some(1);
+    if is_valid {
+        Ok(normalized)
+    } else {
+        Err(Error::InvalidCreditorReference(reference.to_owned()))
+    }
+}
+
+/// Validates the amount and formats it with 2 decimal places.
+fn normalize_amount(amount: &str) -> Result<String, Error> {
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_valid = (1..=9).contains(&integer.len())
+        && integer.bytes().all(|b| b.is_ascii_digit())
+        && fraction.len() <= 2
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+        && !amount.ends_with('.')
+        && integer.bytes().chain(fraction.bytes()).any(|b| b != b'0');
+    if !is_valid {
+        return Err(Error::InvalidAmount(amount.to_owned()));
+    }
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    Ok(format!("{integer}.{fraction:0<2}"))
+}
+
+fn validate_text(field: &'static str, value: &str, max: usize) -> Result<(), Error> {
+    if !(1..=max).contains(&value.chars().count()) {
+        return Err(Error::InvalidLength { field, max });
+    }
+    if value.contains(['\r', '\n']) {
+        return Err(Error::LineBreak(field));
+    }
+    Ok(())
+}
+
+impl Address<'_> {
+    /// Validates the address and returns its fields as the structured address
+    /// (address type "S").
+    fn to_fields(&self, names: &[&'static str; 5]) -> Result<[&str; 7], Error> {
+        validate_text(names[0], self.name, 70)?;
+        if let Some(street) = self.street {
+            validate_text(names[1], street, 70)?;
+        }
+        if let Some(building_number) = self.building_number {
+            validate_text(names[2], building_number, 16)?;
+        }
+        validate_text(names[3], self.postal_code, 16)?;
+        validate_text(names[4], self.town, 35)?;
+        if self.country.len() != 2 || !self.country.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(Error::InvalidCountry(self.country.to_owned()));
+        }
+        Ok([
+            "S",
+            self.name,
+            self.street.unwrap_or_default(),
+            self.building_number.unwrap_or_default(),
+            self.postal_code,
+            self.town,
+            self.country,
+        ])
+    }
+}
+
+/// Builds the payload of the Swiss QR-bill (the Swiss Payments Code).
+///
+/// The payload is in version 2.0 and is encoded in UTF-8. The addresses are
+/// structured addresses, and the reference type is derived from the account
+/// and the reference: a QR-IBAN requires a QR reference, and an IBAN takes a
+/// creditor reference or no reference.
+pub fn build(bill: &Bill<'_>) -> Result<String, Error> {
+    let (account, is_qr_iban) = normalize_iban(bill.account)?;
+    let creditor = bill.creditor.to_fields(&CREDITOR_FIELDS)?;
+    let amount = bill.amount.map(normalize_amount).transpose()?;
+    let debtor = bill
+        .debtor
+        .as_ref()
+        .map(|debtor| debtor.to_fields(&DEBTOR_FIELDS))
+        .transpose()?
+        .unwrap_or_default();
+    let (reference_type, reference) = match (bill.reference, is_qr_iban) {
+        (Some(reference), true) => ("QRR", normalize_qr_reference(reference)?),
+        (None, true) => return Err(Error::MissingQrReference),
+        (Some(reference), false) => {
+            if normalize_qr_reference(reference).is_ok() {
+                return Err(Error::UnexpectedQrReference);
+            }
+            ("SCOR", normalize_creditor_reference(reference)?)
+        }
+        (None, false) => ("NON", String::new()),
+    };
+    if let Some(message) = bill.message {
+        validate_text("message", message, 140)?;
+    }
+    if let Some(billing_information) = bill.billing_information {
+        validate_text("billing information", billing_information, 140)?;
+        if !billing_information.starts_with("//") {
+            return Err(Error::InvalidBillingInformation);
+        }
+    }
+    let additional_information_len = [bill.message, bill.billing_information]
+        .into_iter()
+        .flatten()
+        .map(|value| value.chars().count())
+        .sum::<usize>();
+    if additional_information_len > 140 {
+        return Err(Error::InvalidLength {
+            field: "message and billing information",
+            max: 140,
+        });
+    }
+
+    let currency = match bill.currency {
+        Currency::Chf => "CHF",
+        Currency::Eur => "EUR",
+    };
+    let mut fields = vec!["SPC", "0200", "1", &account];
+    fields.extend(creditor);
+    fields.extend([""; 7]);
+    fields.extend([amount.as_deref().unwrap_or_default(), currency]);
+    fields.extend(debtor);
+    fields.extend([
+        reference_type,
+        &reference,
+        bill.message.unwrap_or_default(),
+        "EPD",
+    ]);
+    if let Some(billing_information) = bill.billing_information {
+        fields.push(billing_information);
+    }
+    let payload = fields.join("\n");
+    let len = payload.chars().count();
+    if len > MAX_PAYLOAD_LEN {
+        return Err(Error::PayloadTooLong(len));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IBAN: &str = "CH5800791123000889012";
+
+    const QR_IBAN: &str = "CH4431999123000889012";
+
+    const CREDITOR: Address<'_> = Address {
+        name: "Robert Schneider AG",
+        street: Some("Rue du Lac"),
+        building_number: Some("1268"),
+        postal_code: "2501",
+        town: "Biel",
+        country: "CH",
+    };
+
+    fn new_bill<'a>(account: &'a str, reference: Option<&'a str>) -> Bill<'a> {
+        Bill {
+            account,
+            creditor: CREDITOR,
+            amount: None,
+            currency: Currency::Chf,
+            debtor: None,
+            reference,
+            message: None,
+            billing_information: None,
+        }
+    }
+
+    #[test]
+    fn build_payload() {
+        let bill = Bill {
+            amount: Some("1949.75"),
+            debtor: Some(Address {
+                name: "Pia-Maria Rutschmann-Schnyder",
+                street: Some("Grosse Marktgasse"),
+                building_number: Some("28"),
+                postal_code: "9400",
+                town: "Rorschach",
+                country: "CH",
+            }),
+            message: Some("Order of 15 June 2020"),
+            billing_information: Some("//S1/10/10201409/11/200701/20/140.000-53"),
+            ..new_bill(
+                "CH44 3199 9123 0008 8901 2",
+                Some("21 00000 00003 13947 14300 09017"),
+            )
+        };
+        assert_eq!(
+            build(&bill).unwrap(),
+            "SPC\n0200\n1\nCH4431999123000889012\nS\nRobert Schneider AG\nRue du Lac\n1268\n2501\nBiel\nCH\n\n\n\n\n\n\n\n1949.75\nCHF\nS\nPia-Maria Rutschmann-Schnyder\nGrosse Marktgasse\n28\n9400\nRorschach\nCH\nQRR\n210000000003139471430009017\nOrder of 15 June 2020\nEPD\n//S1/10/10201409/11/200701/20/140.000-53"
+        );
+        let bill = Bill {
+            amount: Some("012.5"),
+            currency: Currency::Eur,
+            ..new_bill(IBAN, Some("RF18539007547034"))
+        };
+        assert_eq!(
+            build(&bill).unwrap(),
+            "SPC\n0200\n1\nCH5800791123000889012\nS\nRobert Schneider AG\nRue du Lac\n1268\n2501\nBiel\nCH\n\n\n\n\n\n\n\n12.50\nEUR\n\n\n\n\n\n\n\nSCOR\nRF18539007547034\n\nEPD"
+        );
+        assert!(
+            build(&new_bill(IBAN, None))
+                .unwrap()
+                .ends_with("\nNON\n\n\nEPD")
+        );
+    }
+
+    #[test]
+    fn validate_iban() {
+        assert_eq!(
+            normalize_iban("ch44 3199 9123 0008 8901 2").unwrap(),
+            (QR_IBAN.to_owned(), true)
+        );
+        assert_eq!(normalize_iban(IBAN).unwrap(), (IBAN.to_owned(), false));
+        assert!(normalize_iban("CH4431999123000889013").is_err());
+        assert!(normalize_iban("DE89370400440532013000").is_err());
+    }
+
+    #[test]
+    fn validate_references() {
+        assert_eq!(mod10_check_digit("21000000000313947143000901"), Some(7));
+        assert_eq!(mod10_check_digit("00000000000000000000000000"), Some(0));
+        assert!(normalize_qr_reference("210000000003139471430009017").is_ok());
+        assert!(normalize_qr_reference("210000000003139471430009016").is_err());
+        assert!(normalize_qr_reference("21000000000313947143000901").is_err());
+        assert_eq!(
+            normalize_creditor_reference("rf18 5390 0754 7034").unwrap(),
+            "RF18539007547034"
+        );
+        assert!(normalize_creditor_reference("RF19539007547034").is_err());
+    }
+
+    #[test]
+    fn validate_amount() {
+        assert_eq!(normalize_amount("0.01").unwrap(), "0.01");
+        assert_eq!(normalize_amount("100").unwrap(), "100.00");
+        assert_eq!(normalize_amount("999999999.99").unwrap(), "999999999.99");
+        assert!(normalize_amount("0").is_err());
+        assert!(normalize_amount("1000000000").is_err());
+        assert!(normalize_amount("1.234").is_err());
+    }
+
+    #[test]
+    fn build_invalid_payload() {
+        assert_eq!(
+            build(&new_bill(QR_IBAN, None)).unwrap_err(),
+            Error::MissingQrReference
+        );
+        assert_eq!(
+            build(&new_bill(QR_IBAN, Some("RF18539007547034"))).unwrap_err(),
+            Error::InvalidQrReference("RF18539007547034".to_owned())
+        );
+        assert_eq!(
+            build(&new_bill(IBAN, Some("210000000003139471430009017"))).unwrap_err(),
+            Error::UnexpectedQrReference
+        );
+        let bill = Bill {
+            creditor: Address {
+                country: "ch",
+                ..CREDITOR
+            },
+            ..new_bill(QR_IBAN, Some("210000000003139471430009017"))
+        };
+        assert_eq!(
+            build(&bill).unwrap_err(),
+            Error::InvalidCountry("ch".to_owned())
+        );
+        let bill = Bill {
+            creditor: Address {
+                town: "",
+                ..CREDITOR
+            },
+            ..bill
+        };
+        assert_eq!(
+            build(&bill).unwrap_err(),
+            Error::InvalidLength {
+                field: "town of the creditor",
+                max: 35
+            }
+        );
+        let bill = Bill {
+            creditor: CREDITOR,
+            message: Some("a\nb"),
+            ..bill
+        };
+        assert_eq!(build(&bill).unwrap_err(), Error::LineBreak("message"));
+        let bill = Bill {
+            message: Some(&"a".repeat(100)),
+            billing_information: Some(&"/".repeat(41)),
+            ..bill
+        };
+        assert_eq!(
+            build(&bill).unwrap_err(),
+            Error::InvalidLength {
+                field: "message and billing information",
+                max: 140
+            }
+        );
+    }
+}
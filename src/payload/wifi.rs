@@ -0,0 +1,387 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{error, fmt};
+
+use super::{split_fields, unescape_field};
+use crate::cli::Security;
+
+/// The maximum length of the SSID in bytes.
+const MAX_SSID_LEN: usize = 32;
+
+/// An error which can be returned when building the Wi-Fi network payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The SSID is empty or too long.
+    InvalidSsidLength(usize),
+
+    /// The password is required for the security type.
+    MissingPassword(Security),
+
+    /// The password cannot be specified for an open network.
+    UnexpectedPassword,
+
+    /// The password is invalid for the security type.
+    InvalidPassword(Security),
+
+    /// The payload is malformed.
+    InvalidPayload(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSsidLength(len) => write!(
+                f,
+                "SSID must be between 1 and {MAX_SSID_LEN} bytes, but it is {len} bytes"
+            ),
+            Self::MissingPassword(security) => {
+                write!(f, "password is required for {}", name(*security))
+            }
+            Self::UnexpectedPassword => {
+                write!(f, "password cannot be specified for an open network")
+            }
+            Self::InvalidPassword(Security::Wpa) => write!(
+                f,
+                "password for WPA must be between 8 and 63 ASCII characters, or 64 hexadecimal digits"
+            ),
+            Self::InvalidPassword(Security::Wpa3) => {
+                write!(f, "password for WPA3 must be at least 8 characters")
+            }
+            Self::InvalidPassword(Security::Wep) => write!(
+                f,
+                "password for WEP must be 5 or 13 ASCII characters, or 10 or 26 hexadecimal digits"
+            ),
+            Self::InvalidPassword(Security::Nopass) => unreachable!(),
+            Self::InvalidPayload(reason) => write!(f, "invalid Wi-Fi network payload: {reason}"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+const fn name(security: Security) -> &'static str {
+    match security {
+        Security::Wpa => "WPA",
+        Security::Wpa3 => "WPA3",
+        Security::Wep => "WEP",
+        Security::Nopass => "an open network",
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Returns [`true`] if the password is a raw key in hexadecimal rather than a
+/// passphrase.
+fn is_hex_key(password: &str, security: Security) -> bool {
+    let len = password.len();
+    is_hex(password)
+        && match security {
+            Security::Wpa => len == 64,
+            Security::Wep => matches!(len, 10 | 26),
+            Security::Wpa3 | Security::Nopass => false,
+        }
+}
+
+fn validate_password(password: &str, security: Security) -> Result<(), Error> {
+    let is_valid = match security {
+        Security::Wpa => {
+            is_hex_key(password, security)
+                || (password.is_ascii() && (8..=63).contains(&password.len()))
+        }
+        Security::Wpa3 => password.chars().count() >= 8,
+        Security::Wep => {
+            is_hex_key(password, security)
+                || (password.is_ascii() && matches!(password.len(), 5 | 13))
+        }
+        Security::Nopass => unreachable!(),
+    };
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidPassword(security))
+    }
+}
+
+/// Escapes the special characters in the field, and quotes it if it could be
+/// interpreted as hexadecimal.
+fn escape(field: &str, quote: bool) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    if quote {
+        format!("\"{escaped}\"")
+    } else {
+        escaped
+    }
+}
+
+/// Builds the payload for joining the Wi-Fi network.
+///
+/// The payload is in the `WIFI:` URI format defined in the WPA3 specification.
+/// For WPA3, the transition disable indication (`R:1`) is added so that the
+/// device does not fall back to WPA2.
+pub fn build(
+    ssid: &str,
+    password: Option<&str>,
+    security: Security,
+    hidden: bool,
+) -> Result<String, Error> {
+    if !(1..=MAX_SSID_LEN).contains(&ssid.len()) {
+        return Err(Error::InvalidSsidLength(ssid.len()));
+    }
+    match (security, password) {
+        (Security::Nopass, Some(_)) => return Err(Error::UnexpectedPassword),
+        (Security::Nopass, None) => {}
+        (security, None) => return Err(Error::MissingPassword(security)),
+        (security, Some(password)) => validate_password(password, security)?,
+    }
+
+    let mut payload = String::from("WIFI:");
+    payload.push_str(match security {
+        Security::Wpa => "T:WPA;",
+        Security::Wpa3 => "T:WPA;R:1;",
+        Security::Wep => "T:WEP;",
+        Security::Nopass => "T:nopass;",
+    });
+    payload.push_str("S:");
+    payload.push_str(&escape(ssid, is_hex(ssid)));
+    payload.push(';');
+    if hidden {
+        payload.push_str("H:true;");
+    }
+    if let Some(password) = password {
+        let quote = is_hex(password) && !is_hex_key(password, security);
+        payload.push_str("P:");
+        payload.push_str(&escape(password, quote));
+        payload.push(';');
+    }
+    payload.push(';');
+    Ok(payload)
+}
+
+/// A Wi-Fi network.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Network {
+    /// The SSID of the network.
+    pub ssid: String,
+
+    /// The password of the network.
+    pub password: Option<String>,
+
+    /// The security type of the network.
+    pub security: Security,
+
+    /// Whether the network does not broadcast its SSID.
+    pub hidden: bool,
+}
+
+impl Network {
+    /// Returns the fields of the network as pairs of the name and the value.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let security = match self.security {
+            Security::Nopass => "None",
+            security => name(security),
+        };
+        let mut fields = vec![
+            ("SSID", self.ssid.clone()),
+            ("Security", security.to_owned()),
+        ];
+        if let Some(ref password) = self.password {
+            fields.push(("Password", password.clone()));
+        }
+        fields.push(("Hidden", self.hidden.to_string()));
+        fields
+    }
+}
+
+/// Unescapes the field, and removes the quotes around it.
+fn unquote(field: &str) -> String {
+    field
+        .strip_prefix('"')
+        .and_then(|field| field.strip_suffix('"'))
+        .filter(|field| !field.ends_with('\\') || field.ends_with("\\\\"))
+        .map_or_else(|| unescape_field(field), unescape_field)
+}
+
+/// Parses the payload in the `WIFI:` URI format.
+///
+/// `SAE` and the transition disable indication (`R:1`) are interpreted as
+/// WPA3. The unknown fields are ignored.
+pub fn parse(payload: &str) -> Result<Network, Error> {
+    let rest = payload
+        .get(..5)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("WIFI:"))
+        .map(|_| &payload[5..])
+        .ok_or(Error::InvalidPayload("scheme is not WIFI"))?;
+    let (mut ssid, mut password, mut kind, mut hidden, mut is_wpa3) =
+        (None, None, None, false, false);
+    for (key, value) in split_fields(rest) {
+        match key {
+            "S" => ssid = Some(unquote(value)),
+            "P" => password = Some(unquote(value)),
+            "T" => kind = Some(value.to_ascii_uppercase()),
+            "H" => hidden = value.eq_ignore_ascii_case("true"),
+            "R" => is_wpa3 = value == "1",
+            _ => {}
+        }
+    }
+    let ssid = ssid
+        .filter(|ssid| !ssid.is_empty())
+        .ok_or(Error::InvalidPayload("missing SSID"))?;
+    let security = match kind.as_deref() {
+        Some("WPA" | "WPA2") if is_wpa3 => Security::Wpa3,
+        Some("WPA" | "WPA2") => Security::Wpa,
+        Some("SAE" | "WPA3") => Security::Wpa3,
+        Some("WEP") => Security::Wep,
+        Some("NOPASS" | "") | None => Security::Nopass,
+        Some(_) => return Err(Error::InvalidPayload("unknown security type")),
+    };
+    Ok(Network {
+        ssid,
+        password: password.filter(|password| !password.is_empty()),
+        security,
+        hidden,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_payload() {
+        assert_eq!(
+            build("example", Some("password"), Security::Wpa, false).unwrap(),
+            "WIFI:T:WPA;S:example;P:password;;"
+        );
+        assert_eq!(
+            build("example", Some("password"), Security::Wpa3, true).unwrap(),
+            "WIFI:T:WPA;R:1;S:example;H:true;P:password;;"
+        );
+        assert_eq!(
+            build("example", Some("12345"), Security::Wep, false).unwrap(),
+            "WIFI:T:WEP;S:example;P:\"12345\";;"
+        );
+        assert_eq!(
+            build("example", Some("0123456789"), Security::Wep, false).unwrap(),
+            "WIFI:T:WEP;S:example;P:0123456789;;"
+        );
+        assert_eq!(
+            build("example", None, Security::Nopass, false).unwrap(),
+            "WIFI:T:nopass;S:example;;"
+        );
+    }
+
+    #[test]
+    fn escape_field() {
+        assert_eq!(
+            build(
+                r#"a;b,c:d\e"f"#,
+                Some("p;a,s:s\\w\"d"),
+                Security::Wpa,
+                false
+            )
+            .unwrap(),
+            r#"WIFI:T:WPA;S:a\;b\,c\:d\\e\"f;P:p\;a\,s\:s\\w\"d;;"#
+        );
+        assert_eq!(
+            build("CAFE", Some("deadbeef"), Security::Wpa, false).unwrap(),
+            r#"WIFI:T:WPA;S:"CAFE";P:"deadbeef";;"#
+        );
+        let key = "0123456789abcdef".repeat(4);
+        assert_eq!(
+            build("example", Some(&key), Security::Wpa, false).unwrap(),
+            format!("WIFI:T:WPA;S:example;P:{key};;")
+        );
+    }
+
+    #[test]
+    fn parse_payload() {
+        for (ssid, password, security, hidden) in [
+            ("example", Some("password"), Security::Wpa, false),
+            ("example", Some("password"), Security::Wpa3, true),
+            ("CAFE", Some("12345"), Security::Wep, false),
+            (r#"a;b,c:d\e"f"#, None, Security::Nopass, false),
+        ] {
+            let payload = build(ssid, password, security, hidden).unwrap();
+            assert_eq!(
+                parse(&payload).unwrap(),
+                Network {
+                    ssid: ssid.to_owned(),
+                    password: password.map(str::to_owned),
+                    security,
+                    hidden
+                }
+            );
+        }
+        assert_eq!(
+            parse("WIFI:S:example;T:SAE;P:password;;").unwrap().security,
+            Security::Wpa3
+        );
+        assert_eq!(
+            parse("WIFI:S:example;;").unwrap().fields(),
+            [
+                ("SSID", "example".to_owned()),
+                ("Security", "None".to_owned()),
+                ("Hidden", "false".to_owned())
+            ]
+        );
+        assert_eq!(
+            parse("WIFI:T:WPA;P:password;;").unwrap_err(),
+            Error::InvalidPayload("missing SSID")
+        );
+        assert_eq!(
+            parse("WIFI:T:EAP;S:example;;").unwrap_err(),
+            Error::InvalidPayload("unknown security type")
+        );
+    }
+
+    #[test]
+    fn build_invalid_payload() {
+        assert_eq!(
+            build("", None, Security::Nopass, false).unwrap_err(),
+            Error::InvalidSsidLength(0)
+        );
+        assert_eq!(
+            build(&"a".repeat(33), None, Security::Nopass, false).unwrap_err(),
+            Error::InvalidSsidLength(33)
+        );
+        assert_eq!(
+            build("example", None, Security::Wpa, false).unwrap_err(),
+            Error::MissingPassword(Security::Wpa)
+        );
+        assert_eq!(
+            build("example", Some("password"), Security::Nopass, false).unwrap_err(),
+            Error::UnexpectedPassword
+        );
+        assert_eq!(
+            build("example", Some("short"), Security::Wpa, false).unwrap_err(),
+            Error::InvalidPassword(Security::Wpa)
+        );
+        assert_eq!(
+            build("example", Some(&"g".repeat(64)), Security::Wpa, false).unwrap_err(),
+            Error::InvalidPassword(Security::Wpa)
+        );
+        assert_eq!(
+            build("example", Some("pässwörd"), Security::Wpa, false).unwrap_err(),
+            Error::InvalidPassword(Security::Wpa)
+        );
+        assert!(build("example", Some("pässwörd"), Security::Wpa3, false).is_ok());
+        assert_eq!(
+            build("example", Some("short"), Security::Wpa3, false).unwrap_err(),
+            Error::InvalidPassword(Security::Wpa3)
+        );
+        assert_eq!(
+            build("example", Some("password"), Security::Wep, false).unwrap_err(),
+            Error::InvalidPassword(Security::Wep)
+        );
+    }
+}
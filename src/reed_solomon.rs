@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use rqrr::DeQRError;
+
+/// Exponent and logarithm tables for GF(2^8) with the primitive polynomial
+/// x^8 + x^4 + x^3 + x^2 + 1.
+static TABLES: ([u8; 256], [u8; 256]) = build_tables();
+
+#[allow(clippy::cast_possible_truncation)]
+const fn build_tables() -> ([u8; 256], [u8; 256]) {
+    let (mut exp, mut log) = ([0; 256], [0; 256]);
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    exp[255] = exp[0];
+    (exp, log)
+}
+
+/// Returns α^`e`.
+const fn alpha_pow(e: usize) -> u8 {
+    TABLES.0[e % 255]
+}
+
+const fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    TABLES.0[(TABLES.1[a as usize] as usize + TABLES.1[b as usize] as usize) % 255]
+}
+
+const fn div(a: u8, b: u8) -> u8 {
+    debug_assert!(b != 0, "division by zero");
+    if a == 0 {
+        return 0;
+    }
+    TABLES.0[(TABLES.1[a as usize] as usize + 255 - TABLES.1[b as usize] as usize) % 255]
+}
+
+/// Evaluates the polynomial whose coefficients are in ascending order of
+/// degree.
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |acc, &c| mul(acc, x) ^ c)
+}
+
+/// Computes the syndromes of the block whose codewords are in descending order
+/// of degree.
+fn syndromes(block: &[u8], ecc_len: usize) -> Vec<u8> {
+    (0..ecc_len)
+        .map(|i| {
+            let x = alpha_pow(i);
+            block.iter().fold(0, |acc, &c| mul(acc, x) ^ c)
+        })
+        .collect()
+}
+
+/// Computes the error locator polynomial using the Berlekamp-Massey algorithm.
+///
+/// Returns the polynomial and the number of errors.
+fn error_locator(syndromes: &[u8]) -> (Vec<u8>, usize) {
+    let (mut locator, mut prev) = (vec![1], vec![1]);
+    let (mut errors, mut shift, mut prev_discrepancy) = (0, 1, 1);
+    for (k, &syndrome) in syndromes.iter().enumerate() {
+        let discrepancy = (1..=errors).fold(syndrome, |d, i| {
+            d ^ mul(
+                locator.get(i).copied().unwrap_or_default(),
+                syndromes[k - i],
+            )
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let coef = div(discrepancy, prev_discrepancy);
+        let mut next = locator.clone();
+        next.resize(next.len().max(prev.len() + shift), 0);
+        for (i, &p) in prev.iter().enumerate() {
+            next[i + shift] ^= mul(coef, p);
+        }
+        if 2 * errors <= k {
+            prev = locator;
+            errors = k + 1 - errors;
+            prev_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        locator = next;
+    }
+    (locator, errors)
+}
+
+/// Corrects errors in the block in place.
+///
+/// The block consists of the data codewords followed by `ecc_len` error
+/// correction codewords.
+pub fn correct(block: &mut [u8], ecc_len: usize) -> Result<(), DeQRError> {
+    let syndromes = self::syndromes(block, ecc_len);
+    if syndromes.iter().all(|&s| s == 0) {
+        return Ok(());
+    }
+
+    let (locator, errors) = error_locator(&syndromes);
+    if errors * 2 > ecc_len {
+        return Err(DeQRError::DataEcc);
+    }
+    let evaluator = (0..ecc_len)
+        .map(|i| {
+            (0..=i.min(locator.len() - 1)).fold(0, |acc, j| acc ^ mul(syndromes[i - j], locator[j]))
+        })
+        .collect::<Vec<_>>();
+    let derivative = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &c)| if i % 2 == 1 { c } else { 0 })
+        .collect::<Vec<_>>();
+
+    let len = block.len();
+    let mut found = 0;
+    for (position, codeword) in block.iter_mut().enumerate() {
+        let degree = len - 1 - position;
+        let x_inv = alpha_pow(255 - degree % 255);
+        if eval(&locator, x_inv) != 0 {
+            continue;
+        }
+        let denominator = eval(&derivative, x_inv);
+        if denominator == 0 {
+            return Err(DeQRError::DataEcc);
+        }
+        *codeword ^= mul(alpha_pow(degree), div(eval(&evaluator, x_inv), denominator));
+        found += 1;
+    }
+
+    if found == errors && self::syndromes(block, ecc_len).iter().all(|&s| s == 0) {
+        Ok(())
+    } else {
+        Err(DeQRError::DataEcc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qrcode2::ec;
+
+    use super::*;
+
+    fn block(data: &[u8], ecc_len: usize) -> Vec<u8> {
+        [data, &ec::create_error_correction_code(data, ecc_len)].concat()
+    }
+
+    #[test]
+    fn validate_tables() {
+        assert_eq!(alpha_pow(0), 1);
+        assert_eq!(alpha_pow(8), 0x1d);
+        assert_eq!(alpha_pow(255), 1);
+        assert_eq!(mul(0x02, 0x80), 0x1d);
+        assert_eq!(div(mul(0x53, 0xca), 0xca), 0x53);
+    }
+
+    #[test]
+    fn correct_without_errors() {
+        let expected = block(b"QR code", 10);
+        let mut actual = expected.clone();
+        correct(&mut actual, 10).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn correct_errors() {
+        let data = (0..=u8::MAX).cycle().skip(7).step_by(3).take(100);
+        let expected = block(&data.collect::<Vec<_>>(), 30);
+        for errors in 1..=15 {
+            let mut actual = expected.clone();
+            for i in 0..errors {
+                actual[i * 8 + 1] ^= 0x5a;
+            }
+            correct(&mut actual, 30).unwrap();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn correct_too_many_errors() {
+        let mut actual = block(b"QR code", 10);
+        for codeword in actual.iter_mut().take(6) {
+            *codeword ^= 0xff;
+        }
+        assert!(matches!(correct(&mut actual, 10), Err(DeQRError::DataEcc)));
+    }
+}
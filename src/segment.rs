@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow, bail};
+use clap::ValueEnum;
+use encoding_rs::SHIFT_JIS;
+use serde::Deserialize;
+
+use crate::cli::{Eci, Mode};
+
+/// The characters which can be encoded in alphanumeric mode.
+const ALPHANUMERIC_CHARSET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// A segment of the data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Segment {
+    /// The data encoded in the mode.
+    Data(Mode, Vec<u8>),
+
+    /// The ECI designator.
+    Eci(u32),
+}
+
+/// A segment in the JSON specification.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSegment {
+    mode: String,
+    data: String,
+}
+
+/// Replaces the escape sequences in the data.
+///
+/// The supported escape sequences are `\\`, `\n`, `\r`, `\t`, and `\xHH`.
+fn unescape(data: &str) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(data.len());
+    let mut chars = data.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut bytes = [u8::default(); 4];
+            buf.extend_from_slice(c.encode_utf8(&mut bytes).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => buf.push(b'\\'),
+            Some('n') => buf.push(b'\n'),
+            Some('r') => buf.push(b'\r'),
+            Some('t') => buf.push(b'\t'),
+            Some('x') => {
+                let hex = chars.by_ref().take(2).collect::<String>();
+                let byte = (hex.len() == 2)
+                    .then(|| u8::from_str_radix(&hex, 16).ok())
+                    .flatten()
+                    .ok_or_else(|| anyhow!("invalid escape sequence `\\x{hex}`"))?;
+                buf.push(byte);
+            }
+            Some(c) => bail!("invalid escape sequence `\\{c}`"),
+            None => bail!("incomplete escape sequence"),
+        }
+    }
+    Ok(buf)
+}
+
+impl Segment {
+    /// Constructs a new segment from the mode name and the data.
+    ///
+    /// The data in Kanji mode is converted from UTF-8 to Shift JIS if it is a
+    /// valid UTF-8 string.
+    fn new(mode: &str, data: &str) -> anyhow::Result<Self> {
+        if mode.eq_ignore_ascii_case("eci") {
+            return match Eci::from_str(data)? {
+                Eci::Designator(designator) => Ok(Self::Eci(designator)),
+                Eci::None => bail!("ECI segment requires a character set"),
+            };
+        }
+        let mode = Mode::from_str(mode, true).map_err(|_| anyhow!("unknown mode `{mode}`"))?;
+        let mut data = unescape(data)?;
+        match mode {
+            Mode::Numeric if !data.iter().all(u8::is_ascii_digit) => {
+                bail!("data contains characters other than digits");
+            }
+            Mode::Alphanumeric if !data.iter().all(|b| ALPHANUMERIC_CHARSET.contains(b)) => {
+                bail!("data contains characters which cannot be encoded in alphanumeric mode");
+            }
+            Mode::Kanji => {
+                if let Ok(text) = std::str::from_utf8(&data) {
+                    let (text, _, has_unmappable) = SHIFT_JIS.encode(text);
+                    if has_unmappable {
+                        bail!("data cannot be encoded in Shift JIS");
+                    }
+                    data = text.into_owned();
+                }
+                let is_kanji = |c: &[u8]| {
+                    let c = u16::from_be_bytes([c[0], c.get(1).copied().unwrap_or_default()]);
+                    (0x8140..=0x9ffc).contains(&c) || (0xe040..=0xebbf).contains(&c)
+                };
+                if data.len() % 2 != 0 || !data.chunks(2).all(is_kanji) {
+                    bail!("data contains characters which cannot be encoded in Kanji mode");
+                }
+            }
+            _ => {}
+        }
+        Ok(Self::Data(mode, data))
+    }
+}
+
+/// Parses the segment specification.
+///
+/// If the specification starts with `[`, it is parsed as a JSON array of
+/// objects with `mode` and `data`. Otherwise, each line is parsed as a mode
+/// and the data separated by a space. Empty lines and lines starting with `#`
+/// are ignored.
+pub fn parse(spec: &str) -> anyhow::Result<Vec<Segment>> {
+    if spec.trim_start().starts_with('[') {
+        let segments = serde_json::from_str::<Vec<RawSegment>>(spec)
+            .context("could not parse the segment specification as JSON")?;
+        return segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                Segment::new(&segment.mode, &segment.data)
+                    .with_context(|| format!("invalid segment at index {i}"))
+            })
+            .collect();
+    }
+    spec.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let (mode, data) = line.split_once(' ').unwrap_or((line, ""));
+            Segment::new(mode, data).with_context(|| format!("invalid segment at line {}", i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_data() {
+        assert_eq!(unescape("QR code").unwrap(), b"QR code");
+        assert_eq!(unescape("コード").unwrap(), "コード".as_bytes());
+        assert_eq!(
+            unescape(r"\\\n\r\t\x00\xFFa").unwrap(),
+            b"\\\n\r\t\x00\xffa"
+        );
+        assert!(unescape(r"\x0").is_err());
+        assert!(unescape(r"\xgg").is_err());
+        assert!(unescape(r"\a").is_err());
+        assert!(unescape("\\").is_err());
+    }
+
+    #[test]
+    fn parse_text_specification() {
+        let spec = "# comment\n\
+                    eci utf-8\n\
+                    byte QR code\n\
+                    \n\
+                    numeric 0123\n\
+                    alphanumeric AC-42\n\
+                    kanji 点茗\n\
+                    byte \\xff\n";
+        assert_eq!(
+            parse(spec).unwrap(),
+            [
+                Segment::Eci(26),
+                Segment::Data(Mode::Byte, b"QR code".to_vec()),
+                Segment::Data(Mode::Numeric, b"0123".to_vec()),
+                Segment::Data(Mode::Alphanumeric, b"AC-42".to_vec()),
+                Segment::Data(Mode::Kanji, b"\x93\x5f\xe4\xaa".to_vec()),
+                Segment::Data(Mode::Byte, b"\xff".to_vec()),
+            ]
+        );
+        assert_eq!(
+            parse("BYTE  QR").unwrap(),
+            [Segment::Data(Mode::Byte, b" QR".to_vec())]
+        );
+    }
+
+    #[test]
+    fn parse_json_specification() {
+        let spec = r#"[
+            {"mode": "eci", "data": "shift_jis"},
+            {"mode": "kanji", "data": "点茗"},
+            {"mode": "byte", "data": "\\x83R"}
+        ]"#;
+        assert_eq!(
+            parse(spec).unwrap(),
+            [
+                Segment::Eci(20),
+                Segment::Data(Mode::Kanji, b"\x93\x5f\xe4\xaa".to_vec()),
+                Segment::Data(Mode::Byte, b"\x83R".to_vec()),
+            ]
+        );
+        assert!(parse(r#"[{"mode": "byte"}]"#).is_err());
+        assert!(parse(r#"[{"mode": "byte", "data": "", "foo": 0}]"#).is_err());
+    }
+
+    #[test]
+    fn parse_invalid_specification() {
+        assert_eq!(
+            format!("{:#}", parse("byte QR\nfoo QR").unwrap_err()),
+            "invalid segment at line 2: unknown mode `foo`"
+        );
+        assert_eq!(
+            format!("{:#}", parse("eci none").unwrap_err()),
+            "invalid segment at line 1: ECI segment requires a character set"
+        );
+        assert!(parse("numeric 01a").is_err());
+        assert!(parse("alphanumeric ac").is_err());
+        assert!(parse("kanji Ω̈").is_err());
+        assert!(parse("kanji QR").is_err());
+        assert!(parse(r#"[{"mode": "eci", "data": "utf-32"}]"#).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use predicates::prelude::predicate;
+
+use crate::utils::command;
+
+#[test]
+fn basic_capacity() {
+    command::command()
+        .arg("capacity")
+        .arg("--variant")
+        .arg("micro")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Version  Level  Width  Height  Bits  Numeric  Alphanumeric  Byte  Kanji\n",
+            "M1       L         11      11    20        5             -     -      -\n",
+            "M2       L         13      13    40       10             6     -      -\n",
+            "M2       M         13      13    32        8             5     -      -\n",
+            "M3       L         15      15    84       23            14     9      6\n",
+            "M3       M         15      15    68       18            11     7      4\n",
+            "M4       L         17      17   128       35            21    15      9\n",
+            "M4       M         17      17   112       30            18    13      8\n",
+            "M4       Q         17      17    80       21            13     9      5\n"
+        )));
+}
+
+#[test]
+fn infer_subcommand_name_for_capacity_command() {
+    command::command()
+        .arg("cap")
+        .arg("-V")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("qrtool-capacity"));
+}
+
+#[test]
+fn capacity_of_all_types() {
+    command::command()
+        .arg("capacity")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(concat!(
+            "Version  Level  Width  Height   Bits  Numeric  Alphanumeric  Byte  Kanji\n",
+            "1        L         21      21    152       41            25    17     10\n",
+        )))
+        .stdout(predicate::str::contains(
+            "40       L        177     177  23648     7089          4296  2953   1817\n",
+        ))
+        .stdout(predicate::str::contains(
+            "M4       Q         17      17     80       21            13     9      5\n",
+        ))
+        .stdout(predicate::str::ends_with(
+            "R17x139  H        139      17    608      178           108    74     46\n",
+        ));
+}
+
+#[test]
+fn capacity_with_error_correction_level() {
+    command::command()
+        .arg("capacity")
+        .arg("-l")
+        .arg("h")
+        .arg("--variant")
+        .arg("rmqr")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(concat!(
+            "Version  Level  Width  Height  Bits  Numeric  Alphanumeric  Byte  Kanji\n",
+            "R11x27   H         27      11    40        9             6     4      2\n",
+        )));
+    command::command()
+        .arg("capacity")
+        .arg("-l")
+        .arg("q")
+        .arg("--variant")
+        .arg("rmqr")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Version  Level  Width  Height  Bits  Numeric  Alphanumeric  Byte  Kanji\n",
+        ));
+}
+
+#[test]
+fn capacity_with_payload() {
+    command::command()
+        .arg("capacity")
+        .arg("--variant")
+        .arg("micro")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Version  Level  Width  Height  Bits  Numeric  Alphanumeric  Byte  Kanji  Remaining\n",
+            "M1       L         11      11    20        5             -     -      -          -\n",
+            "M2       L         13      13    40       10             6     -      -          -\n",
+            "M2       M         13      13    32        8             5     -      -          -\n",
+            "M3       L         15      15    84       23            14     9      6         23\n",
+            "M3       M         15      15    68       18            11     7      4          7\n",
+            "M4       L         17      17   128       35            21    15      9         64\n",
+            "M4       M         17      17   112       30            18    13      8         48\n",
+            "M4       Q         17      17    80       21            13     9      5         16\n",
+            "Smallest: M3-M\n"
+        )));
+    command::command()
+        .arg("capacity")
+        .arg("01234567")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Smallest: M2-M\n"));
+    command::command()
+        .arg("capacity")
+        .arg("--variant")
+        .arg("normal")
+        .arg("-l")
+        .arg("h")
+        .arg("--mode")
+        .arg("byte")
+        .arg("--length")
+        .arg("1274")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "40       H        177     177  10208     3057          1852  1273    784         -4\nSmallest: none\n",
+        ));
+}
+
+#[test]
+fn capacity_with_non_ascii_payload() {
+    command::command()
+        .arg("capacity")
+        .arg("-l")
+        .arg("l")
+        .arg("--variant")
+        .arg("normal")
+        .arg("abcdefghijklmnoé")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "Smallest: 2-L
+",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("-l")
+        .arg("l")
+        .arg("--verbose")
+        .arg("-t")
+        .arg("ascii")
+        .arg("abcdefghijklmnoé")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Version: 2\nLevel: L\n"));
+    command::command()
+        .arg("capacity")
+        .arg("-l")
+        .arg("l")
+        .arg("--variant")
+        .arg("normal")
+        .arg("--mode")
+        .arg("byte")
+        .arg("abcdefghijklmnoé")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Smallest: 1-L\n"));
+}
+
+#[test]
+fn capacity_with_payload_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    std::fs::write(&input, "https://example.com/").unwrap();
+    command::command()
+        .arg("capacity")
+        .arg("-r")
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Smallest: R9x59-M\n"));
+    command::command()
+        .arg("capacity")
+        .arg("-r")
+        .arg("non_existent.txt")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "could not read data from non_existent.txt",
+        ));
+}
+
+#[test]
+fn capacity_as_json() {
+    command::command()
+        .arg("capacity")
+        .arg("-t")
+        .arg("json")
+        .arg("--variant")
+        .arg("micro")
+        .arg("-l")
+        .arg("l")
+        .arg("--mode")
+        .arg("numeric")
+        .arg("--length")
+        .arg("6")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            r#"{"symbols":["#,
+            r#"{"variant":"micro","version":"M1","level":"L","width":11,"height":11,"bits":20,"numeric":5,"alphanumeric":null,"byte":null,"kanji":null,"remaining":-3},"#,
+            r#"{"variant":"micro","version":"M2","level":"L","width":13,"height":13,"bits":40,"numeric":10,"alphanumeric":6,"byte":null,"kanji":null,"remaining":15},"#,
+            r#"{"variant":"micro","version":"M3","level":"L","width":15,"height":15,"bits":84,"numeric":23,"alphanumeric":14,"byte":9,"kanji":6,"remaining":57},"#,
+            r#"{"variant":"micro","version":"M4","level":"L","width":17,"height":17,"bits":128,"numeric":35,"alphanumeric":21,"byte":15,"kanji":9,"remaining":99}"#,
+            r#"],"smallest":{"variant":"micro","version":"M2","level":"L","width":13,"height":13,"bits":40,"numeric":10,"alphanumeric":6,"byte":null,"kanji":null,"remaining":15}}"#,
+            "\n"
+        )));
+    command::command()
+        .arg("capacity")
+        .arg("-t")
+        .arg("json")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("-l")
+        .arg("h")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"{"symbols":[{"variant":"rmqr","version":"R11x27","level":"H","width":27,"height":11,"bits":40,"numeric":9,"alphanumeric":6,"byte":4,"kanji":2},"#,
+        ))
+        .stdout(predicate::str::ends_with("}]}\n"));
+}
+
+#[test]
+fn capacity_with_invalid_options() {
+    command::command()
+        .arg("capacity")
+        .arg("--length")
+        .arg("10")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+    command::command()
+        .arg("capacity")
+        .arg("--mode")
+        .arg("byte")
+        .arg("--length")
+        .arg("10")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--length <NUMBER>' cannot be used with '[STRING]'",
+        ));
+    command::command()
+        .arg("capacity")
+        .arg("-t")
+        .arg("a")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'a' for '--type <FORMAT>'",
+        ));
+}
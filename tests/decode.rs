@@ -4,7 +4,9 @@
 
 mod utils;
 
-use predicates::prelude::predicate;
+use std::{fs, path::Path};
+
+use predicates::{boolean::PredicateBooleanExt, prelude::predicate};
 
 use crate::utils::command;
 
@@ -954,7 +956,10 @@ fn decode_with_verbose() {
         .assert()
         .success()
         .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 1\nLevel: M\n"));
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\nModule size: 8 px\nRotation: 0 degrees\n"
+        )));
 }
 
 #[test]
@@ -966,7 +971,881 @@ fn decode_with_metadata() {
         .assert()
         .success()
         .stdout(predicate::eq(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 1\nLevel: M\n"));
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\nModule size: 8 px\nRotation: 0 degrees\n"
+        )));
+}
+
+#[test]
+fn decode_with_output_encoding() {
+    command::command()
+        .arg("decode")
+        .arg("data/mode/kanji.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"\x93\xfa\x96\x7b\x8e\x59\x8b\xc6\x8b\x4b\x8a\x69" as &[u8],
+        ));
+    command::command()
+        .arg("decode")
+        .arg("--output-encoding")
+        .arg("raw")
+        .arg("data/mode/kanji.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"\x93\xfa\x96\x7b\x8e\x59\x8b\xc6\x8b\x4b\x8a\x69" as &[u8],
+        ));
+    command::command()
+        .arg("decode")
+        .arg("--output-encoding")
+        .arg("utf-8")
+        .arg("data/mode/kanji.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("日本産業規格"));
+}
+
+#[test]
+fn decode_with_eci() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("-r")
+        .arg("data/mode/kanji.txt")
+        .arg("--eci")
+        .arg("shift_jis")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg("--verbose")
+        .arg("--output-encoding")
+        .arg("utf-8")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("日本産業規格"))
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nECI: 20 (Shift_JIS)\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\nModule size: 8 px\nRotation: 0 degrees\n"
+        )));
+
+    let input = dir.path().join("input.txt");
+    fs::write(&input, b"caf\xe9").unwrap();
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("-r")
+        .arg(&input)
+        .arg("--eci")
+        .arg("iso-8859-1")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nECI: 3 (ISO-8859-1)\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\nModule size: 8 px\nRotation: 0 degrees\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"caf\xe9" as &[u8]));
+    command::command()
+        .arg("decode")
+        .arg("--output-encoding")
+        .arg("utf-8")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("café"));
+}
+
+#[test]
+fn decode_with_gs1() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--gs1")
+        .arg("(01)09501101530003(10)ABC123(17)250101")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 2\nLevel: M\nFNC1: first position (GS1)\n",
+            "Corners: (32, 32), (232, 32), (232, 232), (32, 232)\nModule size: 8 px\nRotation: 0 degrees\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("(01)09501101530003(10)ABC123(17)250101"));
+}
+
+fn encode_with_structured_append(dir: &Path) {
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(dir.join("output.png"))
+        .arg("-v")
+        .arg("1")
+        .arg("-l")
+        .arg("h")
+        .arg("--structured-append")
+        .arg("Hello, world! QR code")
+        .assert()
+        .success();
+}
+
+#[test]
+fn decode_with_structured_append() {
+    let dir = tempfile::tempdir().unwrap();
+    encode_with_structured_append(dir.path());
+    command::command()
+        .arg("decode")
+        .args([5, 3, 1, 2, 4].map(|i| dir.path().join(format!("output-{i:02}.png"))))
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world! QR code"));
+}
+
+#[test]
+fn decode_with_structured_append_and_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    encode_with_structured_append(dir.path());
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .args(
+            (1..=5)
+                .rev()
+                .map(|i| dir.path().join(format!("output-{i:02}.png"))),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: H\nSequence: 1/5\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: H\nSequence: 2/5\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: H\nSequence: 3/5\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: H\nSequence: 4/5\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: H\nSequence: 5/5\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+}
+
+#[test]
+fn decode_with_missing_structured_append_symbols() {
+    let dir = tempfile::tempdir().unwrap();
+    encode_with_structured_append(dir.path());
+    command::command()
+        .arg("decode")
+        .args([1, 3, 5].map(|i| dir.path().join(format!("output-{i:02}.png"))))
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "Structured Append sequence of 5 symbols is missing symbol(s) 2, 4",
+        ));
+}
+
+#[test]
+fn decode_with_duplicated_structured_append_symbol() {
+    let dir = tempfile::tempdir().unwrap();
+    encode_with_structured_append(dir.path());
+    command::command()
+        .arg("decode")
+        .args([1, 2, 3, 3, 4, 5].map(|i| dir.path().join(format!("output-{i:02}.png"))))
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "symbol 3 of Structured Append sequence of 5 symbols was found more than once",
+        ));
+}
+
+#[test]
+fn decode_multiple_images() {
+    command::command()
+        .arg("decode")
+        .arg("data/basic/basic.png")
+        .arg("data/basic/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeQR code"));
+}
+
+#[test]
+fn decode_with_parse() {
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/parse/otp.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Format: otpauth URI\n",
+            "Type: TOTP\n",
+            "Issuer: Example Co\n",
+            "Account: alice@example.com\n",
+            "Secret: JBSWY3DPEHPK3PXP\n",
+            "Algorithm: SHA1\n",
+            "Digits: 6\n",
+            "Period: 30\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/parse/emv.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Format: EMV QR code\n",
+            "00 Payload Format Indicator: 01\n",
+            "26 Merchant Account Information:\n",
+            "  00 Globally Unique Identifier: br.gov.bcb.pix\n",
+            "  01: 123e4567-e12b-12d1-a456-426655440000\n",
+            "52 Merchant Category Code: 0000\n",
+            "53 Transaction Currency: 986\n",
+            "58 Country Code: BR\n",
+            "59 Merchant Name: Fulano de Tal\n",
+            "60 Merchant City: BRASILIA\n",
+            "62 Additional Data Field Template:\n",
+            "  05 Reference Label: ***\n",
+            "63 CRC: 1D3D\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/parse/wifi.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Format: Wi-Fi\n",
+            "SSID: My Network\n",
+            "Security: WPA\n",
+            "Password: pa;ss\n",
+            "Hidden: false\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/basic/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Format: Text\nText: QR code\n"));
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/basic/basic.png")
+        .arg("data/parse/wifi.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Format: Text\n",
+            "Text: QR code\n",
+            "\n",
+            "Format: Wi-Fi\n",
+            "SSID: My Network\n",
+            "Security: WPA\n",
+            "Password: pa;ss\n",
+            "Hidden: false\n"
+        )));
+}
+
+#[test]
+fn decode_with_parse_as_json() {
+    command::command()
+        .arg("decode")
+        .arg("--parse=json")
+        .arg("data/parse/wifi.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            r#"{"format":"wifi","fields":["#,
+            r#"{"name":"SSID","value":"My Network"},"#,
+            r#"{"name":"Security","value":"WPA"},"#,
+            r#"{"name":"Password","value":"pa;ss"},"#,
+            r#"{"name":"Hidden","value":"false"}]}"#,
+            "\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--parse=JSON")
+        .arg("data/basic/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            r#"{"format":"text","fields":[{"name":"Text","value":"QR code"}]}"#.to_owned() + "\n",
+        ));
+}
+
+#[test]
+fn decode_with_parse_from_invalid_payload() {
+    command::command()
+        .arg("decode")
+        .arg("--parse")
+        .arg("data/parse/invalid_wifi.png")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not parse the decoded data"))
+        .stderr(predicate::str::contains(
+            "invalid Wi-Fi network payload: missing SSID",
+        ));
+}
+
+#[test]
+fn decode_with_parse_with_invalid_format() {
+    command::command()
+        .arg("decode")
+        .arg("--parse=xml")
+        .arg("data/parse/wifi.png")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'xml' for '--parse[=<FORMAT>]'",
+        ));
+}
+
+#[test]
+fn decode_with_format() {
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg("data/basic/basic.png")
+        .arg("data/invert/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            r#"{"source":"data/basic/basic.png","inverted":false,"#,
+            r#""payload":{"text":"QR code"},"#,
+            r#""metadata":{"symbol_version":"1","error_correction_level":"M"},"#,
+            r#""mask":3,"eci":[],"corners":[[32,32],[200,32],[200,200],[32,200]],"#,
+            r#""module_size":8.0,"rotation":0.0,"mirrored":false}"#,
+            "\n",
+            r#"{"source":"data/invert/basic.png","inverted":true,"#,
+            r#""payload":{"text":"QR code"},"#,
+            r#""metadata":{"symbol_version":"1","error_correction_level":"M"},"#,
+            r#""mask":3,"eci":[],"corners":[[32,32],[200,32],[200,200],[32,200]],"#,
+            r#""module_size":8.0,"rotation":0.0,"mirrored":false}"#,
+            "\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("JSON")
+        .write_stdin(include_bytes!("data/basic/basic.png"))
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            r#"[{"source":null,"inverted":false,"payload":{"text":"QR code"},"#,
+            r#""metadata":{"symbol_version":"1","error_correction_level":"M"},"#,
+            r#""mask":3,"eci":[],"corners":[[32,32],[200,32],[200,200],[32,200]],"#,
+            r#""module_size":8.0,"rotation":0.0,"mirrored":false}]"#,
+            "\n"
+        )));
+}
+
+#[test]
+fn decode_with_format_from_binary_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&path)
+        .write_stdin(b"\x82\xa0\xff".as_slice())
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""payload":{"base64":"gqD/"}"#));
+}
+
+#[test]
+fn decode_with_format_and_structured_append() {
+    let dir = tempfile::tempdir().unwrap();
+    encode_with_structured_append(dir.path());
+    let output = command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .args([5, 3, 1, 2, 4].map(|i| dir.path().join(format!("output-{i:02}.png"))))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 5);
+    for (i, line) in lines.into_iter().enumerate() {
+        assert!(line.contains(&format!(
+            r#""sequence":{{"position":{},"count":5,"payload":{{"text":"Hello, world! QR code"}}}}"#,
+            i + 1
+        )));
+        assert!(line.contains(&format!("output-{:02}.png", i + 1)));
+    }
+}
+
+#[test]
+fn decode_with_format_and_non_ascii_structured_append() {
+    let dir = tempfile::tempdir().unwrap();
+    let text = format!("a{}", "日本語テキスト".repeat(200));
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(dir.path().join("output.png"))
+        .arg("-l")
+        .arg("h")
+        .arg("--structured-append")
+        .arg(&text)
+        .assert()
+        .success();
+    let output = command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("json")
+        .args((1..=4).map(|i| dir.path().join(format!("output-{i:02}.png"))))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("base64"));
+    for i in 1..=4 {
+        assert!(stdout.contains(&format!(
+            r#""sequence":{{"position":{i},"count":4,"payload":{{"text":"{text}"}}}}"#
+        )));
+    }
+    assert!(!dir.path().join("output-05.png").exists());
+}
+
+#[test]
+fn decode_with_geometry() {
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .arg("data/geometry/rotated.png")
+        .arg("data/geometry/mirrored.png")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\n",
+            "Corners: (199, 32), (199, 200), (31, 200), (31, 32)\n",
+            "Module size: 8 px\nRotation: 90 degrees\n",
+            "Version: 1\nLevel: M\n",
+            "Corners: (199, 32), (31, 32), (31, 200), (199, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\nMirrored: yes\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg("data/geometry/mirrored.png")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(concat!(
+            r#""corners":[[199,32],[31,32],[31,200],[199,200]],"#,
+            r#""module_size":8.0,"rotation":0.0,"mirrored":true}"#
+        )));
+}
+
+#[test]
+fn decode_with_annotate() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("decode")
+        .arg("--annotate")
+        .arg(&output)
+        .arg("data/basic/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR code"));
+    let image = image::open(&output).unwrap().into_rgba8();
+    let input = image::load_from_memory(include_bytes!("data/basic/basic.png"))
+        .unwrap()
+        .into_rgba8();
+    assert_eq!(image.dimensions(), input.dimensions());
+    assert_eq!(image[(116, 32)], image::Rgba([0xff, 0x00, 0x00, 0xff]));
+    assert_eq!(image[(116, 116)], input[(116, 116)]);
+
+    command::command()
+        .arg("decode")
+        .arg("--annotate")
+        .arg(&output)
+        .arg("data/basic/basic.png")
+        .arg("data/geometry/rotated.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeQR code"));
+    for i in 1..=2 {
+        assert!(dir.path().join(format!("output-{i:02}.png")).exists());
+    }
+}
+
+#[cfg(feature = "decode-from-gif")]
+#[test]
+fn decode_from_animated_gif() {
+    command::command()
+        .arg("decode")
+        .arg("data/animation/animated.gif")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeAnimated"));
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .write_stdin(include_bytes!("data/animation/animated.gif"))
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nFrame: 1\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: M\nFrame: 3\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+}
+
+#[test]
+fn decode_from_animated_png() {
+    command::command()
+        .arg("decode")
+        .arg("data/animation/animated.png")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeAnimated"));
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .write_stdin(include_bytes!("data/animation/animated.png"))
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nFrame: 1\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: M\nFrame: 3\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+}
+
+#[cfg(feature = "decode-from-webp")]
+#[test]
+fn decode_from_animated_webp() {
+    command::command()
+        .arg("decode")
+        .arg("data/animation/animated.webp")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeAnimated"));
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .write_stdin(include_bytes!("data/animation/animated.webp"))
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nFrame: 1\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: M\nFrame: 3\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+}
+
+#[cfg(feature = "decode-from-tiff")]
+#[test]
+fn decode_from_multipage_tiff() {
+    command::command()
+        .arg("decode")
+        .arg("data/multipage/multipage.tiff")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 1Page 3"));
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .write_stdin(include_bytes!("data/multipage/multipage.tiff"))
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nPage: 0\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: M\nPage: 2\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg("data/multipage/multipage.tiff")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""page":0,"payload":{"text":"Page 1"}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""page":2,"payload":{"text":"Page 3"}"#,
+        ));
+}
+
+#[cfg(feature = "decode-from-tiff")]
+#[test]
+fn decode_from_multipage_tiff_with_annotate() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("decode")
+        .arg("--annotate")
+        .arg(&output)
+        .arg("data/multipage/multipage.tiff")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 1Page 3"));
+    assert!(!output.exists());
+    let pages = (1..=3)
+        .map(|i| {
+            image::open(dir.path().join(format!("output-{i:02}.png")))
+                .unwrap()
+                .into_rgba8()
+        })
+        .collect::<Vec<_>>();
+    let red = image::Rgba([0xff, 0x00, 0x00, 0xff]);
+    assert_eq!(pages[0][(116, 32)], red);
+    assert_ne!(pages[1][(116, 32)], red);
+    assert_eq!(pages[2][(116, 32)], red);
+    assert!(!dir.path().join("output-04.png").exists());
+}
+
+#[cfg(feature = "decode-from-ico")]
+#[test]
+fn decode_from_ico_with_multiple_entries() {
+    command::command()
+        .arg("decode")
+        .arg("data/multipage/multiple.ico")
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeIcon"));
+    command::command()
+        .arg("decode")
+        .arg("--metadata")
+        .arg("data/multipage/multiple.ico")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nEntry: 0\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n",
+            "Version: 1\nLevel: M\nEntry: 2\n",
+            "Corners: (32, 32), (200, 32), (200, 200), (32, 200)\n",
+            "Module size: 8 px\nRotation: 0 degrees\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("-t")
+        .arg("ico")
+        .write_stdin(include_bytes!("data/multipage/multiple.cur"))
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR codeIcon"));
+}
+
+#[cfg(feature = "decode-from-pdf")]
+#[test]
+fn decode_from_pdf() {
+    command::command()
+        .arg("decode")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 1Page 3"));
+    command::command()
+        .arg("decode")
+        .arg("-t")
+        .arg("pdf")
+        .write_stdin(include_bytes!("data/pdf/document.pdf"))
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 1Page 3"));
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .write_stdin(include_bytes!("data/pdf/document.pdf"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""page":0,"payload":{"text":"Page 1"}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""page":2,"payload":{"text":"Page 3"}"#,
+        ));
+}
+
+#[cfg(feature = "decode-from-pdf")]
+#[test]
+fn decode_from_pdf_with_dpi() {
+    command::command()
+        .arg("decode")
+        .arg("--dpi")
+        .arg("300")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 1Page 3"));
+    command::command()
+        .arg("decode")
+        .arg("--dpi")
+        .arg("0")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '0' for '--dpi <DPI>'",
+        ));
+    command::command()
+        .arg("decode")
+        .arg("--dpi")
+        .arg("1201")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '1201' for '--dpi <DPI>'",
+        ));
+}
+
+#[cfg(feature = "decode-from-pdf")]
+#[test]
+fn decode_from_pdf_with_extract_images() {
+    command::command()
+        .arg("decode")
+        .arg("--extract-images")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Page 3"));
+    command::command()
+        .arg("decode")
+        .arg("--extract-images")
+        .arg("data/pdf/blank.pdf")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("the PDF has no raster images"));
+    command::command()
+        .arg("decode")
+        .arg("--extract-images")
+        .arg("--dpi")
+        .arg("300")
+        .arg("data/pdf/document.pdf")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--extract-images' cannot be used with '--dpi <DPI>'",
+        ));
+}
+
+#[cfg(feature = "decode-from-pdf")]
+#[test]
+fn decode_from_pdf_with_wrong_format() {
+    command::command()
+        .arg("decode")
+        .arg("-t")
+        .arg("pdf")
+        .arg("data/decode/decode.png")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("could not read the image"));
+}
+
+#[test]
+fn decode_from_animated_image_with_format() {
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg("data/animation/animated.png")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""frame":1,"payload":{"text":"QR code"}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""frame":3,"payload":{"text":"Animated"}"#,
+        ));
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("jsonl")
+        .arg("data/basic/basic.png")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""frame""#).not());
+}
+
+#[test]
+fn decode_with_invalid_format() {
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("yaml")
+        .arg("data/basic/basic.png")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'yaml' for '--format <FORMAT>'",
+        ));
 }
 
 #[test]
@@ -978,4 +1857,22 @@ fn validate_the_options_dependencies_for_decode_command() {
         .assert()
         .failure()
         .code(2);
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("json")
+        .arg("--parse")
+        .arg("data/basic/basic.png")
+        .assert()
+        .failure()
+        .code(2);
+    command::command()
+        .arg("decode")
+        .arg("--format")
+        .arg("json")
+        .arg("--metadata")
+        .arg("data/basic/basic.png")
+        .assert()
+        .failure()
+        .code(2);
 }
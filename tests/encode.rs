@@ -508,7 +508,7 @@ fn encode_with_invalid_symbol_version() {
         .failure()
         .code(2)
         .stderr(predicate::str::contains(
-            "unexpected argument 'QR code' found",
+            "the subcommand 'QR code' cannot be used with",
         ));
 }
 
@@ -903,6 +903,7 @@ fn encode_to_invalid_output_format() {
 }
 
 #[cfg(feature = "optimize-output-png")]
+#[allow(clippy::too_many_lines)]
 #[test]
 fn encode_to_optimized_png() {
     let default_output = command::command()
@@ -1328,7 +1329,9 @@ fn encode_in_numeric_mode_max() {
         .failure()
         .code(65)
         .stderr(predicate::str::contains("could not construct a QR code"))
-        .stderr(predicate::str::contains("data too long"));
+        .stderr(predicate::str::contains(
+            "the data is at least 4 bits (1 byte) over the capacity of version 40 at level L",
+        ));
 }
 
 #[test]
@@ -1380,7 +1383,9 @@ fn encode_in_alphanumeric_mode_max() {
         .failure()
         .code(65)
         .stderr(predicate::str::contains("could not construct a QR code"))
-        .stderr(predicate::str::contains("data too long"));
+        .stderr(predicate::str::contains(
+            "the data is 3 bits (1 byte) over the capacity of version 40 at level L",
+        ));
 }
 
 #[test]
@@ -1433,7 +1438,9 @@ fn encode_in_byte_mode_max() {
         .failure()
         .code(65)
         .stderr(predicate::str::contains("could not construct a QR code"))
-        .stderr(predicate::str::contains("data too long"));
+        .stderr(predicate::str::contains(
+            "the data is 4 bits (1 byte) over the capacity of version 40 at level L",
+        ));
 }
 
 #[test]
@@ -1486,7 +1493,9 @@ fn encode_in_kanji_mode_max() {
         .failure()
         .code(65)
         .stderr(predicate::str::contains("could not construct a QR code"))
-        .stderr(predicate::str::contains("data too long"));
+        .stderr(predicate::str::contains(
+            "the data is 2 bits (1 byte) over the capacity of version 40 at level L",
+        ));
 }
 
 #[test]
@@ -1742,6 +1751,107 @@ fn encode_as_rmqr_code_with_invalid_symbol_version() {
         .stderr(predicate::str::contains("invalid version"));
 }
 
+#[test]
+fn encode_with_auto_variant() {
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--verbose")
+        .arg("01234567")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Variant: micro\nVersion: 2\nLevel: M\nMask: 1\n",
+            "Mask 0: SUM1 4, SUM2 4, score 68\n",
+            "Mask 1: SUM1 8, SUM2 6, score 104 (applied)\n",
+            "Mask 2: SUM1 8, SUM2 4, score 72\n",
+            "Mask 3: SUM1 4, SUM2 6, score 70\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--prefer")
+        .arg("height")
+        .arg("--verbose")
+        .arg("01234567")
+        .assert()
+        .success()
+        .stderr(predicate::eq("Variant: rmqr\nVersion: R7x43\nLevel: M\n"));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--prefer")
+        .arg("width")
+        .arg("--verbose")
+        .arg("https://example.com/")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Variant: normal\nVersion: 2\nLevel: M\nMask: 1\n",
+            "Mask 0: N1 263, N2 207, N3 1000, N4 0, penalty 1470\n",
+            "Mask 1: N1 218, N2 189, N3 880, N4 0, penalty 1287 (applied)\n",
+            "Mask 2: N1 229, N2 156, N3 880, N4 0, penalty 1265\n",
+            "Mask 3: N1 253, N2 156, N3 880, N4 0, penalty 1289\n",
+            "Mask 4: N1 242, N2 162, N3 840, N4 0, penalty 1244\n",
+            "Mask 5: N1 237, N2 231, N3 840, N4 0, penalty 1308\n",
+            "Mask 6: N1 248, N2 225, N3 760, N4 10, penalty 1243\n",
+            "Mask 7: N1 267, N2 243, N3 1040, N4 0, penalty 1550\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--eci")
+        .arg("utf-8")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stderr(predicate::eq(
+            "Variant: rmqr\nVersion: R13x27\nLevel: M\nECI: 26 (UTF-8)\n",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--verbose")
+        .arg("é")
+        .assert()
+        .success()
+        .stderr(predicate::eq(
+            "Variant: rmqr\nVersion: R11x27\nLevel: M\nECI: 26 (UTF-8)\n",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("auto")
+        .arg("--eci")
+        .arg("none")
+        .arg("--verbose")
+        .arg("é")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with(
+            "Variant: micro\nVersion: 3\nLevel: M\n",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("-v")
+        .arg("1")
+        .arg("--variant")
+        .arg("auto")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "symbol version cannot be specified when the type of QR code is auto",
+        ));
+}
+
 #[test]
 fn encode_with_invalid_variant() {
     command::command()
@@ -1758,920 +1868,900 @@ fn encode_with_invalid_variant() {
 }
 
 #[test]
-fn encode_from_named_fg_color() {
-    let output = command::command()
+fn encode_with_version_range() {
+    command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("brown")
+        .arg("--min-version")
+        .arg("5")
+        .arg("--verbose")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout)
-            .map(DynamicImage::into_rgb8)
-            .map(DynamicImage::from)
-            .unwrap(),
-        image::open("tests/data/colored/fg.png").unwrap()
-    );
-    assert!(output.status.success());
-}
-
-#[test]
-fn encode_from_named_bg_color() {
-    let output = command::command()
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Version: 5\nLevel: M\n"));
+    command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--min-version")
+        .arg("5")
+        .arg("--max-version")
+        .arg("7")
+        .arg("--verbose")
+        .arg("a".repeat(100))
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Version: 6\nLevel: M\n"));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--min-version")
+        .arg("3")
+        .arg("--verbose")
+        .arg("QR")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Version: 3\nLevel: M\n"));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--min-version")
+        .arg("11")
+        .arg("59")
+        .arg("--max-version")
+        .arg("13")
+        .arg("--verbose")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout)
-            .map(DynamicImage::into_rgb8)
-            .map(DynamicImage::from)
-            .unwrap(),
-        image::open("tests/data/colored/bg.png").unwrap()
-    );
-    assert!(output.status.success());
+        .assert()
+        .success()
+        .stderr(predicate::eq("Version: R11x59\nLevel: M\n"));
+    command::command()
+        .arg("encode")
+        .arg("--gs1")
+        .arg("--min-version")
+        .arg("3")
+        .arg("--verbose")
+        .arg("(01)09501101530003")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with(
+            "Version: 3\nLevel: M\nFNC1: first position (GS1)\n",
+        ));
 }
 
 #[test]
-fn encode_from_named_color() {
-    let output = command::command()
+fn encode_with_invalid_version_range() {
+    command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--max-version")
+        .arg("4")
+        .arg("-l")
+        .arg("m")
+        .arg("a".repeat(100))
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "could not construct a QR code within the version range",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--min-version")
+        .arg("5")
+        .arg("--max-version")
+        .arg("3")
+        .arg("-l")
+        .arg("m")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout)
-            .map(DynamicImage::into_rgb8)
-            .map(DynamicImage::from)
-            .unwrap(),
-        image::open("tests/data/colored/rgb.png").unwrap()
-    );
-    assert!(output.status.success());
-}
-
-#[test]
-fn encode_to_svg_from_named_color() {
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not set the version range"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("svg")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--max-version")
+        .arg("5")
+        .arg("--variant")
+        .arg("micro")
         .arg("QR code")
         .assert()
-        .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
-}
-
-#[test]
-fn encode_to_eps_from_named_color() {
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not set the version range"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("eps")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--min-version")
+        .arg("7")
+        .arg("30")
         .arg("QR code")
         .assert()
-        .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
-}
-
-#[cfg(feature = "output-as-ansi")]
-#[test]
-fn encode_to_ansi_from_named_color() {
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not set the version range"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--min-version")
+        .arg("5")
+        .arg("--variant")
+        .arg("auto")
         .arg("QR code")
         .assert()
-        .success()
-        .stdout(predicate::eq(if cfg!(windows) {
-            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
-        } else {
-            include_str!("data/colored/rgb_ansi_vga.txt")
-        }));
-}
-
-#[cfg(feature = "output-as-ansi")]
-#[test]
-fn encode_to_ansi_256_from_named_color() {
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "version range cannot be specified when the type of QR code is auto",
+        ));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi256")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("-v")
+        .arg("5")
+        .arg("--min-version")
+        .arg("5")
+        .arg("-l")
+        .arg("m")
         .arg("QR code")
         .assert()
-        .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--symbol-version <NUMBER>...' cannot be used with '--min-version <NUMBER>...'",
+        ));
 }
 
-#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_to_ansi_true_color_from_named_color() {
+fn encode_with_boost_ecc() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi-true-color")
-        .arg("--foreground")
-        .arg("brown")
-        .arg("--background")
-        .arg("lightslategray")
+        .arg("--boost-ecc")
+        .arg("--verbose")
+        .arg("-o")
+        .arg(&output)
         .arg("QR code")
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!(
-            "data/colored/rgb_ansi_true_color.txt"
-        )));
-}
-
-#[test]
-fn encode_from_hex_fg_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("#a52a2a")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/fg.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("a52a2a")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/fg.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-}
-
-#[test]
-fn encode_from_hex_bg_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--background")
-            .arg("#778899")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/bg.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--background")
-            .arg("778899")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/bg.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-}
-
-#[test]
-fn encode_from_hex_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("#a52a2a")
-            .arg("--background")
-            .arg("#778899")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("a52a2a")
-            .arg("--background")
-            .arg("778899")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-}
-
-#[test]
-fn encode_to_svg_from_hex_color() {
+        .stderr(predicate::str::starts_with("Version: 1\nLevel: H\n"));
     command::command()
-        .arg("encode")
-        .arg("-t")
-        .arg("svg")
-        .arg("--foreground")
-        .arg("#a52a2a")
-        .arg("--background")
-        .arg("#778899")
-        .arg("QR code")
+        .arg("decode")
+        .arg("--metadata")
+        .arg(&output)
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
+        .stderr(predicate::str::starts_with("Version: 1\nLevel: H\n"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("svg")
-        .arg("--foreground")
-        .arg("a52a2a")
-        .arg("--background")
-        .arg("778899")
-        .arg("QR code")
+        .arg("-l")
+        .arg("l")
+        .arg("--boost-ecc")
+        .arg("--verbose")
+        .arg("a".repeat(20))
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
-}
-
-#[test]
-fn encode_to_eps_from_hex_color() {
+        .stderr(predicate::str::starts_with("Version: 2\nLevel: Q\n"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("eps")
-        .arg("--foreground")
-        .arg("#a52a2a")
-        .arg("--background")
-        .arg("#778899")
-        .arg("QR code")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--boost-ecc")
+        .arg("--verbose")
+        .arg("0".repeat(20))
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
+        .stderr(predicate::str::starts_with("Version: 4\nLevel: Q\n"));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("eps")
-        .arg("--foreground")
-        .arg("a52a2a")
-        .arg("--background")
-        .arg("778899")
-        .arg("QR code")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--boost-ecc")
+        .arg("--verbose")
+        .arg("QR")
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
+        .stderr(predicate::eq("Version: R11x27\nLevel: H\n"));
 }
 
-#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_to_ansi_from_hex_color() {
+fn encode_with_data_too_long() {
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi")
-        .arg("--foreground")
-        .arg("#a52a2a")
-        .arg("--background")
-        .arg("#778899")
-        .arg("QR code")
+        .arg("-v")
+        .arg("1")
+        .arg("-l")
+        .arg("m")
+        .arg("a".repeat(30))
         .assert()
-        .success()
-        .stdout(predicate::eq(if cfg!(windows) {
-            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
-        } else {
-            include_str!("data/colored/rgb_ansi_vga.txt")
-        }));
+        .failure()
+        .code(65)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(concat!(
+            "the data is 124 bits (16 bytes) over the capacity of version 1 at level M\n",
+            "    hint: use rMQR code R15x43 with `--variant rmqr -v 15 43`\n",
+        )));
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi")
-        .arg("--foreground")
-        .arg("a52a2a")
-        .arg("--background")
-        .arg("778899")
-        .arg("QR code")
+        .arg("-v")
+        .arg("1")
+        .arg("-l")
+        .arg("l")
+        .arg("hello world hello world")
         .assert()
-        .success()
-        .stdout(predicate::eq(if cfg!(windows) {
-            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
-        } else {
-            include_str!("data/colored/rgb_ansi_vga.txt")
-        }));
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(concat!(
+            "the data is 44 bits (6 bytes) over the capacity of version 1 at level L\n",
+            "    hint: use QR code version 2 with `--variant normal -v 2`\n",
+            "    hint: convert the data to uppercase so that it can be encoded in alphanumeric mode\n",
+            "    hint: split the data into 2 symbols with `--structured-append`\n",
+        )));
+    command::command()
+        .arg("encode")
+        .arg("-v")
+        .arg("40")
+        .arg("-l")
+        .arg("h")
+        .arg("--mode")
+        .arg("byte")
+        .arg("a".repeat(1500))
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(concat!(
+            "the data is 1812 bits (227 bytes) over the capacity of version 40 at level H\n",
+            "    hint: lower the error correction level to Q with `-l q`\n",
+            "    hint: split the data into 2 symbols with `--structured-append`\n",
+        )));
 }
 
-#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_to_ansi_256_from_hex_color() {
+fn encode_with_mask() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi256")
-        .arg("--foreground")
-        .arg("#a52a2a")
-        .arg("--background")
-        .arg("#778899")
+        .arg("--mask")
+        .arg("6")
+        .arg("--verbose")
+        .arg("-o")
+        .arg(&output)
         .arg("QR code")
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nMask: 6\n",
+            "Mask 0: N1 170, N2 111, N3 880, N4 0, penalty 1161\n",
+            "Mask 1: N1 185, N2 129, N3 880, N4 0, penalty 1194\n",
+            "Mask 2: N1 214, N2 108, N3 760, N4 0, penalty 1082\n",
+            "Mask 3: N1 177, N2 99, N3 840, N4 0, penalty 1116\n",
+            "Mask 4: N1 173, N2 123, N3 840, N4 0, penalty 1136\n",
+            "Mask 5: N1 193, N2 141, N3 840, N4 0, penalty 1174\n",
+            "Mask 6: N1 191, N2 123, N3 800, N4 0, penalty 1114 (applied)\n",
+            "Mask 7: N1 199, N2 153, N3 880, N4 0, penalty 1232\n"
+        )));
     command::command()
-        .arg("encode")
-        .arg("-t")
-        .arg("ansi256")
-        .arg("--foreground")
-        .arg("a52a2a")
-        .arg("--background")
-        .arg("778899")
-        .arg("QR code")
+        .arg("decode")
+        .arg(&output)
         .assert()
         .success()
-        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
-}
-
-#[cfg(feature = "output-as-ansi")]
-#[test]
-fn encode_to_ansi_true_color_from_hex_color() {
+        .stdout(predicate::eq("QR code"));
+    let default = dir.path().join("default.png");
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi-true-color")
-        .arg("--foreground")
-        .arg("#a52a2a")
-        .arg("--background")
-        .arg("#778899")
+        .arg("-o")
+        .arg(&default)
         .arg("QR code")
         .assert()
-        .success()
-        .stdout(predicate::eq(include_str!(
-            "data/colored/rgb_ansi_true_color.txt"
-        )));
+        .success();
     command::command()
         .arg("encode")
-        .arg("-t")
-        .arg("ansi-true-color")
-        .arg("--foreground")
-        .arg("a52a2a")
-        .arg("--background")
-        .arg("778899")
+        .arg("--mask")
+        .arg("3")
+        .arg("-o")
+        .arg(&output)
         .arg("QR code")
         .assert()
+        .success();
+    assert_eq!(
+        image::open(&output).unwrap().into_luma8(),
+        image::open(&default).unwrap().into_luma8()
+    );
+
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--mask")
+        .arg("2")
+        .arg("--verbose")
+        .arg("QR")
+        .assert()
         .success()
-        .stdout(predicate::eq(include_str!(
-            "data/colored/rgb_ansi_true_color.txt"
+        .stderr(predicate::eq(concat!(
+            "Version: 2\nLevel: M\nMask: 2\n",
+            "Mask 0: SUM1 5, SUM2 6, score 86\n",
+            "Mask 1: SUM1 7, SUM2 4, score 71\n",
+            "Mask 2: SUM1 7, SUM2 6, score 103 (applied)\n",
+            "Mask 3: SUM1 5, SUM2 4, score 69\n"
         )));
 }
 
 #[test]
-fn encode_from_hex_color_with_alpha() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("#a52a2a7f")
-            .arg("--background")
-            .arg("#7788997f")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("a52a2a7f")
-            .arg("--background")
-            .arg("7788997f")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+fn encode_with_invalid_mask() {
+    command::command()
+        .arg("encode")
+        .arg("--mask")
+        .arg("8")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '8' for '--mask <NUMBER>'",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--mask")
+        .arg("4")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "mask pattern must be between 0 and 3 for Micro QR code",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--mask")
+        .arg("0")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "mask pattern can only be specified for normal QR code and Micro QR code",
+        ));
 }
 
 #[test]
-fn encode_from_short_hex_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("#111")
-            .arg("--background")
-            .arg("#eee")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb_short.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("111")
-            .arg("--background")
-            .arg("eee")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb_short.png").unwrap()
-        );
-        assert!(output.status.success());
+fn encode_with_structured_append() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("-l")
+        .arg("l")
+        .arg("--structured-append")
+        .arg("--verbose")
+        .arg("0123456789".repeat(800))
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 30\nLevel: L\nMask: 4\n",
+            "Mask 0: N1 5360, N2 6351, N3 1680, N4 0, penalty 13391\n",
+            "Mask 1: N1 4906, N2 6723, N3 1880, N4 0, penalty 13509\n",
+            "Mask 2: N1 5928, N2 7512, N3 4000, N4 0, penalty 17440\n",
+            "Mask 3: N1 4614, N2 6363, N3 2160, N4 0, penalty 13137\n",
+            "Mask 4: N1 4362, N2 6774, N3 1960, N4 0, penalty 13096 (applied)\n",
+            "Mask 5: N1 5029, N2 7053, N3 2400, N4 0, penalty 14482\n",
+            "Mask 6: N1 4933, N2 6969, N3 2440, N4 0, penalty 14342\n",
+            "Mask 7: N1 5003, N2 6630, N3 2120, N4 0, penalty 13753\n",
+            "Version: 30\nLevel: L\nMask: 4\n",
+            "Mask 0: N1 5373, N2 6309, N3 1720, N4 0, penalty 13402\n",
+            "Mask 1: N1 4909, N2 6702, N3 1840, N4 0, penalty 13451\n",
+            "Mask 2: N1 5959, N2 7539, N3 3880, N4 0, penalty 17378\n",
+            "Mask 3: N1 4643, N2 6387, N3 2200, N4 0, penalty 13230\n",
+            "Mask 4: N1 4398, N2 6825, N3 2000, N4 0, penalty 13223 (applied)\n",
+            "Mask 5: N1 5037, N2 7065, N3 2360, N4 0, penalty 14462\n",
+            "Mask 6: N1 4947, N2 6945, N3 2320, N4 0, penalty 14212\n",
+            "Mask 7: N1 5007, N2 6666, N3 2080, N4 0, penalty 13753\n"
+        )));
+    assert!(!output.exists());
+    for file in ["output-01.png", "output-02.png"] {
+        assert!(image::open(dir.path().join(file)).is_ok());
     }
+    assert!(!dir.path().join("output-03.png").exists());
 }
 
 #[test]
-fn encode_from_short_hex_color_with_alpha() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("#1118")
-            .arg("--background")
-            .arg("#eee8")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba_short.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("1118")
-            .arg("--background")
-            .arg("eee8")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba_short.png").unwrap()
-        );
-        assert!(output.status.success());
+fn encode_with_structured_append_and_symbol_version() {
+    let dir = tempfile::tempdir().unwrap();
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(dir.path().join("output.svg"))
+        .arg("-t")
+        .arg("svg")
+        .arg("-v")
+        .arg("10")
+        .arg("--structured-append")
+        .arg("0123456789".repeat(800))
+        .assert()
+        .success();
+    for i in 1..=16 {
+        assert!(dir.path().join(format!("output-{i:02}.svg")).exists());
     }
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(dir.path().join("output.png"))
+        .arg("-v")
+        .arg("1")
+        .arg("--structured-append")
+        .arg("0123456789".repeat(800))
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not construct a QR code"))
+        .stderr(predicate::str::contains("data too long"));
 }
 
 #[test]
-fn encode_from_invalid_hex_fg_color() {
+fn encode_with_structured_append_if_data_fits_in_single_symbol() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
     command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("#g")
+        .arg("-o")
+        .arg(&output)
+        .arg("--structured-append")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value '#g' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid hex format"));
+        .success();
+    assert_eq!(
+        image::open(&output)
+            .map(DynamicImage::into_luma8)
+            .map(DynamicImage::from)
+            .unwrap(),
+        image::open("tests/data/basic/basic.png").unwrap()
+    );
+    assert!(!dir.path().join("output-01.png").exists());
 }
 
 #[test]
-fn encode_from_invalid_hex_bg_color() {
+fn encode_with_structured_append_without_output() {
     command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("#g")
+        .arg("--structured-append")
         .arg("QR code")
         .assert()
         .failure()
         .code(2)
         .stderr(predicate::str::contains(
-            "invalid value '#g' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid hex format"));
+            "the following required arguments were not provided",
+        ));
 }
 
 #[test]
-fn encode_from_rgb_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgb(165 42 42)")
-            .arg("--background")
-            .arg("rgb(119 136 153)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgb(165, 42, 42)")
-            .arg("--background")
-            .arg("rgb(119, 136, 153)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/rgb.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+fn encode_with_structured_append_as_micro_qr_code() {
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg("output.png")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--structured-append")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Structured Append is only available for normal QR code",
+        ));
 }
 
 #[test]
-fn encode_from_rgb_color_with_alpha() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgb(165 42 42 / 49.8%)")
-            .arg("--background")
-            .arg("rgb(119 136 153 / 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgb(165, 42, 42, 49.8%)")
-            .arg("--background")
-            .arg("rgb(119, 136, 153, 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+fn encode_with_eci() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--eci")
+        .arg("utf-8")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nECI: 26 (UTF-8)\nMask: 1\n",
+            "Mask 0: N1 177, N2 141, N3 800, N4 0, penalty 1118\n",
+            "Mask 1: N1 172, N2 117, N3 880, N4 0, penalty 1169 (applied)\n",
+            "Mask 2: N1 217, N2 117, N3 760, N4 0, penalty 1094\n",
+            "Mask 3: N1 199, N2 144, N3 800, N4 0, penalty 1143\n",
+            "Mask 4: N1 177, N2 129, N3 880, N4 0, penalty 1186\n",
+            "Mask 5: N1 194, N2 165, N3 840, N4 0, penalty 1199\n",
+            "Mask 6: N1 191, N2 150, N3 840, N4 0, penalty 1181\n",
+            "Mask 7: N1 183, N2 144, N3 920, N4 0, penalty 1247\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR code"));
+    command::command()
+        .arg("encode")
+        .arg("--eci")
+        .arg("ISO-8859-7")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nECI: 9 (ISO-8859-7)\nMask: 4\n",
+            "Mask 0: N1 200, N2 129, N3 880, N4 0, penalty 1209\n",
+            "Mask 1: N1 169, N2 105, N3 880, N4 0, penalty 1154\n",
+            "Mask 2: N1 215, N2 150, N3 720, N4 0, penalty 1085\n",
+            "Mask 3: N1 199, N2 123, N3 1000, N4 0, penalty 1322\n",
+            "Mask 4: N1 168, N2 102, N3 840, N4 0, penalty 1110 (applied)\n",
+            "Mask 5: N1 187, N2 165, N3 880, N4 0, penalty 1232\n",
+            "Mask 6: N1 176, N2 144, N3 920, N4 0, penalty 1240\n",
+            "Mask 7: N1 189, N2 144, N3 1000, N4 0, penalty 1333\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("--eci")
+        .arg("170")
+        .arg("--verbose")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("ECI: 170\n"));
 }
 
 #[test]
-fn encode_from_rgba_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgba(165 42 42 / 49.8%)")
-            .arg("--background")
-            .arg("rgba(119 136 153 / 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("rgba(165, 42, 42, 49.8%)")
-            .arg("--background")
-            .arg("rgba(119, 136, 153, 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/rgba.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+fn encode_with_automatic_utf8_eci() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--verbose")
+        .arg("QRコード")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nECI: 26 (UTF-8)\nMask: 7\n",
+            "Mask 0: N1 165, N2 111, N3 880, N4 0, penalty 1156\n",
+            "Mask 1: N1 170, N2 141, N3 920, N4 0, penalty 1231\n",
+            "Mask 2: N1 187, N2 147, N3 760, N4 0, penalty 1094\n",
+            "Mask 3: N1 195, N2 108, N3 800, N4 0, penalty 1103\n",
+            "Mask 4: N1 185, N2 162, N3 840, N4 0, penalty 1187\n",
+            "Mask 5: N1 175, N2 150, N3 880, N4 0, penalty 1205\n",
+            "Mask 6: N1 185, N2 138, N3 840, N4 0, penalty 1163\n",
+            "Mask 7: N1 168, N2 108, N3 800, N4 0, penalty 1076 (applied)\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("QRコード"));
+    command::command()
+        .arg("encode")
+        .arg("--eci")
+        .arg("none")
+        .arg("--verbose")
+        .arg("QRコード")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nMask: 2\n",
+            "Mask 0: N1 178, N2 126, N3 880, N4 0, penalty 1184\n",
+            "Mask 1: N1 189, N2 159, N3 800, N4 0, penalty 1148\n",
+            "Mask 2: N1 174, N2 96, N3 720, N4 0, penalty 990 (applied)\n",
+            "Mask 3: N1 198, N2 132, N3 840, N4 0, penalty 1170\n",
+            "Mask 4: N1 195, N2 156, N3 760, N4 0, penalty 1111\n",
+            "Mask 5: N1 187, N2 147, N3 760, N4 0, penalty 1094\n",
+            "Mask 6: N1 198, N2 138, N3 800, N4 0, penalty 1136\n",
+            "Mask 7: N1 194, N2 150, N3 1080, N4 0, penalty 1424\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--verbose")
+        .arg("QRコード")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 4\nLevel: M\nMask: 0\n",
+            "Mask 0: SUM1 8, SUM2 11, score 139 (applied)\n",
+            "Mask 1: SUM1 6, SUM2 9, score 105\n",
+            "Mask 2: SUM1 6, SUM2 5, score 86\n",
+            "Mask 3: SUM1 4, SUM2 9, score 73\n"
+        )));
 }
 
 #[test]
-fn encode_from_invalid_rgb_fg_color() {
+fn encode_with_eci_as_micro_qr_code() {
     command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("rgb(0)")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--eci")
+        .arg("utf-8")
         .arg("QR code")
         .assert()
         .failure()
-        .code(2)
+        .code(1)
         .stderr(predicate::str::contains(
-            "invalid value 'rgb(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+            "ECI is not available for Micro QR code",
+        ));
+}
+
+#[test]
+fn encode_with_invalid_eci() {
     command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("rgba(0)")
+        .arg("--eci")
+        .arg("utf-32")
         .arg("QR code")
         .assert()
         .failure()
         .code(2)
         .stderr(predicate::str::contains(
-            "invalid value 'rgba(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+            "expected a character set name or a number between 0 and 999999",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--eci")
+        .arg("1000000")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2);
 }
 
 #[test]
-fn encode_from_invalid_rgb_bg_color() {
+fn encode_from_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
     command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("rgb(0)")
-        .arg("QR code")
+        .arg("-o")
+        .arg(&output)
+        .arg("--segments")
+        .arg("data/segments/segments.txt")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 3\nLevel: M\nECI: 26 (UTF-8)\nMask: 5\n",
+            "Mask 0: N1 248, N2 261, N3 960, N4 0, penalty 1469\n",
+            "Mask 1: N1 232, N2 240, N3 880, N4 0, penalty 1352\n",
+            "Mask 2: N1 306, N2 291, N3 880, N4 0, penalty 1477\n",
+            "Mask 3: N1 277, N2 258, N3 1000, N4 0, penalty 1535\n",
+            "Mask 4: N1 290, N2 303, N3 920, N4 0, penalty 1513\n",
+            "Mask 5: N1 290, N2 246, N3 800, N4 0, penalty 1336 (applied)\n",
+            "Mask 6: N1 302, N2 258, N3 800, N4 0, penalty 1360\n",
+            "Mask 7: N1 306, N2 267, N3 1120, N4 0, penalty 1693\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("QR コード ABC-1230123456789"));
+}
+
+#[test]
+fn encode_from_json_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("-v")
+        .arg("3")
+        .arg("-l")
+        .arg("h")
+        .arg("--segments")
+        .arg("data/segments/segments.json")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 3\nLevel: H\nECI: 20 (Shift_JIS)\nMask: 1\n",
+            "Mask 0: N1 314, N2 306, N3 1040, N4 10, penalty 1670\n",
+            "Mask 1: N1 261, N2 183, N3 920, N4 0, penalty 1364 (applied)\n",
+            "Mask 2: N1 324, N2 246, N3 800, N4 0, penalty 1370\n",
+            "Mask 3: N1 293, N2 270, N3 800, N4 0, penalty 1363\n",
+            "Mask 4: N1 294, N2 282, N3 960, N4 0, penalty 1536\n",
+            "Mask 5: N1 301, N2 282, N3 880, N4 0, penalty 1463\n",
+            "Mask 6: N1 293, N2 294, N3 880, N4 0, penalty 1467\n",
+            "Mask 7: N1 276, N2 258, N3 960, N4 0, penalty 1494\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg("--output-encoding")
+        .arg("utf-8")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("日本産業規格0123456789"));
+}
+
+#[test]
+fn encode_from_segments_from_stdin() {
+    command::command()
+        .arg("encode")
+        .arg("-t")
+        .arg("ascii")
+        .arg("--segments")
+        .arg("-")
+        .write_stdin("numeric 0123\nalphanumeric QR CODE\n")
+        .assert()
+        .success();
+}
+
+#[test]
+fn encode_from_invalid_segments() {
+    command::command()
+        .arg("encode")
+        .arg("--segments")
+        .arg("-")
+        .write_stdin("numeric 0123\nalphanumeric QR code\n")
         .assert()
         .failure()
-        .code(2)
+        .code(1)
         .stderr(predicate::str::contains(
-            "invalid value 'rgb(0)' for '--background <COLOR>'",
+            "could not parse the segment specification",
         ))
-        .stderr(predicate::str::contains("invalid color function"));
+        .stderr(predicate::str::contains("invalid segment at line 2"));
     command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("rgba(0)")
+        .arg("--segments")
+        .arg("non_existent.txt")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "could not read data from non_existent.txt",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--segments")
+        .arg("data/segments/segments.txt")
         .arg("QR code")
         .assert()
         .failure()
         .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'rgba(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_hsl_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsl(248 39% 39.2%)")
-            .arg("--background")
-            .arg("hsl(0 0% 66.3%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/hsl.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsl(248, 39%, 39.2%)")
-            .arg("--background")
-            .arg("hsl(0, 0%, 66.3%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout)
-                .map(DynamicImage::into_rgb8)
-                .map(DynamicImage::from)
-                .unwrap(),
-            image::open("tests/data/colored/hsl.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+        .stderr(predicate::str::contains("cannot be used with"));
 }
 
 #[test]
-fn encode_from_hsl_color_with_alpha() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsl(248 39% 39.2% / 49.8%)")
-            .arg("--background")
-            .arg("hsl(0 0% 66.3% / 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/hsla.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsl(248, 39%, 39.2%, 49.8%)")
-            .arg("--background")
-            .arg("hsl(0, 0%, 66.3%, 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/hsla.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-}
-
-#[test]
-fn encode_from_hsla_color() {
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsla(248 39% 39.2% / 49.8%)")
-            .arg("--background")
-            .arg("hsla(0 0% 66.3% / 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/hsla.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
-    {
-        let output = command::command()
-            .arg("encode")
-            .arg("--foreground")
-            .arg("hsla(248, 39%, 39.2%, 49.8%)")
-            .arg("--background")
-            .arg("hsla(0, 0%, 66.3%, 49.8%)")
-            .arg("QR code")
-            .output()
-            .unwrap();
-        assert_eq!(
-            image::load_from_memory(&output.stdout).unwrap(),
-            image::open("tests/data/colored/hsla.png").unwrap()
-        );
-        assert!(output.status.success());
-    }
+fn encode_with_gs1() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--gs1")
+        .arg("--verbose")
+        .arg("(01)09501101530003(17)250101(10)ABC")
+        .assert()
+        .success()
+        .stderr(predicate::eq(concat!(
+            "Version: 2\nLevel: M\nFNC1: first position (GS1)\nMask: 4\n",
+            "Mask 0: N1 205, N2 177, N3 840, N4 0, penalty 1222\n",
+            "Mask 1: N1 236, N2 168, N3 960, N4 0, penalty 1364\n",
+            "Mask 2: N1 266, N2 210, N3 840, N4 0, penalty 1316\n",
+            "Mask 3: N1 224, N2 153, N3 960, N4 0, penalty 1337\n",
+            "Mask 4: N1 238, N2 177, N3 840, N4 0, penalty 1255 (applied)\n",
+            "Mask 5: N1 273, N2 219, N3 840, N4 0, penalty 1332\n",
+            "Mask 6: N1 253, N2 216, N3 840, N4 10, penalty 1319\n",
+            "Mask 7: N1 219, N2 156, N3 920, N4 0, penalty 1295\n"
+        )));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("(01)09501101530003(17)250101(10)ABC"));
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--gs1")
+        .arg("(10)A%B(21)12345(30)8")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("(10)A%B(21)12345(30)8"));
+    command::command()
+        .arg("encode")
+        .arg("--gs1")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--verbose")
+        .arg("(01)09501101530003")
+        .assert()
+        .success()
+        .stderr(predicate::eq(
+            "Version: R13x27\nLevel: M\nFNC1: first position (GS1)\n",
+        ));
 }
 
 #[test]
-fn encode_from_invalid_hsl_fg_color() {
+fn encode_with_invalid_gs1() {
     command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("hsl(0)")
-        .arg("QR code")
+        .arg("--gs1")
+        .arg("(01)09501101530004")
         .assert()
         .failure()
-        .code(2)
+        .code(65)
         .stderr(predicate::str::contains(
-            "invalid value 'hsl(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+            "invalid data for GS1 application identifier (01): invalid check digit",
+        ));
     command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("hsla(0)")
-        .arg("QR code")
+        .arg("--gs1")
+        .arg("(05)123")
         .assert()
         .failure()
-        .code(2)
+        .code(65)
         .stderr(predicate::str::contains(
-            "invalid value 'hsla(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_invalid_hsl_bg_color() {
+            "unknown GS1 application identifier (05)",
+        ));
     command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("hsl(0)")
-        .arg("QR code")
+        .arg("--gs1")
+        .arg("0109501101530003")
         .assert()
         .failure()
-        .code(2)
+        .code(65)
         .stderr(predicate::str::contains(
-            "invalid value 'hsl(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+            "could not parse the GS1 element string",
+        ));
     command::command()
         .arg("encode")
-        .arg("--background")
-        .arg("hsla(0)")
-        .arg("QR code")
+        .arg("--gs1")
+        .arg("--variant")
+        .arg("micro")
+        .arg("(01)09501101530003")
         .assert()
         .failure()
-        .code(2)
+        .code(1)
         .stderr(predicate::str::contains(
-            "invalid value 'hsla(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+            "GS1 is not available for Micro QR code",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("--gs1")
+        .arg("--mode")
+        .arg("byte")
+        .arg("(01)09501101530003")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
 }
 
 #[test]
-fn encode_from_hwb_color() {
+fn encode_from_named_fg_color() {
     let output = command::command()
         .arg("encode")
         .arg("--foreground")
-        .arg("hwb(50.6 0% 0%)")
-        .arg("--background")
-        .arg("hwb(0 66.3% 33.7%)")
+        .arg("brown")
         .arg("QR code")
         .output()
         .unwrap();
@@ -2680,69 +2770,38 @@ fn encode_from_hwb_color() {
             .map(DynamicImage::into_rgb8)
             .map(DynamicImage::from)
             .unwrap(),
-        image::open("tests/data/colored/hwb.png").unwrap()
+        image::open("tests/data/colored/fg.png").unwrap()
     );
     assert!(output.status.success());
 }
 
 #[test]
-fn encode_from_hwb_color_with_alpha() {
+fn encode_from_named_bg_color() {
     let output = command::command()
         .arg("encode")
-        .arg("--foreground")
-        .arg("hwb(50.6 0% 0% / 49.8%)")
         .arg("--background")
-        .arg("hwb(0 66.3% 33.7% / 49.8%)")
+        .arg("lightslategray")
         .arg("QR code")
         .output()
         .unwrap();
     assert_eq!(
-        image::load_from_memory(&output.stdout).unwrap(),
-        image::open("tests/data/colored/hwb_with_alpha.png").unwrap()
+        image::load_from_memory(&output.stdout)
+            .map(DynamicImage::into_rgb8)
+            .map(DynamicImage::from)
+            .unwrap(),
+        image::open("tests/data/colored/bg.png").unwrap()
     );
     assert!(output.status.success());
 }
 
 #[test]
-fn encode_from_invalid_hwb_fg_color() {
-    command::command()
-        .arg("encode")
-        .arg("--foreground")
-        .arg("hwb(0)")
-        .arg("QR code")
-        .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'hwb(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_invalid_hwb_bg_color() {
-    command::command()
-        .arg("encode")
-        .arg("--background")
-        .arg("hwb(0)")
-        .arg("QR code")
-        .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'hwb(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_oklab_color() {
+fn encode_from_named_color() {
     let output = command::command()
         .arg("encode")
         .arg("--foreground")
-        .arg("oklab(50.4% -0.0906 0.0069)")
+        .arg("brown")
         .arg("--background")
-        .arg("oklab(61.9% -0.0120 -0.0302)")
+        .arg("lightslategray")
         .arg("QR code")
         .output()
         .unwrap();
@@ -2751,526 +2810,2503 @@ fn encode_from_oklab_color() {
             .map(DynamicImage::into_rgb8)
             .map(DynamicImage::from)
             .unwrap(),
-        image::open("tests/data/colored/oklab.png").unwrap()
+        image::open("tests/data/colored/rgb.png").unwrap()
     );
     assert!(output.status.success());
 }
 
 #[test]
-fn encode_from_oklab_color_with_alpha() {
-    let output = command::command()
+fn encode_to_svg_from_named_color() {
+    command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("svg")
         .arg("--foreground")
-        .arg("oklab(50.4% -0.0906 0.0069 / 0.5)")
+        .arg("brown")
         .arg("--background")
-        .arg("oklab(61.9% -0.0120 -0.0302 / 0.5)")
+        .arg("lightslategray")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout).unwrap(),
-        image::open("tests/data/colored/oklab_with_alpha.png").unwrap()
-    );
-    assert!(output.status.success());
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
 }
 
 #[test]
-fn encode_from_invalid_oklab_fg_color() {
+fn encode_to_eps_from_named_color() {
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("eps")
         .arg("--foreground")
-        .arg("oklab(0)")
+        .arg("brown")
+        .arg("--background")
+        .arg("lightslategray")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'oklab(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
 }
 
+#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_from_invalid_oklab_bg_color() {
+fn encode_to_ansi_from_named_color() {
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("ansi")
+        .arg("--foreground")
+        .arg("brown")
         .arg("--background")
-        .arg("oklab(0)")
+        .arg("lightslategray")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'oklab(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+        .success()
+        .stdout(predicate::eq(if cfg!(windows) {
+            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
+        } else {
+            include_str!("data/colored/rgb_ansi_vga.txt")
+        }));
 }
 
+#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_from_oklch_color() {
-    let output = command::command()
+fn encode_to_ansi_256_from_named_color() {
+    command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("ansi256")
         .arg("--foreground")
-        .arg("oklch(59.41% 0.16 301.29)")
+        .arg("brown")
         .arg("--background")
-        .arg("oklch(61.9% 0.032 248.35)")
+        .arg("lightslategray")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout)
-            .map(DynamicImage::into_rgb8)
-            .map(DynamicImage::from)
-            .unwrap(),
-        image::open("tests/data/colored/oklch.png").unwrap()
-    );
-    assert!(output.status.success());
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
 }
 
+#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_from_oklch_color_with_alpha() {
-    let output = command::command()
+fn encode_to_ansi_true_color_from_named_color() {
+    command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("ansi-true-color")
         .arg("--foreground")
-        .arg("oklch(59.41% 0.16 301.29 / 49.8%)")
+        .arg("brown")
         .arg("--background")
-        .arg("oklch(61.9% 0.032 248.35 / 49.8%)")
+        .arg("lightslategray")
         .arg("QR code")
-        .output()
-        .unwrap();
-    assert_eq!(
-        image::load_from_memory(&output.stdout).unwrap(),
-        image::open("tests/data/colored/oklch_with_alpha.png").unwrap()
-    );
-    assert!(output.status.success());
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!(
+            "data/colored/rgb_ansi_true_color.txt"
+        )));
 }
 
 #[test]
-fn encode_from_invalid_oklch_fg_color() {
+fn encode_from_hex_fg_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("#a52a2a")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/fg.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("a52a2a")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/fg.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_hex_bg_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--background")
+            .arg("#778899")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/bg.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--background")
+            .arg("778899")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/bg.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_hex_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("#a52a2a")
+            .arg("--background")
+            .arg("#778899")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("a52a2a")
+            .arg("--background")
+            .arg("778899")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_to_svg_from_hex_color() {
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("svg")
         .arg("--foreground")
-        .arg("oklch(0)")
+        .arg("#a52a2a")
+        .arg("--background")
+        .arg("#778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'oklch(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_invalid_oklch_bg_color() {
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("svg")
+        .arg("--foreground")
+        .arg("a52a2a")
         .arg("--background")
-        .arg("oklch(0)")
+        .arg("778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'oklch(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.svg")));
 }
 
 #[test]
-fn encode_from_invalid_fg_color_function() {
+fn encode_to_eps_from_hex_color() {
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("eps")
         .arg("--foreground")
-        .arg("fn(0)")
+        .arg("#a52a2a")
+        .arg("--background")
+        .arg("#778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'fn(0)' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
-}
-
-#[test]
-fn encode_from_invalid_bg_color_function() {
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("eps")
+        .arg("--foreground")
+        .arg("a52a2a")
         .arg("--background")
-        .arg("fn(0)")
+        .arg("778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'fn(0)' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid color function"));
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb.eps")));
 }
 
+#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_from_unknown_fg_color() {
+fn encode_to_ansi_from_hex_color() {
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("ansi")
         .arg("--foreground")
-        .arg("a")
+        .arg("#a52a2a")
+        .arg("--background")
+        .arg("#778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'a' for '--foreground <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid unknown format"));
-}
-
-#[test]
-fn encode_from_unknown_bg_color() {
+        .success()
+        .stdout(predicate::eq(if cfg!(windows) {
+            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
+        } else {
+            include_str!("data/colored/rgb_ansi_vga.txt")
+        }));
     command::command()
         .arg("encode")
+        .arg("-t")
+        .arg("ansi")
+        .arg("--foreground")
+        .arg("a52a2a")
         .arg("--background")
-        .arg("a")
+        .arg("778899")
         .arg("QR code")
         .assert()
-        .failure()
-        .code(2)
-        .stderr(predicate::str::contains(
-            "invalid value 'a' for '--background <COLOR>'",
-        ))
-        .stderr(predicate::str::contains("invalid unknown format"));
+        .success()
+        .stdout(predicate::eq(if cfg!(windows) {
+            include_str!("data/colored/rgb_ansi_windows_10_console.txt")
+        } else {
+            include_str!("data/colored/rgb_ansi_vga.txt")
+        }));
 }
 
+#[cfg(feature = "output-as-ansi")]
 #[test]
-fn encode_with_colors_to_pic() {
+fn encode_to_ansi_256_from_hex_color() {
+    command::command()
+        .arg("encode")
+        .arg("-t")
+        .arg("ansi256")
+        .arg("--foreground")
+        .arg("#a52a2a")
+        .arg("--background")
+        .arg("#778899")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
+    command::command()
+        .arg("encode")
+        .arg("-t")
+        .arg("ansi256")
+        .arg("--foreground")
+        .arg("a52a2a")
+        .arg("--background")
+        .arg("778899")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!("data/colored/rgb_ansi_256.txt")));
+}
+
+#[cfg(feature = "output-as-ansi")]
+#[test]
+fn encode_to_ansi_true_color_from_hex_color() {
+    command::command()
+        .arg("encode")
+        .arg("-t")
+        .arg("ansi-true-color")
+        .arg("--foreground")
+        .arg("#a52a2a")
+        .arg("--background")
+        .arg("#778899")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!(
+            "data/colored/rgb_ansi_true_color.txt"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("-t")
+        .arg("ansi-true-color")
+        .arg("--foreground")
+        .arg("a52a2a")
+        .arg("--background")
+        .arg("778899")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::eq(include_str!(
+            "data/colored/rgb_ansi_true_color.txt"
+        )));
+}
+
+#[test]
+fn encode_from_hex_color_with_alpha() {
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("pic")
             .arg("--foreground")
-            .arg("brown")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("pic")
+            .arg("#a52a2a7f")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("#7788997f")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
     }
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("pic")
             .arg("--foreground")
-            .arg("brown")
+            .arg("a52a2a7f")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("7788997f")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
     }
 }
 
 #[test]
-fn encode_with_colors_to_ascii() {
+fn encode_from_short_hex_color() {
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("ascii")
             .arg("--foreground")
-            .arg("brown")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("ascii")
+            .arg("#111")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("#eee")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb_short.png").unwrap()
+        );
+        assert!(output.status.success());
     }
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("ascii")
             .arg("--foreground")
-            .arg("brown")
+            .arg("111")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("eee")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb_short.png").unwrap()
+        );
+        assert!(output.status.success());
     }
 }
 
 #[test]
-fn encode_with_colors_to_ascii_invert() {
+fn encode_from_short_hex_color_with_alpha() {
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("ascii-invert")
             .arg("--foreground")
-            .arg("brown")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("ascii-invert")
+            .arg("#1118")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("#eee8")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba_short.png").unwrap()
+        );
+        assert!(output.status.success());
     }
     {
-        command::command()
+        let output = command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("ascii-invert")
             .arg("--foreground")
-            .arg("brown")
+            .arg("1118")
             .arg("--background")
-            .arg("lightslategray")
+            .arg("eee8")
             .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba_short.png").unwrap()
+        );
+        assert!(output.status.success());
     }
 }
 
 #[test]
-fn encode_with_colors_to_unicode() {
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("unicode")
-            .arg("--foreground")
-            .arg("brown")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("unicode")
-            .arg("--background")
-            .arg("lightslategray")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("unicode")
-            .arg("--foreground")
-            .arg("brown")
-            .arg("--background")
-            .arg("lightslategray")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
+fn encode_from_invalid_hex_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("#g")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '#g' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid hex format"));
+}
+
+#[test]
+fn encode_from_invalid_hex_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("#g")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '#g' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid hex format"));
+}
+
+#[test]
+fn encode_from_rgb_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgb(165 42 42)")
+            .arg("--background")
+            .arg("rgb(119 136 153)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgb(165, 42, 42)")
+            .arg("--background")
+            .arg("rgb(119, 136, 153)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/rgb.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_rgb_color_with_alpha() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgb(165 42 42 / 49.8%)")
+            .arg("--background")
+            .arg("rgb(119 136 153 / 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgb(165, 42, 42, 49.8%)")
+            .arg("--background")
+            .arg("rgb(119, 136, 153, 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_rgba_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgba(165 42 42 / 49.8%)")
+            .arg("--background")
+            .arg("rgba(119 136 153 / 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("rgba(165, 42, 42, 49.8%)")
+            .arg("--background")
+            .arg("rgba(119, 136, 153, 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/rgba.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_invalid_rgb_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("rgb(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'rgb(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("rgba(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'rgba(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_rgb_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("rgb(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'rgb(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("rgba(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'rgba(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_hsl_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsl(248 39% 39.2%)")
+            .arg("--background")
+            .arg("hsl(0 0% 66.3%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/hsl.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsl(248, 39%, 39.2%)")
+            .arg("--background")
+            .arg("hsl(0, 0%, 66.3%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout)
+                .map(DynamicImage::into_rgb8)
+                .map(DynamicImage::from)
+                .unwrap(),
+            image::open("tests/data/colored/hsl.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_hsl_color_with_alpha() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsl(248 39% 39.2% / 49.8%)")
+            .arg("--background")
+            .arg("hsl(0 0% 66.3% / 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/hsla.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsl(248, 39%, 39.2%, 49.8%)")
+            .arg("--background")
+            .arg("hsl(0, 0%, 66.3%, 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/hsla.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_hsla_color() {
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsla(248 39% 39.2% / 49.8%)")
+            .arg("--background")
+            .arg("hsla(0 0% 66.3% / 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/hsla.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+    {
+        let output = command::command()
+            .arg("encode")
+            .arg("--foreground")
+            .arg("hsla(248, 39%, 39.2%, 49.8%)")
+            .arg("--background")
+            .arg("hsla(0, 0%, 66.3%, 49.8%)")
+            .arg("QR code")
+            .output()
+            .unwrap();
+        assert_eq!(
+            image::load_from_memory(&output.stdout).unwrap(),
+            image::open("tests/data/colored/hsla.png").unwrap()
+        );
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn encode_from_invalid_hsl_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("hsl(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hsl(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("hsla(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hsla(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_hsl_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("hsl(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hsl(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("hsla(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hsla(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_hwb_color() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("hwb(50.6 0% 0%)")
+        .arg("--background")
+        .arg("hwb(0 66.3% 33.7%)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout)
+            .map(DynamicImage::into_rgb8)
+            .map(DynamicImage::from)
+            .unwrap(),
+        image::open("tests/data/colored/hwb.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_hwb_color_with_alpha() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("hwb(50.6 0% 0% / 49.8%)")
+        .arg("--background")
+        .arg("hwb(0 66.3% 33.7% / 49.8%)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout).unwrap(),
+        image::open("tests/data/colored/hwb_with_alpha.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_invalid_hwb_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("hwb(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hwb(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_hwb_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("hwb(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'hwb(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_oklab_color() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklab(50.4% -0.0906 0.0069)")
+        .arg("--background")
+        .arg("oklab(61.9% -0.0120 -0.0302)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout)
+            .map(DynamicImage::into_rgb8)
+            .map(DynamicImage::from)
+            .unwrap(),
+        image::open("tests/data/colored/oklab.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_oklab_color_with_alpha() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklab(50.4% -0.0906 0.0069 / 0.5)")
+        .arg("--background")
+        .arg("oklab(61.9% -0.0120 -0.0302 / 0.5)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout).unwrap(),
+        image::open("tests/data/colored/oklab_with_alpha.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_invalid_oklab_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklab(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'oklab(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_oklab_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("oklab(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'oklab(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_oklch_color() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklch(59.41% 0.16 301.29)")
+        .arg("--background")
+        .arg("oklch(61.9% 0.032 248.35)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout)
+            .map(DynamicImage::into_rgb8)
+            .map(DynamicImage::from)
+            .unwrap(),
+        image::open("tests/data/colored/oklch.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_oklch_color_with_alpha() {
+    let output = command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklch(59.41% 0.16 301.29 / 49.8%)")
+        .arg("--background")
+        .arg("oklch(61.9% 0.032 248.35 / 49.8%)")
+        .arg("QR code")
+        .output()
+        .unwrap();
+    assert_eq!(
+        image::load_from_memory(&output.stdout).unwrap(),
+        image::open("tests/data/colored/oklch_with_alpha.png").unwrap()
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn encode_from_invalid_oklch_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("oklch(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'oklch(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_oklch_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("oklch(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'oklch(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_fg_color_function() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("fn(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'fn(0)' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_invalid_bg_color_function() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("fn(0)")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'fn(0)' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid color function"));
+}
+
+#[test]
+fn encode_from_unknown_fg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--foreground")
+        .arg("a")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'a' for '--foreground <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid unknown format"));
+}
+
+#[test]
+fn encode_from_unknown_bg_color() {
+    command::command()
+        .arg("encode")
+        .arg("--background")
+        .arg("a")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'a' for '--background <COLOR>'",
+        ))
+        .stderr(predicate::str::contains("invalid unknown format"));
+}
+
+#[test]
+fn encode_with_colors_to_pic() {
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("pic")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("pic")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("pic")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+}
+
+#[test]
+fn encode_with_colors_to_ascii() {
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+}
+
+#[test]
+fn encode_with_colors_to_ascii_invert() {
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii-invert")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii-invert")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("ascii-invert")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+}
+
+#[test]
+fn encode_with_colors_to_unicode() {
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+}
+
+#[test]
+fn encode_with_colors_to_unicode_invert() {
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode-invert")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode-invert")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+    {
+        command::command()
+            .arg("encode")
+            .arg("-t")
+            .arg("unicode-invert")
+            .arg("--foreground")
+            .arg("brown")
+            .arg("--background")
+            .arg("lightslategray")
+            .arg("QR code")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "foreground and/or background colors cannot be changed",
+            ));
+    }
+}
+
+#[test]
+fn encode_with_verbose() {
+    command::command()
+        .arg("encode")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: M\nMask: 3\n",
+            "Mask 0: N1 170, N2 111, N3 880, N4 0, penalty 1161\n",
+            "Mask 1: N1 185, N2 129, N3 880, N4 0, penalty 1194\n",
+            "Mask 2: N1 214, N2 108, N3 760, N4 0, penalty 1082\n",
+            "Mask 3: N1 177, N2 99, N3 840, N4 0, penalty 1116 (applied)\n",
+            "Mask 4: N1 173, N2 123, N3 840, N4 0, penalty 1136\n",
+            "Mask 5: N1 193, N2 141, N3 840, N4 0, penalty 1174\n",
+            "Mask 6: N1 191, N2 123, N3 800, N4 0, penalty 1114\n",
+            "Mask 7: N1 199, N2 153, N3 880, N4 0, penalty 1232\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("-l")
+        .arg("h")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 1\nLevel: H\nMask: 4\n",
+            "Mask 0: N1 171, N2 144, N3 920, N4 0, penalty 1235\n",
+            "Mask 1: N1 194, N2 120, N3 840, N4 0, penalty 1154\n",
+            "Mask 2: N1 183, N2 147, N3 1000, N4 0, penalty 1330\n",
+            "Mask 3: N1 192, N2 153, N3 920, N4 0, penalty 1265\n",
+            "Mask 4: N1 183, N2 138, N3 760, N4 0, penalty 1081 (applied)\n",
+            "Mask 5: N1 193, N2 138, N3 920, N4 0, penalty 1251\n",
+            "Mask 6: N1 193, N2 147, N3 840, N4 0, penalty 1180\n",
+            "Mask 7: N1 187, N2 162, N3 920, N4 0, penalty 1269\n"
+        )));
+
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 3\nLevel: M\nMask: 0\n",
+            "Mask 0: SUM1 7, SUM2 9, score 121 (applied)\n",
+            "Mask 1: SUM1 7, SUM2 7, score 119\n",
+            "Mask 2: SUM1 5, SUM2 6, score 86\n",
+            "Mask 3: SUM1 4, SUM2 5, score 69\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("-l")
+        .arg("q")
+        .arg("--variant")
+        .arg("micro")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq(concat!(
+            "Version: 4\nLevel: Q\nMask: 2\n",
+            "Mask 0: SUM1 11, SUM2 8, score 139\n",
+            "Mask 1: SUM1 9, SUM2 6, score 105\n",
+            "Mask 2: SUM1 9, SUM2 10, score 154 (applied)\n",
+            "Mask 3: SUM1 9, SUM2 6, score 105\n"
+        )));
+
+    command::command()
+        .arg("encode")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq("Version: R13x27\nLevel: M\n"));
+    command::command()
+        .arg("encode")
+        .arg("-l")
+        .arg("h")
+        .arg("--variant")
+        .arg("rmqr")
+        .arg("--verbose")
+        .arg("QR code")
+        .assert()
+        .success()
+        .stdout(predicate::ne(&[] as &[u8]))
+        .stderr(predicate::eq("Version: R11x43\nLevel: H\n"));
+}
+
+#[test]
+fn validate_the_options_dependencies_for_encode_command() {
+    command::command()
+        .arg("encode")
+        .arg("-r")
+        .arg("data/encode/data.txt")
+        .arg("QR code")
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn encode_wifi() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("--ssid")
+        .arg("My;Network")
+        .arg("--password")
+        .arg("pass:word")
+        .arg("--hidden")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            r"WIFI:T:WPA;S:My\;Network;H:true;P:pass\:word;;",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("-t")
+        .arg("svg")
+        .arg("--ssid")
+        .arg("example")
+        .arg("--security")
+        .arg("wpa3")
+        .arg("--password")
+        .arg("password")
+        .arg("--foreground")
+        .arg("brown")
+        .arg("-o")
+        .arg(dir.path().join("output.svg"))
+        .assert()
+        .success();
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("--ssid")
+        .arg("example")
+        .arg("--security")
+        .arg("nopass")
+        .arg("-t")
+        .arg("ascii")
+        .arg("--margin")
+        .arg("0")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("##############"));
+}
+
+#[test]
+fn encode_wifi_with_invalid_password() {
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("--ssid")
+        .arg("example")
+        .arg("--password")
+        .arg("short")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "could not build the Wi-Fi network payload",
+        ))
+        .stderr(predicate::str::contains(
+            "password for WPA must be between 8 and 63 ASCII characters, or 64 hexadecimal digits",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("--ssid")
+        .arg("example")
+        .arg("--security")
+        .arg("wep")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("password is required for WEP"));
+    command::command()
+        .arg("encode")
+        .arg("wifi")
+        .arg("-r")
+        .arg("data/basic/basic.png")
+        .arg("--ssid")
+        .arg("example")
+        .arg("--security")
+        .arg("nopass")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("unexpected argument '-r' found"));
+}
+
+#[test]
+fn encode_command_name_as_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("wifi")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("wifi"));
+    command::command()
+        .arg("encode")
+        .arg("-o")
+        .arg(&output)
+        .arg("--")
+        .arg("w")
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("w"));
+}
+
+#[test]
+fn encode_contact() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("contact")
+        .arg("--name")
+        .arg("Shun Sakai")
+        .arg("--org")
+        .arg("Example, Inc.")
+        .arg("--phone")
+        .arg("+81312345678")
+        .arg("--email")
+        .arg("sorairolake@example.com")
+        .arg("--url")
+        .arg("https://example.com/")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "MECARD:N:Shun Sakai;ORG:Example, Inc.;TEL:+81312345678;",
+            r"EMAIL:sorairolake@example.com;URL:https\://example.com/;;"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("contact")
+        .arg("--name")
+        .arg("Shun Sakai")
+        .arg("--format")
+        .arg("vcard4")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Shun Sakai\r\nEND:VCARD\r\n",
+        ));
+}
+
+#[test]
+fn encode_contact_from_vcard() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.vcf");
+    std::fs::write(
+        &input,
+        concat!(
+            "BEGIN:VCARD\r\n",
+            "VERSION:3.0\r\n",
+            "N:Sakai;Shun;;;\r\n",
+            "TEL;TYPE=CELL:+81-90-1234-5678\r\n",
+            "PHOTO;ENCODING=b;TYPE=JPEG:AAAA\r\n",
+            "END:VCARD\r\n"
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("contact")
+        .arg("--format")
+        .arg("mecard")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq("MECARD:N:Shun Sakai;TEL:+81-90-1234-5678;;"));
+    command::command()
+        .arg("encode")
+        .arg("contact")
+        .arg("non_existent.vcf")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "could not read data from non_existent.vcf",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("contact")
+        .arg("--name")
+        .arg("Shun Sakai")
+        .arg(&input)
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn encode_event() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--summary")
+        .arg("Meeting; Q4, planning")
+        .arg("--start")
+        .arg("2026-10-17T09:00")
+        .arg("--end")
+        .arg("2026-10-17T10:30")
+        .arg("--timezone")
+        .arg("Europe/Zurich")
+        .arg("--description")
+        .arg("Agenda:\n1. Budget")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "BEGIN:VEVENT\r\n",
+            "SUMMARY:Meeting\\; Q4\\, planning\r\n",
+            "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+            "DTEND;TZID=Europe/Zurich:20261017T103000\r\n",
+            "DESCRIPTION:Agenda:\\n1. Budget\r\n",
+            "END:VEVENT\r\n"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--summary")
+        .arg("Holiday")
+        .arg("--start")
+        .arg("2026-12-24")
+        .arg("--end")
+        .arg("2026-12-26T00:00")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "start and end must be both dates or both date-times",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--summary")
+        .arg("Holiday")
+        .arg("--start")
+        .arg("2026-02-29")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "invalid date or date-time `2026-02-29`",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--summary")
+        .arg("Holiday")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn encode_event_from_ics() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.ics");
+    std::fs::write(
+        &input,
+        format!(
+            concat!(
+                "BEGIN:VCALENDAR\r\n",
+                "VERSION:2.0\r\n",
+                "BEGIN:VEVENT\r\n",
+                "UID:1@example.com\r\n",
+                "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+                "SUMMARY:Team meeting\r\n",
+                "LOCATION:Room 1\r\n",
+                "DESCRIPTION:{}\r\n",
+                "END:VEVENT\r\n",
+                "END:VCALENDAR\r\n"
+            ),
+            "x".repeat(200)
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "DESCRIPTION:{}\r\n {}\r\n",
+            "x".repeat(63),
+            "x".repeat(74)
+        )));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--max-version=8")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stderr(predicate::eq(
+            "Warning: the event was stripped to fit in the symbol\n",
+        ));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            concat!(
+                "BEGIN:VEVENT\r\n",
+                "SUMMARY:Team meeting\r\n",
+                "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+                "LOCATION:Room 1\r\n",
+                "DESCRIPTION:{}\r\n",
+                "END:VEVENT\r\n"
+            ),
+            "x".repeat(32)
+        )));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--max-version=4")
+        .arg(&input)
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "could not construct a QR code within the version range",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--summary")
+        .arg("Team meeting")
+        .arg(&input)
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn encode_event_from_ics_with_non_ascii() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.ics");
+    std::fs::write(
+        &input,
+        format!(
+            concat!(
+                "BEGIN:VCALENDAR\r\n",
+                "VERSION:2.0\r\n",
+                "BEGIN:VEVENT\r\n",
+                "UID:1@example.com\r\n",
+                "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+                "SUMMARY:Team meeting\r\n",
+                "LOCATION:Room 1\r\n",
+                "DESCRIPTION:{}\r\n",
+                "END:VEVENT\r\n",
+                "END:VCALENDAR\r\n"
+            ),
+            "é".repeat(200)
+        ),
+    )
+    .unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("event")
+        .arg("--max-version=8")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stderr(predicate::eq(
+            "Warning: the event was stripped to fit in the symbol\n",
+        ));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            concat!(
+                "BEGIN:VEVENT\r\n",
+                "SUMMARY:Team meeting\r\n",
+                "DTSTART;TZID=Europe/Zurich:20261017T090000\r\n",
+                "LOCATION:Room 1\r\n",
+                "DESCRIPTION:{}\r\n",
+                "END:VEVENT\r\n"
+            ),
+            "é".repeat(15)
+        )));
+}
+
+#[test]
+fn encode_epc() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89 3704 0044 0532 0130 00")
+        .arg("--bic")
+        .arg("COBADEFFXXX")
+        .arg("--amount")
+        .arg("12.30")
+        .arg("--remittance")
+        .arg("Invoice 123")
+        .arg("--verbose")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Version: 5\nLevel: M\n"));
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "BCD\n002\n1\nSCT\nCOBADEFFXXX\nExample GmbH\nDE89370400440532013000\n",
+            "EUR12.30\n\n\nInvoice 123"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("girocode")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013000")
+        .arg("-t")
+        .arg("svg")
+        .arg("-o")
+        .arg(dir.path().join("output.svg"))
+        .assert()
+        .success();
+}
+
+#[test]
+fn encode_epc_with_invalid_options() {
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013001")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "could not build the EPC QR code payload",
+        ))
+        .stderr(predicate::str::contains(
+            "invalid IBAN `DE89370400440532013001`",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013000")
+        .arg("--amount")
+        .arg("1,50")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("invalid amount `1,50`"));
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("-l")
+        .arg("h")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013000")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "EPC QR code requires the error correction level M",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013000")
+        .arg("--max-version")
+        .arg("14")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "EPC QR code requires the version 13 or lower",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("epc")
+        .arg("--name")
+        .arg("Example GmbH")
+        .arg("--iban")
+        .arg("DE89370400440532013000")
+        .arg("--variant")
+        .arg("micro")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "EPC QR code must be a single normal QR code",
+        ));
+}
+
+#[test]
+fn encode_emv() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("emv")
+        .arg("--field")
+        .arg("26.00=br.gov.bcb.pix")
+        .arg("--field")
+        .arg("26.01=123e4567-e12b-12d1-a456-426655440000")
+        .arg("--currency")
+        .arg("986")
+        .arg("--amount")
+        .arg("10.00")
+        .arg("--country")
+        .arg("BR")
+        .arg("--merchant-name")
+        .arg("Fulano de Tal")
+        .arg("--merchant-city")
+        .arg("BRASILIA")
+        .arg("--reference")
+        .arg("***")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "000201010211",
+            "26580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000",
+            "520400005303986540510.005802BR5913Fulano de Tal6008BRASILIA",
+            "62070503***63041EE9"
+        )));
 }
 
 #[test]
-fn encode_with_colors_to_unicode_invert() {
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("unicode-invert")
-            .arg("--foreground")
-            .arg("brown")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
-        command::command()
-            .arg("encode")
-            .arg("-t")
-            .arg("unicode-invert")
-            .arg("--background")
-            .arg("lightslategray")
-            .arg("QR code")
-            .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
-    {
+fn encode_emv_from_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.json");
+    std::fs::write(
+        &input,
+        r#"{
+            "26": { "00": "br.gov.bcb.pix", "01": "123e4567-e12b-12d1-a456-426655440000" },
+            "52": "0000",
+            "53": "986",
+            "58": "BR",
+            "59": "Fulano de Tal",
+            "60": "BRASILIA",
+            "62": { "05": "***" }
+        }"#,
+    )
+    .unwrap();
+    let output = dir.path().join("output.png");
+    command::command()
+        .arg("encode")
+        .arg("emv")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000",
+            "5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+        )));
+    command::command()
+        .arg("encode")
+        .arg("emv")
+        .arg("--currency")
+        .arg("986")
+        .arg(&input)
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn encode_emv_with_invalid_options() {
+    let encode_emv = |field: &str| {
         command::command()
             .arg("encode")
-            .arg("-t")
-            .arg("unicode-invert")
-            .arg("--foreground")
-            .arg("brown")
-            .arg("--background")
-            .arg("lightslategray")
-            .arg("QR code")
+            .arg("emv")
+            .arg("--field")
+            .arg(field)
+            .arg("--currency")
+            .arg("986")
+            .arg("--country")
+            .arg("BR")
+            .arg("--merchant-name")
+            .arg("Fulano de Tal")
+            .arg("--merchant-city")
+            .arg("BRASILIA")
             .assert()
-            .failure()
-            .code(1)
-            .stderr(predicate::str::contains(
-                "foreground and/or background colors cannot be changed",
-            ));
-    }
+    };
+    encode_emv("26=br.gov.bcb.pix")
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("field 26 must be a template"));
+    encode_emv("26.00")
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "invalid field `26.00`: expected ID=VALUE",
+        ));
+    encode_emv("63=0000")
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("field 63 is set automatically"));
+    encode_emv("59=Fulano")
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "field 59 is specified more than once",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("emv")
+        .arg("--currency")
+        .arg("986")
+        .arg("--country")
+        .arg("BR")
+        .arg("--merchant-name")
+        .arg("Fulano de Tal")
+        .arg("--merchant-city")
+        .arg("BRASILIA")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "payload must have merchant account information (02 to 51)",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("emv")
+        .arg("--field")
+        .arg("26.00=br.gov.bcb.pix")
+        .arg("--currency")
+        .arg("986")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
 }
 
 #[test]
-fn encode_with_verbose() {
+fn encode_qr_bill() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
     command::command()
         .arg("encode")
+        .arg("qr-bill")
+        .arg("--account")
+        .arg("CH44 3199 9123 0008 8901 2")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
+        .arg("--amount")
+        .arg("1949.75")
+        .arg("--reference")
+        .arg("21 00000 00003 13947 14300 09017")
         .arg("--verbose")
-        .arg("QR code")
+        .arg("-o")
+        .arg(&output)
         .assert()
         .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 1\nLevel: M\n"));
+        .stderr(predicate::str::starts_with("Version: 7\nLevel: M\n"));
+    let png = std::fs::read(&output).unwrap();
+    assert_eq!(&png[37..41], b"pHYs");
+    // 45 modules * 8 pixels / 46 mm.
+    assert_eq!(&png[41..45], 7826_u32.to_be_bytes());
     command::command()
-        .arg("encode")
-        .arg("-l")
-        .arg("h")
-        .arg("--verbose")
-        .arg("QR code")
+        .arg("decode")
+        .arg(&output)
         .assert()
         .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 1\nLevel: H\n"));
-
+        .stdout(predicate::eq(concat!(
+            "SPC\n0200\n1\nCH4431999123000889012\nS\nRobert Schneider AG\n\n\n2501\nBiel\nCH\n",
+            "\n\n\n\n\n\n\n1949.75\nCHF\n\n\n\n\n\n\n\nQRR\n210000000003139471430009017\n\nEPD"
+        )));
     command::command()
         .arg("encode")
-        .arg("--variant")
-        .arg("micro")
-        .arg("--verbose")
-        .arg("QR code")
+        .arg("qr-bill")
+        .arg("-t")
+        .arg("svg")
+        .arg("--account")
+        .arg("CH5800791123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
         .assert()
         .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 3\nLevel: M\n"));
+        .stdout(predicate::str::contains(r#"height="54.976mm""#))
+        .stdout(predicate::str::contains(
+            r##"<path fill="#000000" d="M174.609 174.609h42.783v42.783h-42.783z"/>"##,
+        ));
     command::command()
         .arg("encode")
-        .arg("-l")
-        .arg("q")
-        .arg("--variant")
-        .arg("micro")
-        .arg("--verbose")
-        .arg("QR code")
+        .arg("qr-bill")
+        .arg("-t")
+        .arg("eps")
+        .arg("--account")
+        .arg("CH5800791123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
         .assert()
         .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: 4\nLevel: Q\n"));
+        .stdout(predicate::str::contains("%%HiResBoundingBox: 0 0 "))
+        .stdout(predicate::str::contains(" scale\n"));
+}
 
+#[test]
+fn encode_qr_bill_with_invalid_options() {
     command::command()
         .arg("encode")
-        .arg("--variant")
-        .arg("rmqr")
-        .arg("--verbose")
-        .arg("QR code")
+        .arg("qr-bill")
+        .arg("--account")
+        .arg("CH4431999123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
         .assert()
-        .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: R13x27\nLevel: M\n"));
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "could not build the Swiss QR-bill payload",
+        ))
+        .stderr(predicate::str::contains(
+            "QR reference is required for a QR-IBAN",
+        ));
     command::command()
         .arg("encode")
+        .arg("qr-bill")
+        .arg("--account")
+        .arg("CH4431999123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
+        .arg("--reference")
+        .arg("210000000003139471430009016")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "invalid QR reference `210000000003139471430009016`",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("qr-bill")
         .arg("-l")
-        .arg("h")
-        .arg("--variant")
-        .arg("rmqr")
-        .arg("--verbose")
-        .arg("QR code")
+        .arg("l")
+        .arg("--account")
+        .arg("CH5800791123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Swiss QR-bill requires the error correction level M",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("qr-bill")
+        .arg("-t")
+        .arg("ascii")
+        .arg("--account")
+        .arg("CH5800791123000889012")
+        .arg("--creditor-name")
+        .arg("Robert Schneider AG")
+        .arg("--creditor-postal-code")
+        .arg("2501")
+        .arg("--creditor-town")
+        .arg("Biel")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Swiss QR-bill can only be output as PNG, SVG, or EPS",
+        ));
+}
+
+#[test]
+fn encode_otp() {
+    command::command()
+        .arg("encode")
+        .arg("otp")
+        .arg("-t")
+        .arg("svg")
+        .arg("--issuer")
+        .arg("Example Co")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--secret")
+        .arg("jbsw y3dp ehpk 3pxp")
+        .arg("--algorithm")
+        .arg("sha256")
+        .arg("--digits")
+        .arg("8")
+        .arg("--period")
+        .arg("60")
         .assert()
         .success()
-        .stdout(predicate::ne(&[] as &[u8]))
-        .stderr(predicate::eq("Version: R11x43\nLevel: H\n"));
+        .stdout(predicate::str::starts_with("<?xml"));
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.png");
+    let assert = command::command()
+        .arg("encode")
+        .arg("otp")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--generate-secret")
+        .arg("--counter")
+        .arg("1")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match("^Secret: [A-Z2-7]{32}\n$").unwrap());
+    let secret = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    let secret = secret.trim_end().trim_start_matches("Secret: ");
+    command::command()
+        .arg("decode")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            "otpauth://hotp/alice%40example.com?secret={secret}&counter=1"
+        )));
 }
 
 #[test]
-fn validate_the_options_dependencies_for_encode_command() {
+fn encode_otp_with_invalid_options() {
     command::command()
         .arg("encode")
-        .arg("-r")
-        .arg("data/encode/data.txt")
-        .arg("QR code")
+        .arg("otp")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--secret")
+        .arg("JBSWY3DPEHPK3PX1")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("could not build the otpauth URI"))
+        .stderr(predicate::str::contains(
+            "secret must be a non-empty Base32 string",
+        ));
+    command::command()
+        .arg("encode")
+        .arg("otp")
+        .arg("--issuer")
+        .arg("Example:Co")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--secret")
+        .arg("JBSWY3DPEHPK3PXP")
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("issuer must not contain a colon"));
+    command::command()
+        .arg("encode")
+        .arg("otp")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--secret")
+        .arg("JBSWY3DPEHPK3PXP")
+        .arg("--generate-secret")
+        .assert()
+        .failure()
+        .code(2);
+    command::command()
+        .arg("encode")
+        .arg("otp")
+        .arg("--account")
+        .arg("alice@example.com")
+        .arg("--secret")
+        .arg("JBSWY3DPEHPK3PXP")
+        .arg("--digits")
+        .arg("9")
         .assert()
         .failure()
         .code(2);